@@ -1,24 +1,21 @@
 use anyhow::Result;
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
-};
 use superctrl::computer_use::ComputerUseAgent;
+use superctrl::task::CancellationToken;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let api_key = std::env::var("ANTHROPIC_API_KEY").expect("ANTHROPIC_API_KEY not set");
 
-    let stop_flag = Arc::new(AtomicBool::new(false));
+    let cancel = CancellationToken::new();
 
-    let stop_flag_clone = stop_flag.clone();
+    let cancel_clone = cancel.clone();
     tokio::spawn(async move {
         tokio::signal::ctrl_c().await.ok();
         println!("\nReceived Ctrl+C, stopping...");
-        stop_flag_clone.store(true, Ordering::Relaxed);
+        cancel_clone.cancel();
     });
 
-    let mut agent = ComputerUseAgent::new(api_key, stop_flag)?
+    let mut agent = ComputerUseAgent::new(api_key, cancel)?
         .with_display_size(1920, 1080)
         .with_full_trust_mode(true);
 
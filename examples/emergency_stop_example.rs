@@ -1,16 +1,14 @@
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
-};
 use std::time::Duration;
+use superctrl::task::{ControlSource, TaskRunner};
 
 fn main() {
-    let stop_flag = Arc::new(AtomicBool::new(false));
+    let tasks = TaskRunner::new();
+    let task = tasks.begin();
 
-    let stop_flag_worker = Arc::clone(&stop_flag);
+    let token = task.token();
     let worker = std::thread::spawn(move || {
         for i in 0..100 {
-            if stop_flag_worker.load(Ordering::Acquire) {
+            if token.is_cancelled() {
                 println!("🛑 Worker stopped at iteration {}", i);
                 return;
             }
@@ -24,8 +22,9 @@ fn main() {
     std::thread::sleep(Duration::from_secs(3));
 
     println!("Triggering emergency stop...");
-    stop_flag.store(true, Ordering::Release);
+    tasks.stop(ControlSource::Hotkey);
 
     worker.join().unwrap();
+    drop(task);
     println!("Worker thread has stopped");
 }
@@ -18,9 +18,9 @@ sudo cp target/release/superctrl /usr/local/bin/
 echo "✅ Binary installed successfully!"
 echo ""
 echo "Next steps:"
-echo "1. Set your Anthropic API key:"
-echo "   export ANTHROPIC_API_KEY='your-key-here'"
-echo "   # Add to ~/.zshrc or ~/.bashrc to persist"
+echo "1. Save your Anthropic API key:"
+echo "   superctrl auth login"
+echo "   # Stored in the macOS keychain; ANTHROPIC_API_KEY still overrides it"
 echo ""
 echo "2. Configure macrowhisper integration:"
 echo "   ./install-macrowhisper-action.sh"
@@ -31,8 +31,6 @@ echo "   # Check status: macrowhisper --service-status"
 echo ""
 echo "4. (Optional) Set up launch agent for superctrl:"
 echo "   cp superctrl.plist ~/Library/LaunchAgents/com.superctrl.daemon.plist"
-echo "   # Edit plist to add your ANTHROPIC_API_KEY"
-echo "   nano ~/Library/LaunchAgents/com.superctrl.daemon.plist"
 echo "   launchctl load ~/Library/LaunchAgents/com.superctrl.daemon.plist"
 echo ""
 echo "5. Start superctrl daemon manually (if not using launch agent):"
@@ -2,9 +2,16 @@ use anyhow::{Context, Result};
 use enigo::{
     Button, Coordinate, Direction, Enigo, Key, Keyboard, Mouse, Settings as EnigoSettings,
 };
+use std::fmt;
 use std::thread;
 use std::time::Duration;
 
+use crate::input::{synthetic_input, InjectedEvent};
+
+pub trait AutomationBackend {
+    fn execute_action(&mut self, action: Action) -> Result<()>;
+}
+
 pub struct MacAutomation {
     enigo: Enigo,
 }
@@ -16,6 +23,10 @@ pub enum Action {
         y: i32,
         button: MouseButton,
     },
+    MouseMove {
+        x: i32,
+        y: i32,
+    },
     Type {
         text: String,
     },
@@ -40,6 +51,65 @@ pub enum MouseButton {
     Middle,
 }
 
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Click { x, y, button } => match button {
+                MouseButton::Left => write!(f, "click ({}, {})", x, y),
+                MouseButton::Right => write!(f, "right click ({}, {})", x, y),
+                MouseButton::Middle => write!(f, "middle click ({}, {})", x, y),
+            },
+            Action::MouseMove { x, y } => write!(f, "move mouse to ({}, {})", x, y),
+            Action::Type { text } => write!(f, "type '{}'", text),
+            Action::Keypress { keys } => {
+                let keys: Vec<&str> = keys
+                    .iter()
+                    .map(|k| match k.as_str() {
+                        "meta" => "cmd",
+                        "control" => "ctrl",
+                        other => other,
+                    })
+                    .collect();
+                write!(f, "press {}", keys.join("+"))
+            }
+            Action::Scroll {
+                x,
+                y,
+                scroll_x,
+                scroll_y,
+            } => write!(f, "scroll ({}, {}) at ({}, {})", scroll_x, scroll_y, x, y),
+            Action::Wait { duration_ms } => write!(f, "wait {}ms", duration_ms),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SimulatedAutomation {
+    steps: Vec<String>,
+}
+
+impl SimulatedAutomation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, step: String) {
+        tracing::info!("[dry run] {}", step);
+        self.steps.push(step);
+    }
+
+    pub fn steps(&self) -> &[String] {
+        &self.steps
+    }
+}
+
+impl AutomationBackend for SimulatedAutomation {
+    fn execute_action(&mut self, action: Action) -> Result<()> {
+        self.record(action.to_string());
+        Ok(())
+    }
+}
+
 impl MacAutomation {
     pub fn new() -> Result<Self> {
         let enigo = Enigo::new(&EnigoSettings::default()).context("Failed to initialize Enigo")?;
@@ -47,8 +117,25 @@ impl MacAutomation {
     }
 
     pub fn execute_action(&mut self, action: Action) -> Result<()> {
+        let pointer = match &action {
+            Action::Click { x, y, .. }
+            | Action::MouseMove { x, y }
+            | Action::Scroll { x, y, .. } => Some((*x, *y)),
+            Action::Type { .. } | Action::Keypress { .. } => None,
+            Action::Wait { duration_ms } => return self.wait(*duration_ms),
+        };
+
+        synthetic_input().record(pointer);
+        synthetic_input().expect(injected_events(&action));
+        let result = self.inject(action);
+        synthetic_input().record(pointer);
+        result
+    }
+
+    fn inject(&mut self, action: Action) -> Result<()> {
         match action {
             Action::Click { x, y, button } => self.click(x, y, button),
+            Action::MouseMove { x, y } => self.move_mouse(x, y),
             Action::Type { text } => self.type_text(&text),
             Action::Keypress { keys } => self.keypress(&keys),
             Action::Scroll {
@@ -81,6 +168,12 @@ impl MacAutomation {
         Ok(())
     }
 
+    fn move_mouse(&mut self, x: i32, y: i32) -> Result<()> {
+        self.enigo
+            .move_mouse(x, y, Coordinate::Abs)
+            .context("Failed to move mouse")
+    }
+
     fn type_text(&mut self, text: &str) -> Result<()> {
         self.enigo.text(text).context("Failed to type text")?;
         Ok(())
@@ -199,6 +292,30 @@ impl MacAutomation {
     }
 }
 
+pub fn injected_events(action: &Action) -> Vec<InjectedEvent> {
+    match action {
+        Action::Click { button, .. } => vec![InjectedEvent::Button(match button {
+            MouseButton::Left => rdev::Button::Left,
+            MouseButton::Right => rdev::Button::Right,
+            MouseButton::Middle => rdev::Button::Middle,
+        })],
+        // Typing posts at most one key press per character. Whatever it leaves
+        // unused expires SYNTHETIC_WINDOW after the text has been sent.
+        Action::Type { text } => vec![InjectedEvent::Key; text.chars().count()],
+        Action::Keypress { keys } => vec![InjectedEvent::Key; keys.len()],
+        Action::Scroll {
+            scroll_x, scroll_y, ..
+        } => vec![InjectedEvent::Wheel; (*scroll_x != 0) as usize + (*scroll_y != 0) as usize],
+        Action::MouseMove { .. } | Action::Wait { .. } => Vec::new(),
+    }
+}
+
+impl AutomationBackend for MacAutomation {
+    fn execute_action(&mut self, action: Action) -> Result<()> {
+        MacAutomation::execute_action(self, action)
+    }
+}
+
 impl Default for MacAutomation {
     fn default() -> Self {
         Self::new().expect("Failed to initialize MacAutomation")
@@ -1,5 +1,10 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use crate::config::{api_key_from_env, ConfigSources, Settings};
+use crate::redact;
+use crate::secrets::{self, API_KEY_SECRET, HTTP_TOKEN_SECRET};
 
 #[derive(Parser)]
 #[command(name = "superctrl")]
@@ -10,17 +15,60 @@ pub struct Cli {
 
     #[arg(short, long, value_name = "COMMAND")]
     pub execute: Option<String>,
+
+    #[arg(
+        short = 'c',
+        long = "continue",
+        value_name = "COMMAND",
+        conflicts_with = "execute"
+    )]
+    pub continue_command: Option<String>,
+
+    #[arg(long, requires = "execute")]
+    pub dry_run: bool,
+
+    #[arg(long, value_name = "SCHEMA_FILE", requires = "execute")]
+    pub output_schema: Option<PathBuf>,
+
+    #[arg(long, requires = "execute")]
+    pub wait: bool,
+
+    #[arg(long = "config", value_name = "PATH", global = true)]
+    pub config_path: Option<PathBuf>,
+
+    #[arg(long = "set", value_name = "KEY=VALUE", global = true)]
+    pub overrides: Vec<String>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     Daemon,
-    Status,
+    Status {
+        #[arg(long)]
+        json: bool,
+    },
+    Events {
+        #[arg(long)]
+        json: bool,
+    },
     Stop,
+    Pause,
+    Resume,
+    Ask {
+        question: String,
+    },
     Learn {
         #[command(subcommand)]
         action: LearnAction,
     },
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
+    },
 }
 
 #[derive(Subcommand)]
@@ -32,23 +80,73 @@ pub enum LearnAction {
     Clear,
 }
 
+#[derive(Subcommand)]
+pub enum AuthAction {
+    Login,
+    Logout,
+    Status,
+    SetSecret {
+        name: String,
+    },
+    DeleteSecret {
+        name: String,
+    },
+    HttpToken {
+        #[arg(long)]
+        rotate: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    Path,
+    Get { key: Option<String> },
+    Set { key: String, value: String },
+    Edit,
+    Validate,
+}
+
 impl Cli {
     pub fn parse_args() -> Self {
         Self::parse()
     }
 
     pub fn is_status_command(&self) -> bool {
-        matches!(self.command, Some(Commands::Status))
+        matches!(self.command, Some(Commands::Status { .. }))
+    }
+
+    pub fn is_events_command(&self) -> bool {
+        matches!(self.command, Some(Commands::Events { .. }))
     }
 
     pub fn is_stop_command(&self) -> bool {
         matches!(self.command, Some(Commands::Stop))
     }
 
+    pub fn is_pause_or_resume_command(&self) -> bool {
+        matches!(self.command, Some(Commands::Pause | Commands::Resume))
+    }
+
+    pub fn is_ask_command(&self) -> bool {
+        matches!(self.command, Some(Commands::Ask { .. }))
+    }
+
     pub fn is_learn_command(&self) -> bool {
         matches!(self.command, Some(Commands::Learn { .. }))
     }
 
+    pub fn is_config_command(&self) -> bool {
+        matches!(self.command, Some(Commands::Config { .. }))
+    }
+
+    pub fn is_auth_command(&self) -> bool {
+        matches!(self.command, Some(Commands::Auth { .. }))
+    }
+
+    pub fn config_sources(&self) -> Result<ConfigSources> {
+        ConfigSources::new(self.config_path.clone(), self.overrides.clone())
+    }
+
     pub fn get_learn_action(&self) -> Option<&LearnAction> {
         if let Some(Commands::Learn { action }) = &self.command {
             Some(action)
@@ -60,26 +158,299 @@ impl Cli {
     pub fn get_execute_command(&self) -> Option<&String> {
         self.execute.as_ref()
     }
+
+    pub fn get_continue_command(&self) -> Option<&String> {
+        self.continue_command.as_ref()
+    }
+}
+
+fn confirm(prompt: &str) -> Result<bool> {
+    use std::io::Write;
+
+    print!("{} [y/N] ", prompt);
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+fn print_setting(value: &toml::Value) -> Result<()> {
+    match value {
+        toml::Value::String(value) => println!("{}", value),
+        toml::Value::Table(table) => print!("{}", toml::to_string_pretty(table)?),
+        other => println!("{}", other),
+    }
+    Ok(())
+}
+
+fn validation_error(path: &std::path::Path, errors: Vec<String>) -> anyhow::Error {
+    anyhow::anyhow!("{:?} is invalid:\n  - {}", path, errors.join("\n  - "))
+}
+
+pub fn handle_config_command(sources: &ConfigSources, action: &ConfigAction) -> Result<()> {
+    let path = &sources.path;
+    match action {
+        ConfigAction::Path => {
+            println!("{}", path.display());
+        }
+        ConfigAction::Get { key } => {
+            let settings = Settings::load_layered(sources, |var| std::env::var(var).ok())?;
+            match key {
+                Some(key) => print_setting(&settings.get(key)?)?,
+                None => print!("{}", toml::to_string_pretty(&settings)?),
+            }
+        }
+        ConfigAction::Set { key, value } => {
+            let settings = Settings::set_in_file(path, key, value)?;
+            println!("{} = {}", key, settings.get(key)?);
+        }
+        ConfigAction::Edit => {
+            if !path.exists() {
+                Settings::default().save(path)?;
+            }
+
+            let editor = std::env::var("VISUAL")
+                .or_else(|_| std::env::var("EDITOR"))
+                .unwrap_or_else(|_| "vi".to_string());
+            let status = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(format!("{} \"$1\"", editor))
+                .arg("sh")
+                .arg(path)
+                .status()
+                .with_context(|| format!("Failed to launch editor '{}'", editor))?;
+            if !status.success() {
+                anyhow::bail!("Editor '{}' exited with {}", editor, status);
+            }
+
+            if let Err(errors) = Settings::load(path)?.validate() {
+                return Err(validation_error(path, errors));
+            }
+            println!("Saved {}", path.display());
+        }
+        ConfigAction::Validate => {
+            let settings = Settings::load_layered(sources, |var| std::env::var(var).ok())?;
+            if let Err(errors) = settings.validate() {
+                return Err(validation_error(path, errors));
+            }
+            println!("✓ {} is valid", path.display());
+        }
+    }
+    Ok(())
+}
+
+fn read_secret(label: &str) -> Result<String> {
+    use std::io::{IsTerminal, Write};
+
+    let interactive = std::io::stdin().is_terminal();
+    if interactive {
+        print!("{}: ", label);
+        std::io::stdout().flush()?;
+        let _ = std::process::Command::new("stty").arg("-echo").status();
+    }
+
+    let mut value = String::new();
+    let read = std::io::stdin().read_line(&mut value);
+
+    if interactive {
+        let _ = std::process::Command::new("stty").arg("echo").status();
+        println!();
+    }
+    read.with_context(|| format!("Failed to read the {}", label))?;
+
+    let value = value.trim_end_matches(['\r', '\n']).to_string();
+    if value.trim().is_empty() {
+        anyhow::bail!("No {} entered", label);
+    }
+    Ok(value)
+}
+
+fn check_secret_name(name: &str) -> Result<()> {
+    if name == API_KEY_SECRET {
+        anyhow::bail!("Use `superctrl auth login` and `superctrl auth logout` for the API key");
+    }
+    if name == HTTP_TOKEN_SECRET {
+        anyhow::bail!("Use `superctrl auth http-token` for the HTTP API token");
+    }
+    if !secrets::is_valid_secret_name(name) {
+        anyhow::bail!(
+            "Invalid secret name '{}': use letters, digits, '-', '_' and '.'",
+            name
+        );
+    }
+    Ok(())
+}
+
+pub fn handle_auth_command(sources: &ConfigSources, action: &AuthAction) -> Result<()> {
+    let settings = Settings::load_layered(sources, |var| std::env::var(var).ok())?;
+    let store = secrets::open_store(&settings.secrets)?;
+
+    match action {
+        AuthAction::Login => {
+            let api_key = read_secret("Anthropic API key")?.trim().to_string();
+            store.set(API_KEY_SECRET, &api_key)?;
+            println!("✓ API key saved to the {}", store.description());
+            if api_key_from_env().is_some() {
+                println!("  ANTHROPIC_API_KEY is set and still takes precedence");
+            }
+        }
+        AuthAction::Logout => {
+            store.delete(API_KEY_SECRET)?;
+            println!("✓ API key removed from the {}", store.description());
+        }
+        AuthAction::Status => {
+            let stored = store.get(API_KEY_SECRET)?;
+            println!("Backend:    {}", store.description());
+            println!(
+                "Stored key: {}",
+                stored
+                    .as_deref()
+                    .map_or("not set".to_string(), redact::mask)
+            );
+            match (api_key_from_env(), &stored) {
+                (Some(api_key), _) => println!(
+                    "In use:     ANTHROPIC_API_KEY ({}), overriding the stored key",
+                    redact::mask(&api_key)
+                ),
+                (None, Some(_)) => println!("In use:     stored key"),
+                (None, None) => {
+                    anyhow::bail!("No API key found. Run `superctrl auth login` to save one")
+                }
+            }
+
+            let others: Vec<String> = store
+                .names()?
+                .into_iter()
+                .filter(|name| !secrets::is_reserved_secret(name))
+                .collect();
+            if !others.is_empty() {
+                println!("Secrets:    {}", others.join(", "));
+            }
+        }
+        AuthAction::SetSecret { name } => {
+            check_secret_name(name)?;
+            let value = read_secret(&format!("Value for '{}'", name))?;
+            store.set(name, &value)?;
+            println!("✓ Secret '{}' saved to the {}", name, store.description());
+            println!("  Commands can type it with {}", secrets::placeholder(name));
+        }
+        AuthAction::DeleteSecret { name } => {
+            check_secret_name(name)?;
+            store.delete(name)?;
+            println!(
+                "✓ Secret '{}' removed from the {}",
+                name,
+                store.description()
+            );
+        }
+        AuthAction::HttpToken { rotate } => {
+            let token = secrets::http_token(store.as_ref(), *rotate)?;
+            println!("{}", token);
+            if *rotate {
+                eprintln!("Restart the daemon for the new token to take effect");
+            }
+            if !settings.http.enabled {
+                eprintln!(
+                    "The HTTP API is off. Enable it with `superctrl config set http.enabled true`"
+                );
+            }
+        }
+    }
+    Ok(())
 }
 
 pub async fn handle_cli_command(cli: &Cli) -> Result<()> {
     if let Some(command_text) = cli.get_execute_command() {
-        crate::ipc::send_execute_command(command_text).await?;
+        let output_schema = match &cli.output_schema {
+            Some(path) => {
+                let contents = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read output schema {:?}", path))?;
+                Some(
+                    serde_json::from_str(&contents)
+                        .with_context(|| format!("Output schema {:?} is not valid JSON", path))?,
+                )
+            }
+            None => None,
+        };
+
+        if cli.dry_run {
+            let plan = crate::ipc::send_plan_command(command_text).await?;
+            println!("{}", plan);
+
+            if !confirm("Run this plan for real?")? {
+                println!("Plan discarded");
+                return Ok(());
+            }
+        }
+
+        let response =
+            crate::ipc::send_execute_command(command_text, output_schema, cli.wait).await?;
+        match response.data {
+            Some(result) => println!("{}", serde_json::to_string_pretty(&result)?),
+            None => println!("{}", response.message),
+        }
+        return Ok(());
+    }
+
+    if let Some(command_text) = cli.get_continue_command() {
+        crate::ipc::send_continue_command(command_text).await?;
+        println!("Follow-up command started");
         return Ok(());
     }
 
     match &cli.command {
         Some(Commands::Daemon) => Ok(()),
-        Some(Commands::Status) => {
+        Some(Commands::Status { json }) => {
             let status = crate::ipc::send_status_command().await?;
-            println!("{}", status);
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&status)?);
+            } else {
+                print!("{}", status.render_table());
+            }
             Ok(())
         }
+        Some(Commands::Events { json }) => {
+            let json = *json;
+            crate::ipc::send_events_command(|event| {
+                if json {
+                    match serde_json::to_string(&event) {
+                        Ok(line) => println!("{}", line),
+                        Err(e) => tracing::warn!("Failed to encode event: {}", e),
+                    }
+                } else {
+                    println!(
+                        "{}  {}",
+                        event.at.format("%H:%M:%S"),
+                        event.event.describe()
+                    );
+                }
+            })
+            .await
+        }
         Some(Commands::Stop) => {
             crate::ipc::send_stop_command().await?;
             println!("Emergency stop signal sent");
             Ok(())
         }
+        Some(Commands::Pause) => {
+            crate::ipc::send_pause_command().await?;
+            println!("Task paused");
+            Ok(())
+        }
+        Some(Commands::Resume) => {
+            crate::ipc::send_resume_command().await?;
+            println!("Task resumed");
+            Ok(())
+        }
+        Some(Commands::Ask { question }) => {
+            let answer = crate::ipc::send_ask_command(question).await?;
+            println!("{}", answer);
+            Ok(())
+        }
+        Some(Commands::Config { action }) => handle_config_command(&cli.config_sources()?, action),
+        Some(Commands::Auth { action }) => handle_auth_command(&cli.config_sources()?, action),
         Some(Commands::Learn { action }) => match action {
             LearnAction::Start => {
                 crate::ipc::send_learn_start_command().await?;
@@ -1,13 +1,19 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
-};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::automation::{Action, MacAutomation, MouseButton};
+use crate::automation::{
+    Action, AutomationBackend, MacAutomation, MouseButton, SimulatedAutomation,
+};
+use crate::config::{RedactionSettings, DEFAULT_API_URL, DEFAULT_MODEL};
+use crate::events::{AgentEvent, AgentEvents};
+use crate::history::{screenshot_from_result, TaskRecorder};
+use crate::mcp::McpManager;
 use crate::screenshot::ScreenCapture;
+use crate::secrets::{self, SecretStore};
+use crate::task::{CancellationToken, TaskCancelled};
 
 pub fn calculate_scale_factor(width: u32, height: u32) -> f64 {
     let long_edge = width.max(height) as f64;
@@ -19,27 +25,252 @@ pub fn calculate_scale_factor(width: u32, height: u32) -> f64 {
     long_edge_scale.min(total_pixels_scale).min(1.0)
 }
 
-const MODEL: &str = "claude-sonnet-4-5";
-const MAX_ITERATIONS: usize = 50;
-const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
+pub const MAX_ITERATIONS: usize = 50;
+const CONNECTION_TEST_TIMEOUT: Duration = Duration::from_secs(15);
 const TOOL_VERSION: &str = "computer_20250124";
 const BETA_FLAG: &str = "computer-use-2025-01-24";
+const SUBMIT_RESULT_TOOL: &str = "submit_result";
+const MAX_SUBMIT_REMINDERS: usize = 2;
+const TYPE_CHUNK_CHARS: usize = 16;
+const RESUMED_NOTE: &str =
+    "The task was paused by the user and has now resumed. The user may have \
+    changed the screen while it was paused, so check the current screen below before continuing.";
+const SKIPPED_AFTER_PAUSE_NOTE: &str =
+    "Not executed because the task was paused. Re-plan from the current screen.";
+
+fn result_text(result: &Value) -> String {
+    result
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|block| block["text"].as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn api_error_message(body: &str) -> String {
+    serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|body| body["error"]["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| body.to_string())
+}
+
+pub async fn check_api_connection(api_key: &str, api_url: &str, model: &str) -> Result<()> {
+    let response = reqwest::Client::new()
+        .post(api_url)
+        .timeout(CONNECTION_TEST_TIMEOUT)
+        .header("x-api-key", api_key)
+        .header("anthropic-version", "2023-06-01")
+        .header("content-type", "application/json")
+        .json(&json!({
+            "model": model,
+            "max_tokens": 1,
+            "messages": [{ "role": "user", "content": "ping" }]
+        }))
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {}", api_url))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        anyhow::bail!("{} - {}", status, api_error_message(&error_text));
+    }
+
+    Ok(())
+}
+
+pub fn validate_structured_result(schema: &Value, result: &Value) -> Result<(), Vec<String>> {
+    let validator = jsonschema::validator_for(schema).map_err(|e| vec![e.to_string()])?;
+
+    let errors: Vec<String> = validator
+        .iter_errors(result)
+        .map(|e| {
+            let path = e.instance_path.to_string();
+            if path.is_empty() {
+                e.to_string()
+            } else {
+                format!("{}: {}", path, e)
+            }
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AgentMode {
+    #[default]
+    Act,
+    ReadOnly,
+    DryRun,
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskPlan {
+    pub steps: Vec<String>,
+    pub summary: String,
+}
+
+impl std::fmt::Display for TaskPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.steps.is_empty() {
+            writeln!(f, "No actions planned.")?;
+        } else {
+            writeln!(f, "Planned actions:")?;
+            for (i, step) in self.steps.iter().enumerate() {
+                writeln!(f, "  {}. {}", i + 1, step)?;
+            }
+        }
+        if !self.summary.is_empty() {
+            write!(f, "\n{}", self.summary)?;
+        }
+        Ok(())
+    }
+}
 
 pub struct ComputerUseAgent {
     api_key: String,
+    model: String,
+    api_url: String,
     automation: MacAutomation,
     screenshot: ScreenCapture,
-    stop_flag: Arc<AtomicBool>,
+    cancel: CancellationToken,
     full_trust_mode: bool,
     client: reqwest::Client,
     actual_screen_width: u32,
     actual_screen_height: u32,
+    mcp: Option<Arc<McpManager>>,
+    mode: AgentMode,
+    simulated: Option<SimulatedAutomation>,
+    output_schema: Option<Value>,
+    structured_result: Option<Value>,
+    interrupted_by_pause: bool,
+    conversation: Conversation,
+    recorder: Option<TaskRecorder>,
+    events: Option<AgentEvents>,
+    secrets: Option<Arc<dyn SecretStore>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct AnthropicMessage {
-    role: String,
-    content: Value,
+pub struct AnthropicMessage {
+    pub role: String,
+    pub content: Value,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Conversation {
+    messages: Vec<AnthropicMessage>,
+}
+
+impl Conversation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn messages(&self) -> &[AnthropicMessage] {
+        &self.messages
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    pub fn push(&mut self, role: &str, content: Value) {
+        self.messages.push(AnthropicMessage {
+            role: role.to_string(),
+            content,
+        });
+    }
+
+    pub fn push_user_text(&mut self, text: &str) {
+        self.push_user_blocks(json!([{ "type": "text", "text": text }]));
+    }
+
+    pub fn push_user_blocks(&mut self, blocks: Value) {
+        let Value::Array(new_blocks) = blocks else {
+            return;
+        };
+
+        if let Some(last) = self.messages.last_mut().filter(|m| m.role == "user") {
+            if let Some(existing) = last.content.as_array_mut() {
+                existing.extend(new_blocks);
+                return;
+            }
+        }
+
+        self.push("user", Value::Array(new_blocks));
+    }
+
+    pub fn prune_images(&mut self) {
+        fn prune(value: &mut Value) {
+            let Some(blocks) = value.as_array_mut() else {
+                return;
+            };
+            for block in blocks {
+                if block["type"] == "image" {
+                    *block = json!({ "type": "text", "text": "[screenshot omitted]" });
+                } else if block["type"] == "tool_result" {
+                    prune(&mut block["content"]);
+                }
+            }
+        }
+
+        for message in &mut self.messages {
+            prune(&mut message.content);
+        }
+    }
+
+    pub fn pruned(&self) -> Self {
+        let mut conversation = self.clone();
+        conversation.prune_images();
+        conversation
+    }
+}
+
+pub struct ConversationStore {
+    last: Option<(Conversation, Instant)>,
+    timeout: Duration,
+}
+
+impl ConversationStore {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            last: None,
+            timeout,
+        }
+    }
+
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    pub fn save(&mut self, conversation: Conversation) {
+        if self.timeout.is_zero() || conversation.is_empty() {
+            return;
+        }
+        tracing::debug!(
+            "Keeping conversation with {} messages for follow-ups",
+            conversation.messages().len()
+        );
+        self.last = Some((conversation.pruned(), Instant::now()));
+    }
+
+    pub fn recent(&mut self) -> Option<Conversation> {
+        match &self.last {
+            Some((conversation, saved_at)) if saved_at.elapsed() < self.timeout => {
+                Some(conversation.clone())
+            }
+            _ => {
+                self.last = None;
+                None
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -58,6 +289,14 @@ struct AnthropicResponse {
     stop_reason: String,
     #[serde(default)]
     stop_sequence: Option<String>,
+    #[serde(default)]
+    usage: Usage,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct Usage {
+    input_tokens: u64,
+    output_tokens: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -79,10 +318,11 @@ struct ToolResult {
     result_type: String,
     tool_use_id: String,
     content: Value,
+    is_error: bool,
 }
 
 impl ComputerUseAgent {
-    pub fn new(api_key: String, stop_flag: Arc<AtomicBool>) -> Result<Self> {
+    pub fn new(api_key: String, cancel: CancellationToken) -> Result<Self> {
         let automation = MacAutomation::new()?;
         let client = reqwest::Client::new();
 
@@ -91,13 +331,25 @@ impl ComputerUseAgent {
 
         Ok(Self {
             api_key,
+            model: DEFAULT_MODEL.to_string(),
+            api_url: DEFAULT_API_URL.to_string(),
             automation,
             screenshot,
-            stop_flag,
+            cancel,
             full_trust_mode: true,
             client,
             actual_screen_width: actual_width,
             actual_screen_height: actual_height,
+            mcp: None,
+            mode: AgentMode::Act,
+            simulated: None,
+            output_schema: None,
+            structured_result: None,
+            interrupted_by_pause: false,
+            conversation: Conversation::new(),
+            recorder: None,
+            events: None,
+            secrets: None,
         })
     }
 
@@ -116,15 +368,100 @@ impl ComputerUseAgent {
         self
     }
 
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into();
+        self
+    }
+
+    pub fn with_screenshot_quality(mut self, quality: u8) -> Self {
+        self.screenshot = std::mem::take(&mut self.screenshot).with_quality(quality);
+        self
+    }
+
+    pub fn with_redaction(mut self, redaction: RedactionSettings) -> Self {
+        self.screenshot = std::mem::take(&mut self.screenshot).with_redaction(redaction);
+        self
+    }
+
     pub fn with_full_trust_mode(mut self, enabled: bool) -> Self {
         self.full_trust_mode = enabled;
         self
     }
 
+    pub fn with_mcp_tools(mut self, mcp: Arc<McpManager>) -> Self {
+        self.mcp = Some(mcp);
+        self
+    }
+
+    pub fn with_mode(mut self, mode: AgentMode) -> Self {
+        self.mode = mode;
+        self.simulated = match mode {
+            AgentMode::DryRun => Some(SimulatedAutomation::new()),
+            _ => None,
+        };
+        self
+    }
+
+    pub fn with_output_schema(mut self, schema: Value) -> Result<Self> {
+        jsonschema::validator_for(&schema)
+            .map_err(|e| anyhow::anyhow!("Invalid output schema: {}", e))?;
+        self.output_schema = Some(schema);
+        Ok(self)
+    }
+
+    pub fn with_conversation(mut self, conversation: Conversation) -> Self {
+        self.conversation = conversation;
+        self
+    }
+
+    pub fn with_recorder(mut self, recorder: TaskRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    pub fn with_events(mut self, events: AgentEvents) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    pub fn with_secrets(mut self, store: Arc<dyn SecretStore>) -> Self {
+        self.secrets = Some(store);
+        self
+    }
+
+    pub fn conversation(&self) -> &Conversation {
+        &self.conversation
+    }
+
+    pub fn structured_result(&self) -> Option<&Value> {
+        self.structured_result.as_ref()
+    }
+
+    pub async fn plan_command(&mut self, command: &str) -> Result<TaskPlan> {
+        if self.mode != AgentMode::DryRun {
+            self.simulated = Some(SimulatedAutomation::new());
+            self.mode = AgentMode::DryRun;
+        }
+
+        let summary = self.execute_command(command).await?;
+        let steps = self
+            .simulated
+            .as_ref()
+            .map(|simulated| simulated.steps().to_vec())
+            .unwrap_or_default();
+
+        Ok(TaskPlan { steps, summary })
+    }
+
     pub async fn execute_command(&mut self, command: &str) -> Result<String> {
         let (display_width, display_height) = self.screenshot.get_display_size();
 
-        let system_prompt = format!(
+        let mut system_prompt = format!(
             "You are an automation assistant for macOS with screen resolution {}x{}. \
              You have been granted access to the computer use tool for legitimate desktop automation.\n\n\
              System context:\n\
@@ -178,65 +515,103 @@ impl ComputerUseAgent {
             "display_number": 1
         });
 
-        let mut messages: Vec<AnthropicMessage> = vec![AnthropicMessage {
-            role: "user".to_string(),
-            content: json!([{
-                "type": "text",
-                "text": command
-            }]),
-        }];
+        let mut tools = vec![computer_tool];
+        if self.mode == AgentMode::DryRun {
+            system_prompt.push_str(
+                "\n\nDRY RUN: Actions you request are simulated and logged, not performed, so the screen \
+                 will NOT change after them. Screenshots always show the current real screen. Plan the \
+                 complete sequence of actions needed for the request as if each action succeeded, without \
+                 retrying actions because the screen looks unchanged. When the plan is complete, stop \
+                 calling tools and briefly summarize what the plan does.",
+            );
+        }
+
+        if self.mode == AgentMode::ReadOnly {
+            system_prompt.push_str(
+                "\n\nREAD-ONLY MODE: The user is asking a question about what is on screen. \
+                 Only the screenshot action is permitted; every other action will be refused. \
+                 Take a screenshot, then answer the question directly and concisely in text.",
+            );
+        } else if let Some(mcp) = self.mcp.as_ref().filter(|mcp| !mcp.is_empty()) {
+            tools.extend(mcp.tool_definitions());
+            system_prompt.push_str(
+                "\n\nAdditional tools from connected MCP servers are available next to the computer tool. \
+                 Prefer them over GUI automation when they can accomplish the request directly \
+                 (for example reading or updating issues and documents).",
+            );
+        }
+
+        if self.mode != AgentMode::ReadOnly {
+            if let Some(section) = self.secrets_prompt() {
+                system_prompt.push_str(&section);
+            }
+        }
+
+        if let Some(schema) = &self.output_schema {
+            tools.push(json!({
+                "name": SUBMIT_RESULT_TOOL,
+                "description": "Submit the final structured result of the task. Call this exactly once when \
+                                the task is complete; the task only finishes when a result matching the \
+                                schema is accepted.",
+                "input_schema": {
+                    "type": "object",
+                    "properties": { "result": schema },
+                    "required": ["result"]
+                }
+            }));
+            system_prompt.push_str(
+                "\n\nSTRUCTURED RESULT: When the task is complete you MUST call the submit_result tool \
+                 with the requested data. If it reports validation errors, fix them and call it again.",
+            );
+        }
+        self.structured_result = None;
+
+        self.conversation.push_user_text(command);
 
         let mut iteration = 0;
         let mut final_response = String::new();
+        let mut submit_reminders = 0;
 
         while iteration < MAX_ITERATIONS {
-            if self.stop_flag.load(Ordering::Relaxed) {
-                anyhow::bail!("Execution stopped by user");
+            if self.cancel.wait_if_paused().await? {
+                let note = self.resume_content(RESUMED_NOTE)?;
+                self.conversation.push_user_blocks(note);
             }
 
             iteration += 1;
+            self.emit(AgentEvent::Step {
+                step: iteration,
+                max_steps: MAX_ITERATIONS,
+            });
 
             let request = AnthropicRequest {
-                model: MODEL.to_string(),
+                model: self.model.clone(),
                 max_tokens: 4096,
-                tools: vec![computer_tool.clone()],
-                messages: messages.clone(),
+                tools: tools.clone(),
+                messages: self.conversation.messages.clone(),
                 system: Some(system_prompt.clone()),
             };
 
-            let response = self
-                .client
-                .post(ANTHROPIC_API_URL)
-                .header("x-api-key", &self.api_key)
-                .header("anthropic-version", "2023-06-01")
-                .header("anthropic-beta", BETA_FLAG)
-                .header("content-type", "application/json")
-                .json(&request)
-                .send()
-                .await
-                .map_err(|e| {
-                    tracing::error!("Anthropic API error: {:?}", e);
-                    anyhow::anyhow!("Failed to call Anthropic API: {}", e)
-                })?;
+            let api_response = self
+                .cancel
+                .run_until_cancelled(self.send_request(&request))
+                .await??;
 
-            if !response.status().is_success() {
-                let status = response.status();
-                let error_text = response.text().await.unwrap_or_default();
-                tracing::error!("Anthropic API error: {} - {}", status, error_text);
-                anyhow::bail!("Anthropic API returned error: {} - {}", status, error_text);
+            if let Some(recorder) = &self.recorder {
+                recorder.record_usage(
+                    api_response.usage.input_tokens,
+                    api_response.usage.output_tokens,
+                );
             }
 
-            let api_response: AnthropicResponse = response
-                .json()
-                .await
-                .map_err(|e| anyhow::anyhow!("Failed to parse Anthropic response: {}", e))?;
-
             let mut tool_results = Vec::new();
             let mut assistant_content = Vec::new();
+            let mut skip_remaining = false;
 
             for block in api_response.content {
                 match block {
                     ContentBlock::Text { text } => {
+                        self.emit(AgentEvent::AssistantText(text.clone()));
                         final_response = text.clone();
                         assistant_content.push(json!({
                             "type": "text",
@@ -244,59 +619,124 @@ impl ComputerUseAgent {
                         }));
                     }
                     ContentBlock::ToolUse { id, name, input } => {
-                        if name == "computer" {
-                            let id_clone = id.clone();
-                            let result = match self.execute_computer_action(&input).await {
-                                Ok(r) => r,
+                        self.cancel.check()?;
+
+                        if !skip_remaining {
+                            self.emit(AgentEvent::ToolUse {
+                                tool: name.clone(),
+                                action: input["action"].as_str().map(str::to_string),
+                                point: self.action_point(&name, &input),
+                            });
+                        }
+
+                        let mut step_error = None;
+                        let (result, is_error) = if skip_remaining {
+                            (
+                                json!([{ "type": "text", "text": SKIPPED_AFTER_PAUSE_NOTE }]),
+                                false,
+                            )
+                        } else if self.cancel.wait_if_paused().await? {
+                            skip_remaining = true;
+                            (
+                                self.resume_content(&format!(
+                                    "{} This action was not executed.",
+                                    RESUMED_NOTE
+                                ))?,
+                                false,
+                            )
+                        } else if name == SUBMIT_RESULT_TOOL && self.output_schema.is_some() {
+                            self.accept_structured_result(&input)
+                        } else if name == "computer" {
+                            match self.execute_computer_action(&input).await {
+                                Ok(r) => (r, false),
+                                Err(e) if e.is::<TaskCancelled>() => return Err(e),
                                 Err(e) => {
                                     tracing::error!("Failed to execute computer action: {}", e);
-                                    json!([{
-                                        "type": "text",
-                                        "text": format!("Error executing action: {}", e)
-                                    }])
+                                    step_error = Some(e.to_string());
+                                    (
+                                        json!([{
+                                            "type": "text",
+                                            "text": format!("Error executing action: {}", e)
+                                        }]),
+                                        false,
+                                    )
                                 }
-                            };
-                            tool_results.push(ToolResult {
-                                result_type: "tool_result".to_string(),
-                                tool_use_id: id_clone.clone(),
-                                content: json!(result),
-                            });
+                            }
+                        } else {
+                            self.execute_mcp_tool(&name, &input).await?
+                        };
+                        if std::mem::take(&mut self.interrupted_by_pause) {
+                            skip_remaining = true;
+                        }
 
-                            assistant_content.push(json!({
-                                "type": "tool_use",
-                                "id": id_clone,
-                                "name": name,
-                                "input": input
-                            }));
+                        if let Some(events) = &self.events {
+                            if let Some(screenshot) = screenshot_from_result(&result) {
+                                events.emit(AgentEvent::Screenshot(Arc::new(screenshot)));
+                            }
                         }
+
+                        if let Some(recorder) = &self.recorder {
+                            if is_error && step_error.is_none() {
+                                step_error = Some(result_text(&result));
+                            }
+                            recorder.record_step(&name, &input, &result, step_error.as_deref());
+                        }
+
+                        tool_results.push(ToolResult {
+                            result_type: "tool_result".to_string(),
+                            tool_use_id: id.clone(),
+                            content: result,
+                            is_error,
+                        });
+
+                        assistant_content.push(json!({
+                            "type": "tool_use",
+                            "id": id,
+                            "name": name,
+                            "input": input
+                        }));
                     }
                 }
             }
 
-            messages.push(AnthropicMessage {
-                role: "assistant".to_string(),
-                content: json!(assistant_content),
-            });
+            self.conversation
+                .push("assistant", json!(assistant_content));
 
             if tool_results.is_empty() {
-                break;
+                if self.output_schema.is_none() {
+                    break;
+                }
+
+                if submit_reminders >= MAX_SUBMIT_REMINDERS {
+                    anyhow::bail!("Model finished without submitting a structured result");
+                }
+                submit_reminders += 1;
+                self.conversation.push_user_text(
+                    "You must call the submit_result tool with the result before finishing.",
+                );
+                continue;
             }
 
             let tool_result_content: Vec<Value> = tool_results
                 .into_iter()
                 .map(|tr| {
-                    json!({
+                    let mut block = json!({
                         "type": tr.result_type,
                         "tool_use_id": tr.tool_use_id,
                         "content": tr.content
-                    })
+                    });
+                    if tr.is_error {
+                        block["is_error"] = json!(true);
+                    }
+                    block
                 })
                 .collect();
 
-            messages.push(AnthropicMessage {
-                role: "user".to_string(),
-                content: json!(tool_result_content),
-            });
+            self.conversation.push("user", json!(tool_result_content));
+
+            if self.structured_result.is_some() {
+                break;
+            }
         }
 
         if iteration >= MAX_ITERATIONS {
@@ -306,9 +746,125 @@ impl ComputerUseAgent {
         Ok(final_response)
     }
 
+    async fn send_request(&self, request: &AnthropicRequest) -> Result<AnthropicResponse> {
+        let response = self
+            .client
+            .post(&self.api_url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("anthropic-beta", BETA_FLAG)
+            .header("content-type", "application/json")
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| {
+                tracing::error!("Anthropic API error: {:?}", e);
+                anyhow::anyhow!("Failed to call Anthropic API: {}", e)
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            tracing::error!("Anthropic API error: {} - {}", status, error_text);
+            anyhow::bail!("Anthropic API returned error: {} - {}", status, error_text);
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to parse Anthropic response: {}", e))
+    }
+
+    fn accept_structured_result(&mut self, input: &Value) -> (Value, bool) {
+        let Some(schema) = &self.output_schema else {
+            return (
+                json!([{ "type": "text", "text": "No result was requested" }]),
+                true,
+            );
+        };
+        let result = input.get("result").cloned().unwrap_or(Value::Null);
+
+        match validate_structured_result(schema, &result) {
+            Ok(()) => {
+                tracing::info!("Structured result accepted");
+                self.structured_result = Some(result);
+                (
+                    json!([{ "type": "text", "text": "Result accepted." }]),
+                    false,
+                )
+            }
+            Err(errors) => {
+                tracing::warn!("Structured result rejected: {:?}", errors);
+                (
+                    json!([{
+                        "type": "text",
+                        "text": format!(
+                            "The result does not match the schema:\n- {}\nFix the result and call submit_result again.",
+                            errors.join("\n- ")
+                        )
+                    }]),
+                    true,
+                )
+            }
+        }
+    }
+
+    async fn execute_mcp_tool(
+        &mut self,
+        name: &str,
+        input: &Value,
+    ) -> Result<(Value, bool), TaskCancelled> {
+        if self.mode == AgentMode::ReadOnly {
+            tracing::warn!("Refusing tool {} in read-only mode", name);
+            return Ok((
+                json!([{ "type": "text", "text": format!("Tool {} is not allowed in read-only mode", name) }]),
+                true,
+            ));
+        }
+
+        let Some(mcp) = self.mcp.as_ref().filter(|mcp| mcp.handles(name)) else {
+            tracing::warn!("Model requested unknown tool: {}", name);
+            return Ok((
+                json!([{ "type": "text", "text": format!("Unknown tool: {}", name) }]),
+                true,
+            ));
+        };
+
+        if let Some(simulated) = self.simulated.as_mut() {
+            simulated.record(format!("call {} with {}", name, input));
+            return Ok((
+                json!([{ "type": "text", "text": format!("[dry run] Simulated call to {}; nothing was executed.", name) }]),
+                false,
+            ));
+        }
+
+        let call = self
+            .cancel
+            .run_until_cancelled(mcp.call_tool(name, input.clone()))
+            .await?;
+        Ok(match call {
+            Ok(result) => (result.to_anthropic_content(), result.is_error),
+            Err(e) => {
+                tracing::error!("Failed to call MCP tool {}: {}", name, e);
+                (
+                    json!([{ "type": "text", "text": format!("Error calling tool: {}", e) }]),
+                    true,
+                )
+            }
+        })
+    }
+
     async fn execute_computer_action(&mut self, input: &Value) -> Result<Value> {
         let action = input["action"].as_str().context("Missing action field")?;
 
+        if self.mode == AgentMode::ReadOnly && action != "screenshot" {
+            tracing::warn!("Refusing action {} in read-only mode", action);
+            anyhow::bail!(
+                "Action '{}' is not allowed in read-only mode; only screenshot is permitted",
+                action
+            );
+        }
+
         tracing::info!(
             "Executing action: {} with input: {}",
             action,
@@ -320,17 +876,7 @@ impl ComputerUseAgent {
         let scale_back = 1.0 / scale;
 
         let result = match action {
-            "screenshot" => {
-                let screenshot_base64 = self.screenshot.capture_screenshot()?;
-                json!([{
-                    "type": "image",
-                    "source": {
-                        "type": "base64",
-                        "media_type": "image/jpeg",
-                        "data": screenshot_base64
-                    }
-                }])
-            }
+            "screenshot" => self.screenshot_content()?,
             "left_click" => {
                 let coord = input["coordinate"]
                     .as_array()
@@ -339,23 +885,15 @@ impl ComputerUseAgent {
                 let y = (coord[1].as_f64().context("Invalid y coordinate")? * scale_back) as i32;
 
                 tracing::info!("Clicking at ({}, {})", x, y);
-                self.automation.execute_action(Action::Click {
+                self.backend().execute_action(Action::Click {
                     x,
                     y,
                     button: MouseButton::Left,
                 })?;
 
-                std::thread::sleep(std::time::Duration::from_millis(150));
+                self.settle(150).await?;
 
-                let screenshot_base64 = self.screenshot.capture_screenshot()?;
-                json!([{
-                    "type": "image",
-                    "source": {
-                        "type": "base64",
-                        "media_type": "image/jpeg",
-                        "data": screenshot_base64
-                    }
-                }])
+                self.action_result()?
             }
             "right_click" => {
                 let coord = input["coordinate"]
@@ -364,21 +902,13 @@ impl ComputerUseAgent {
                 let x = (coord[0].as_f64().context("Invalid x coordinate")? * scale_back) as i32;
                 let y = (coord[1].as_f64().context("Invalid y coordinate")? * scale_back) as i32;
 
-                self.automation.execute_action(Action::Click {
+                self.backend().execute_action(Action::Click {
                     x,
                     y,
                     button: MouseButton::Right,
                 })?;
 
-                let screenshot_base64 = self.screenshot.capture_screenshot()?;
-                json!([{
-                    "type": "image",
-                    "source": {
-                        "type": "base64",
-                        "media_type": "image/jpeg",
-                        "data": screenshot_base64
-                    }
-                }])
+                self.action_result()?
             }
             "middle_click" => {
                 let coord = input["coordinate"]
@@ -387,21 +917,13 @@ impl ComputerUseAgent {
                 let x = (coord[0].as_f64().context("Invalid x coordinate")? * scale_back) as i32;
                 let y = (coord[1].as_f64().context("Invalid y coordinate")? * scale_back) as i32;
 
-                self.automation.execute_action(Action::Click {
+                self.backend().execute_action(Action::Click {
                     x,
                     y,
                     button: MouseButton::Middle,
                 })?;
 
-                let screenshot_base64 = self.screenshot.capture_screenshot()?;
-                json!([{
-                    "type": "image",
-                    "source": {
-                        "type": "base64",
-                        "media_type": "image/jpeg",
-                        "data": screenshot_base64
-                    }
-                }])
+                self.action_result()?
             }
             "double_click" => {
                 let coord = input["coordinate"]
@@ -411,22 +933,14 @@ impl ComputerUseAgent {
                 let y = (coord[1].as_f64().context("Invalid y coordinate")? * scale_back) as i32;
 
                 for _ in 0..2 {
-                    self.automation.execute_action(Action::Click {
+                    self.backend().execute_action(Action::Click {
                         x,
                         y,
                         button: MouseButton::Left,
                     })?;
                 }
 
-                let screenshot_base64 = self.screenshot.capture_screenshot()?;
-                json!([{
-                    "type": "image",
-                    "source": {
-                        "type": "base64",
-                        "media_type": "image/jpeg",
-                        "data": screenshot_base64
-                    }
-                }])
+                self.action_result()?
             }
             "triple_click" => {
                 let coord = input["coordinate"]
@@ -436,18 +950,14 @@ impl ComputerUseAgent {
                 let y = (coord[1].as_f64().context("Invalid y coordinate")? * scale_back) as i32;
 
                 for _ in 0..3 {
-                    self.automation.execute_action(Action::Click {
+                    self.backend().execute_action(Action::Click {
                         x,
                         y,
                         button: MouseButton::Left,
                     })?;
                 }
 
-                let screenshot_base64 = self.screenshot.capture_screenshot()?;
-                json!({
-                    "success": true,
-                    "screenshot": screenshot_base64
-                })
+                self.action_result()?
             }
             "type" => {
                 let text = input["text"]
@@ -456,19 +966,28 @@ impl ComputerUseAgent {
                     .to_string();
 
                 tracing::info!("Typing: {}", text);
-                self.automation.execute_action(Action::Type { text })?;
+                let secret_text = self.substitute_secrets(&text)?;
+                let typed = self.type_text(&secret_text).await?;
+                let total = secret_text.chars().count();
+                if typed < total {
+                    self.interrupted_by_pause = true;
+                    let note = if secret_text == text {
+                        format!(
+                            "{} Typing was interrupted after {} of {} characters; the rest was not typed.",
+                            RESUMED_NOTE, typed, total
+                        )
+                    } else {
+                        format!(
+                            "{} Typing was interrupted partway through; the rest was not typed.",
+                            RESUMED_NOTE
+                        )
+                    };
+                    return self.resume_content(&note);
+                }
 
-                std::thread::sleep(std::time::Duration::from_millis(100));
+                self.settle(100).await?;
 
-                let screenshot_base64 = self.screenshot.capture_screenshot()?;
-                json!([{
-                    "type": "image",
-                    "source": {
-                        "type": "base64",
-                        "media_type": "image/jpeg",
-                        "data": screenshot_base64
-                    }
-                }])
+                self.action_result()?
             }
             "key" => {
                 let key_str = if let Some(key) = input["key"].as_str() {
@@ -494,20 +1013,12 @@ impl ComputerUseAgent {
                     .iter()
                     .any(|k| k.to_lowercase() == "return" || k.to_lowercase() == "enter");
                 tracing::info!("Pressing keys: {:?}", keys);
-                self.automation.execute_action(Action::Keypress { keys })?;
+                self.backend().execute_action(Action::Keypress { keys })?;
 
                 let delay_ms = if is_return_or_enter { 500 } else { 100 };
-                std::thread::sleep(std::time::Duration::from_millis(delay_ms));
-
-                let screenshot_base64 = self.screenshot.capture_screenshot()?;
-                json!([{
-                    "type": "image",
-                    "source": {
-                        "type": "base64",
-                        "media_type": "image/jpeg",
-                        "data": screenshot_base64
-                    }
-                }])
+                self.settle(delay_ms).await?;
+
+                self.action_result()?
             }
             "mouse_move" => {
                 let coord = input["coordinate"]
@@ -516,21 +1027,10 @@ impl ComputerUseAgent {
                 let x = (coord[0].as_f64().context("Invalid x coordinate")? * scale_back) as i32;
                 let y = (coord[1].as_f64().context("Invalid y coordinate")? * scale_back) as i32;
 
-                self.automation.execute_action(Action::Click {
-                    x,
-                    y,
-                    button: MouseButton::Left,
-                })?;
+                tracing::info!("Moving mouse to ({}, {})", x, y);
+                self.backend().execute_action(Action::MouseMove { x, y })?;
 
-                let screenshot_base64 = self.screenshot.capture_screenshot()?;
-                json!([{
-                    "type": "image",
-                    "source": {
-                        "type": "base64",
-                        "media_type": "image/jpeg",
-                        "data": screenshot_base64
-                    }
-                }])
+                self.action_result()?
             }
             "scroll" => {
                 let coord = input["coordinate"]
@@ -542,22 +1042,14 @@ impl ComputerUseAgent {
                 let scroll_x = input["scroll_x"].as_i64().unwrap_or(0) as i32;
                 let scroll_y = input["scroll_y"].as_i64().unwrap_or(0) as i32;
 
-                self.automation.execute_action(Action::Scroll {
+                self.backend().execute_action(Action::Scroll {
                     x,
                     y,
                     scroll_x,
                     scroll_y,
                 })?;
 
-                let screenshot_base64 = self.screenshot.capture_screenshot()?;
-                json!([{
-                    "type": "image",
-                    "source": {
-                        "type": "base64",
-                        "media_type": "image/jpeg",
-                        "data": screenshot_base64
-                    }
-                }])
+                self.action_result()?
             }
             "left_click_drag" => {
                 let start_coord = input["start_coordinate"]
@@ -573,29 +1065,21 @@ impl ComputerUseAgent {
                 let end_x = (end_coord[0].as_f64().context("Invalid end x")? * scale_back) as i32;
                 let end_y = (end_coord[1].as_f64().context("Invalid end y")? * scale_back) as i32;
 
-                self.automation.execute_action(Action::Click {
+                self.backend().execute_action(Action::Click {
                     x: start_x,
                     y: start_y,
                     button: MouseButton::Left,
                 })?;
 
-                std::thread::sleep(std::time::Duration::from_millis(100));
+                self.settle(100).await?;
 
-                self.automation.execute_action(Action::Click {
+                self.backend().execute_action(Action::Click {
                     x: end_x,
                     y: end_y,
                     button: MouseButton::Left,
                 })?;
 
-                let screenshot_base64 = self.screenshot.capture_screenshot()?;
-                json!([{
-                    "type": "image",
-                    "source": {
-                        "type": "base64",
-                        "media_type": "image/jpeg",
-                        "data": screenshot_base64
-                    }
-                }])
+                self.action_result()?
             }
             "wait" => {
                 let duration_secs = input["duration_seconds"]
@@ -608,18 +1092,16 @@ impl ComputerUseAgent {
                     "Wait action used ({}ms) - this is usually unnecessary",
                     duration_ms
                 );
-                self.automation
-                    .execute_action(Action::Wait { duration_ms })?;
-
-                let screenshot_base64 = self.screenshot.capture_screenshot()?;
-                json!([{
-                    "type": "image",
-                    "source": {
-                        "type": "base64",
-                        "media_type": "image/jpeg",
-                        "data": screenshot_base64
-                    }
-                }])
+                if self.simulated.is_some() {
+                    self.backend()
+                        .execute_action(Action::Wait { duration_ms })?;
+                } else {
+                    self.cancel
+                        .sleep(Duration::from_millis(duration_ms))
+                        .await?;
+                }
+
+                self.action_result()?
             }
             _ => {
                 anyhow::bail!("Unknown action: {}", action);
@@ -629,6 +1111,136 @@ impl ComputerUseAgent {
         Ok(result)
     }
 
+    fn backend(&mut self) -> &mut dyn AutomationBackend {
+        match self.simulated.as_mut() {
+            Some(simulated) => simulated,
+            None => &mut self.automation,
+        }
+    }
+
+    async fn settle(&self, delay_ms: u64) -> Result<()> {
+        if self.simulated.is_none() {
+            self.cancel.sleep(Duration::from_millis(delay_ms)).await?;
+        }
+        Ok(())
+    }
+
+    fn secrets_prompt(&self) -> Option<String> {
+        let names = match secrets::typeable_secret_names(self.secrets.as_deref()?) {
+            Ok(names) => names,
+            Err(e) => {
+                tracing::warn!("Failed to list secrets: {:#}", e);
+                return None;
+            }
+        };
+        if names.is_empty() {
+            return None;
+        }
+
+        let placeholders: Vec<String> = names
+            .iter()
+            .map(|name| secrets::placeholder(name))
+            .collect();
+        Some(format!(
+            "\n\nSECRETS: These stored secrets can be typed without you seeing them: {}. \
+             To enter one, put its placeholder in the text of a type action, exactly as written; \
+             it is replaced with the real value as it is typed. Never ask the user for these values \
+             and never try to read them back from the screen.",
+            placeholders.join(", ")
+        ))
+    }
+
+    fn substitute_secrets(&self, text: &str) -> Result<String> {
+        if self.simulated.is_some() || secrets::placeholder_names(text).is_empty() {
+            return Ok(text.to_string());
+        }
+        let store = self.secrets.as_deref().context(
+            "Secret placeholders are not available because no secret store is configured",
+        )?;
+        secrets::substitute_placeholders(text, store)
+    }
+
+    async fn type_text(&mut self, text: &str) -> Result<usize> {
+        let chars: Vec<char> = text.chars().collect();
+
+        if self.simulated.is_some() {
+            self.backend().execute_action(Action::Type {
+                text: text.to_string(),
+            })?;
+            return Ok(chars.len());
+        }
+
+        let mut typed = 0;
+        for chunk in chars.chunks(TYPE_CHUNK_CHARS) {
+            if self.cancel.wait_if_paused().await? {
+                return Ok(typed);
+            }
+            self.automation.execute_action(Action::Type {
+                text: chunk.iter().collect(),
+            })?;
+            typed += chunk.len();
+        }
+        Ok(typed)
+    }
+
+    fn emit(&self, event: AgentEvent) {
+        if let Some(events) = &self.events {
+            events.emit(event);
+        }
+    }
+
+    fn action_point(&self, tool: &str, input: &Value) -> Option<(i32, i32)> {
+        if tool != "computer" {
+            return None;
+        }
+        let coord = input["coordinate"].as_array()?;
+        let (display_width, display_height) = self.screenshot.get_display_size();
+        let scale_back = 1.0 / calculate_scale_factor(display_width, display_height);
+        Some((
+            (coord.first()?.as_f64()? * scale_back) as i32,
+            (coord.get(1)?.as_f64()? * scale_back) as i32,
+        ))
+    }
+
+    fn resume_content(&self, note: &str) -> Result<Value> {
+        let mut content = vec![json!({ "type": "text", "text": note })];
+        if self.simulated.is_none() {
+            if let Value::Array(screenshot) = self.screenshot_content()? {
+                content.extend(screenshot);
+            }
+        }
+        Ok(Value::Array(content))
+    }
+
+    fn screenshot_content(&self) -> Result<Value> {
+        let screenshot = self.screenshot.capture_screenshot()?;
+        let mut content = vec![json!({
+            "type": "image",
+            "source": {
+                "type": "base64",
+                "media_type": "image/jpeg",
+                "data": screenshot.data
+            }
+        })];
+        if let Some(note) = screenshot.redaction.describe() {
+            content.push(json!({ "type": "text", "text": note }));
+        }
+        Ok(json!(content))
+    }
+
+    fn action_result(&self) -> Result<Value> {
+        match &self.simulated {
+            Some(simulated) => Ok(json!([{
+                "type": "text",
+                "text": format!(
+                    "[dry run] Simulated: {}. The screen was not changed.",
+                    simulated.steps().last().map(String::as_str).unwrap_or("action")
+                )
+            }])),
+            None => self.screenshot_content(),
+        }
+    }
+
     fn parse_key_combination(&self, key_str: &str) -> Result<Vec<String>> {
         let parts: Vec<&str> = key_str.split('+').map(|s| s.trim()).collect();
         let mut keys = Vec::new();
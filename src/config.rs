@@ -1,45 +1,603 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use crate::hotkey::{HotkeyBindings, HotkeyConfig, ScreenCorner};
+use crate::redact;
+use crate::secrets::{self, SecretsBackend};
+
+const DEFAULT_CONVERSATION_TIMEOUT_SECS: u64 = 300;
+const DEFAULT_HTTP_PORT: u16 = 7717;
+const MAX_SCREEN_EXTENT: u32 = 16_384;
+
+pub const DEFAULT_MODEL: &str = "claude-sonnet-4-5";
+pub const DEFAULT_API_URL: &str = "https://api.anthropic.com/v1/messages";
+pub const DEFAULT_SCREENSHOT_QUALITY: u8 = 40;
+
+pub const CONFIG_PATH_ENV: &str = "SUPERCTRL_CONFIG";
+
+pub const ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("SUPERCTRL_MODEL", "model"),
+    ("SUPERCTRL_API_URL", "api_url"),
+    ("SUPERCTRL_SCREENSHOT_QUALITY", "screenshot_quality"),
+    ("SUPERCTRL_LEARNING_ENABLED", "learning_enabled"),
+    ("SUPERCTRL_STOP_HOTKEY", "hotkeys.stop"),
+    ("SUPERCTRL_PAUSE_HOTKEY", "hotkeys.pause"),
+    ("SUPERCTRL_REPEAT_LAST_HOTKEY", "hotkeys.repeat_last"),
+    ("SUPERCTRL_QUICK_COMMAND_HOTKEY", "hotkeys.quick_command"),
+    ("SUPERCTRL_LEARNING_DB_PATH", "paths.learning_db"),
+    ("SUPERCTRL_HISTORY_DB_PATH", "paths.history_db"),
+    ("SUPERCTRL_SYSTEM_PROMPT_PATH", "paths.system_prompt"),
+    ("SUPERCTRL_MCP_CONFIG", "paths.mcp_config"),
+    ("SUPERCTRL_ROUTINES_PATH", "paths.routines"),
+    ("SUPERCTRL_COMMAND_HISTORY_PATH", "paths.command_history"),
+    (
+        "SUPERCTRL_AUTO_PAUSE_ON_INPUT",
+        "policy.auto_pause_on_input",
+    ),
+    ("SUPERCTRL_FAILSAFE_CORNER", "policy.failsafe_corner"),
+    (
+        "SUPERCTRL_CONVERSATION_TIMEOUT_SECS",
+        "policy.conversation_timeout_secs",
+    ),
+    ("SUPERCTRL_SECRETS_BACKEND", "secrets.backend"),
+    ("SUPERCTRL_SECRETS_FILE", "secrets.file"),
+    ("SUPERCTRL_HTTP_ENABLED", "http.enabled"),
+    ("SUPERCTRL_HTTP_PORT", "http.port"),
+    ("SUPERCTRL_HTTP_ALLOWED_ORIGINS", "http.allowed_origins"),
+];
+
+const DISABLE_CLIPBOARD_ENV: &str = "SUPERCTRL_DISABLE_CLIPBOARD_MONITORING";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    pub command_received: bool,
+    pub command_completed: bool,
+    pub command_failed: bool,
+    pub task_stopped: bool,
+    pub auto_paused: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            command_received: true,
+            command_completed: true,
+            command_failed: true,
+            task_stopped: true,
+            auto_paused: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PathSettings {
+    pub learning_db: PathBuf,
+    pub history_db: PathBuf,
+    pub system_prompt: PathBuf,
+    pub mcp_config: PathBuf,
+    pub routines: PathBuf,
+    pub command_history: PathBuf,
+}
+
+impl Default for PathSettings {
+    fn default() -> Self {
+        Self {
+            learning_db: PathBuf::from("~/.superctrl/learning.db"),
+            history_db: PathBuf::from("~/.superctrl/history.db"),
+            system_prompt: PathBuf::from("~/.superctrl/system_prompt.txt"),
+            mcp_config: PathBuf::from("~/.superctrl/mcp.json"),
+            routines: PathBuf::from("~/.superctrl/routines.json"),
+            command_history: PathBuf::from("~/.superctrl/command_history.json"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PolicySettings {
+    pub auto_pause_on_input: bool,
+    #[serde(with = "corner")]
+    pub failsafe_corner: Option<ScreenCorner>,
+    pub conversation_timeout_secs: u64,
+}
+
+impl Default for PolicySettings {
+    fn default() -> Self {
+        Self {
+            auto_pause_on_input: false,
+            failsafe_corner: None,
+            conversation_timeout_secs: DEFAULT_CONVERSATION_TIMEOUT_SECS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecretsSettings {
+    pub backend: SecretsBackend,
+    pub file: PathBuf,
+}
+
+impl Default for SecretsSettings {
+    fn default() -> Self {
+        Self {
+            backend: SecretsBackend::Auto,
+            file: PathBuf::from("~/.superctrl/secrets.json"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub allowed_origins: Vec<String>,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_HTTP_PORT,
+            allowed_origins: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScreenRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionSettings {
+    pub regions: Vec<ScreenRegion>,
+    pub denylist_apps: Vec<String>,
+    pub denylist_titles: Vec<String>,
+    pub blur_text: bool,
+}
+
+mod corner {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::hotkey::ScreenCorner;
+
+    pub fn serialize<S: Serializer>(
+        corner: &Option<ScreenCorner>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match corner {
+            Some(corner) => serializer.serialize_str(&corner.to_string()),
+            None => serializer.serialize_str("off"),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<ScreenCorner>, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        let value = value.trim();
+        if value.is_empty() || value.eq_ignore_ascii_case("off") {
+            return Ok(None);
+        }
+        value.parse().map(Some).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub model: String,
+    pub api_url: String,
+    pub screenshot_quality: u8,
+    pub learning_enabled: bool,
+    pub clipboard_monitoring: bool,
+    pub hotkeys: HotkeyConfig,
+    pub notifications: NotificationSettings,
+    pub paths: PathSettings,
+    pub policy: PolicySettings,
+    pub secrets: SecretsSettings,
+    pub redaction: RedactionSettings,
+    pub http: HttpSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            model: DEFAULT_MODEL.to_string(),
+            api_url: DEFAULT_API_URL.to_string(),
+            screenshot_quality: DEFAULT_SCREENSHOT_QUALITY,
+            learning_enabled: true,
+            clipboard_monitoring: true,
+            hotkeys: HotkeyConfig::default(),
+            notifications: NotificationSettings::default(),
+            paths: PathSettings::default(),
+            policy: PolicySettings::default(),
+            secrets: SecretsSettings::default(),
+            redaction: RedactionSettings::default(),
+            http: HttpSettings::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigSources {
+    pub path: PathBuf,
+    pub overrides: Vec<String>,
+}
+
+impl ConfigSources {
+    pub fn new(path: Option<PathBuf>, overrides: Vec<String>) -> Result<Self> {
+        let path = match path.or_else(|| std::env::var_os(CONFIG_PATH_ENV).map(PathBuf::from)) {
+            Some(path) => path,
+            None => Settings::default_path()?,
+        };
+        Ok(Self { path, overrides })
+    }
+}
+
+impl Settings {
+    pub fn default_path() -> Result<PathBuf> {
+        let home_dir = dirs::home_dir().context("Failed to determine home directory")?;
+        Ok(home_dir.join(".config/superctrl/config.toml"))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read settings from {:?}", path))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse settings from {:?}", path))
+    }
+
+    pub fn load_layered(
+        sources: &ConfigSources,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        let mut settings = Self::load(&sources.path)?;
+        settings.apply_env(env)?;
+        settings.apply_overrides(&sources.overrides)?;
+        Ok(settings)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Err(errors) = self.validate() {
+            anyhow::bail!("Invalid settings: {}", errors.join("; "));
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("Failed to create settings directory")?;
+        }
+
+        let content = toml::to_string_pretty(self).context("Failed to serialize settings")?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write settings to {:?}", path))
+    }
+
+    pub fn set_in_file(path: &Path, key: &str, raw: &str) -> Result<Self> {
+        let mut table = if path.exists() {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read settings from {:?}", path))?;
+            toml::from_str::<toml::Table>(&content)
+                .with_context(|| format!("Failed to parse settings from {:?}", path))?
+        } else {
+            toml::Table::new()
+        };
+
+        insert_key(&mut table, key, parse_setting(key, raw)?)?;
+        let settings: Self = toml::Value::Table(table.clone())
+            .try_into()
+            .with_context(|| format!("Invalid value for '{}'", key))?;
+        if let Err(errors) = settings.validate() {
+            anyhow::bail!("Invalid settings: {}", errors.join("; "));
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("Failed to create settings directory")?;
+        }
+        let content = toml::to_string_pretty(&table).context("Failed to serialize settings")?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write settings to {:?}", path))?;
+        Ok(settings)
+    }
+
+    pub fn get(&self, key: &str) -> Result<toml::Value> {
+        let root = toml::Value::try_from(self).context("Failed to serialize settings")?;
+        key.split('.')
+            .try_fold(&root, |value, part| value.get(part))
+            .cloned()
+            .with_context(|| format!("Unknown setting '{}'", key))
+    }
+
+    pub fn set(&mut self, key: &str, raw: &str) -> Result<()> {
+        let value = parse_setting(key, raw)?;
+        let toml::Value::Table(mut table) =
+            toml::Value::try_from(&*self).context("Failed to serialize settings")?
+        else {
+            anyhow::bail!("Settings did not serialize to a table");
+        };
+        insert_key(&mut table, key, value)?;
+        *self = toml::Value::Table(table)
+            .try_into()
+            .with_context(|| format!("Invalid value for '{}'", key))?;
+        Ok(())
+    }
+
+    pub fn apply_env(&mut self, env: impl Fn(&str) -> Option<String>) -> Result<()> {
+        for (var, key) in ENV_OVERRIDES {
+            if let Some(value) = env(var) {
+                self.set(key, &value)
+                    .with_context(|| format!("Invalid {}", var))?;
+            }
+        }
+
+        if let Some(value) = env(DISABLE_CLIPBOARD_ENV) {
+            self.clipboard_monitoring = !parse_bool(&value)
+                .with_context(|| format!("Invalid {}", DISABLE_CLIPBOARD_ENV))?;
+        }
+        Ok(())
+    }
+
+    pub fn apply_overrides(&mut self, overrides: &[String]) -> Result<()> {
+        for assignment in overrides {
+            let (key, value) = assignment
+                .split_once('=')
+                .with_context(|| format!("Expected KEY=VALUE, got '{}'", assignment))?;
+            self.set(key.trim(), value)?;
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.model.trim().is_empty() {
+            errors.push("Model must not be empty".to_string());
+        }
+
+        match reqwest::Url::parse(self.api_url.trim()) {
+            Ok(url) if url.scheme() == "https" || url.scheme() == "http" => {}
+            Ok(url) => errors.push(format!(
+                "API endpoint must use http or https, not '{}'",
+                url.scheme()
+            )),
+            Err(e) => errors.push(format!("API endpoint is not a valid URL: {}", e)),
+        }
+
+        if !(1..=100).contains(&self.screenshot_quality) {
+            errors.push(format!(
+                "Screenshot quality must be between 1 and 100, got {}",
+                self.screenshot_quality
+            ));
+        }
+
+        if let Err(e) = HotkeyBindings::from_config(&self.hotkeys) {
+            errors.push(format!("{:#}", e));
+        }
+
+        let paths = [
+            ("learning_db", &self.paths.learning_db),
+            ("history_db", &self.paths.history_db),
+            ("system_prompt", &self.paths.system_prompt),
+            ("mcp_config", &self.paths.mcp_config),
+            ("routines", &self.paths.routines),
+            ("command_history", &self.paths.command_history),
+        ];
+        for (name, path) in paths {
+            if path.as_os_str().is_empty() {
+                errors.push(format!("paths.{} must not be empty", name));
+            }
+        }
+
+        if self.secrets.backend == SecretsBackend::Keychain && !cfg!(target_os = "macos") {
+            errors.push("secrets.backend = \"keychain\" is only available on macOS".to_string());
+        }
+        if self.secrets.file.as_os_str().is_empty() {
+            errors.push("secrets.file must not be empty".to_string());
+        }
+
+        for (index, region) in self.redaction.regions.iter().enumerate() {
+            if region.width == 0 || region.height == 0 {
+                errors.push(format!(
+                    "redaction.regions[{}] must have a non-zero width and height",
+                    index
+                ));
+            }
+            let right = region.x.checked_add(region.width);
+            let bottom = region.y.checked_add(region.height);
+            if !matches!((right, bottom), (Some(right), Some(bottom))
+                if right <= MAX_SCREEN_EXTENT && bottom <= MAX_SCREEN_EXTENT)
+            {
+                errors.push(format!(
+                    "redaction.regions[{}] must lie within {} x {} screen points",
+                    index, MAX_SCREEN_EXTENT, MAX_SCREEN_EXTENT
+                ));
+            }
+        }
+        let denylists = [
+            ("denylist_apps", &self.redaction.denylist_apps),
+            ("denylist_titles", &self.redaction.denylist_titles),
+        ];
+        for (name, entries) in denylists {
+            if entries.iter().any(|entry| entry.trim().is_empty()) {
+                errors.push(format!("redaction.{} must not contain empty entries", name));
+            }
+        }
+
+        if self.http.port == 0 {
+            errors.push("http.port must not be 0".to_string());
+        }
+        for origin in &self.http.allowed_origins {
+            let valid = reqwest::Url::parse(origin).is_ok_and(|url| {
+                matches!(url.scheme(), "http" | "https")
+                    && url.origin().ascii_serialization() == origin.trim_end_matches('/')
+            });
+            if !valid {
+                errors.push(format!(
+                    "http.allowed_origins entry '{}' must be an origin such as http://localhost:3000",
+                    origin
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LiveSettings {
+    current: Arc<RwLock<Arc<Settings>>>,
+}
+
+impl LiveSettings {
+    pub fn new(settings: Settings) -> Self {
+        Self {
+            current: Arc::new(RwLock::new(Arc::new(settings))),
+        }
+    }
+
+    pub fn get(&self) -> Arc<Settings> {
+        self.current.read().unwrap().clone()
+    }
+
+    pub fn replace(&self, settings: Settings) -> Arc<Settings> {
+        std::mem::replace(&mut *self.current.write().unwrap(), Arc::new(settings))
+    }
+}
+
+fn parse_setting(key: &str, raw: &str) -> Result<toml::Value> {
+    match Settings::default().get(key)? {
+        toml::Value::Boolean(_) => parse_bool(raw).map(toml::Value::Boolean),
+        toml::Value::Integer(_) => raw
+            .trim()
+            .parse()
+            .map(toml::Value::Integer)
+            .with_context(|| format!("'{}' expects a whole number, got '{}'", key, raw)),
+        toml::Value::String(_) => Ok(toml::Value::String(raw.to_string())),
+        toml::Value::Array(_) => {
+            let mut parsed: toml::Table = toml::from_str(&format!("value = {}", raw))
+                .with_context(|| format!("'{}' expects a TOML array, got '{}'", key, raw))?;
+            match parsed.remove("value") {
+                Some(value @ toml::Value::Array(_)) => Ok(value),
+                _ => anyhow::bail!("'{}' expects a TOML array, got '{}'", key, raw),
+            }
+        }
+        toml::Value::Table(_) => {
+            anyhow::bail!("'{}' is a section; set one of its keys instead", key)
+        }
+        other => anyhow::bail!("'{}' has unsupported type {}", key, other.type_str()),
+    }
+}
+
+fn parse_bool(raw: &str) -> Result<bool> {
+    match raw.trim().to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" => Ok(false),
+        other => anyhow::bail!("Expected true or false, got '{}'", other),
+    }
+}
+
+fn insert_key(table: &mut toml::Table, key: &str, value: toml::Value) -> Result<()> {
+    let (parents, last) = match key.rsplit_once('.') {
+        Some((parents, last)) => (Some(parents), last),
+        None => (None, key),
+    };
+
+    let mut table = table;
+    for part in parents.into_iter().flat_map(|parents| parents.split('.')) {
+        table = table
+            .entry(part)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .with_context(|| format!("'{}' is not a section", part))?;
+    }
+    table.insert(last.to_string(), value);
+    Ok(())
+}
+
+pub fn expand_home(path: &Path) -> Result<PathBuf> {
+    match path.strip_prefix("~") {
+        Ok(rest) => {
+            let home_dir = dirs::home_dir().context("Failed to determine home directory")?;
+            Ok(home_dir.join(rest))
+        }
+        Err(_) => Ok(path.to_path_buf()),
+    }
+}
+
+pub fn api_key_from_env() -> Option<String> {
+    std::env::var("ANTHROPIC_API_KEY")
+        .ok()
+        .filter(|key| !key.is_empty())
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub api_key: String,
+    pub settings: Settings,
+    pub settings_path: PathBuf,
     pub learning_enabled: bool,
     pub learning_db_path: PathBuf,
+    pub history_db_path: PathBuf,
     pub system_prompt_path: PathBuf,
     pub disable_clipboard_monitoring: bool,
+    pub mcp_config_path: PathBuf,
+    pub routines_path: PathBuf,
+    pub command_history_path: PathBuf,
 }
 
 impl Config {
-    pub fn load() -> Result<Self> {
-        let api_key = std::env::var("ANTHROPIC_API_KEY")
-            .context("ANTHROPIC_API_KEY environment variable not set")?;
-
-        if api_key.is_empty() {
-            anyhow::bail!("ANTHROPIC_API_KEY environment variable is empty");
+    pub fn load(sources: &ConfigSources) -> Result<Self> {
+        let settings = Settings::load_layered(sources, |var| std::env::var(var).ok())?;
+        if let Err(errors) = settings.validate() {
+            anyhow::bail!(
+                "Invalid settings in {:?}: {}",
+                sources.path,
+                errors.join("; ")
+            );
         }
 
-        let learning_enabled = std::env::var("SUPERCTRL_LEARNING_ENABLED")
-            .map(|v| v.to_lowercase() == "true" || v == "1")
-            .unwrap_or(true);
-
-        let disable_clipboard_monitoring = std::env::var("SUPERCTRL_DISABLE_CLIPBOARD_MONITORING")
-            .map(|v| v.to_lowercase() == "true" || v == "1")
-            .unwrap_or(false);
-
-        let home_dir = dirs::home_dir().context("Failed to determine home directory")?;
-
-        let superctrl_dir = home_dir.join(".superctrl");
-        let learning_db_path = superctrl_dir.join("learning.db");
-        let system_prompt_path = superctrl_dir.join("system_prompt.txt");
+        let api_key = match api_key_from_env() {
+            Some(api_key) => api_key,
+            None => secrets::open_store(&settings.secrets)?
+                .get(secrets::API_KEY_SECRET)?
+                .context(
+                    "No Anthropic API key found. Run `superctrl auth login` or set ANTHROPIC_API_KEY",
+                )?,
+        };
+        redact::register_secret(&api_key);
 
+        let paths = settings.paths.clone();
         Ok(Config {
             api_key,
-            learning_enabled,
-            learning_db_path,
-            system_prompt_path,
-            disable_clipboard_monitoring,
+            settings_path: sources.path.clone(),
+            learning_enabled: settings.learning_enabled,
+            learning_db_path: expand_home(&paths.learning_db)?,
+            history_db_path: expand_home(&paths.history_db)?,
+            system_prompt_path: expand_home(&paths.system_prompt)?,
+            disable_clipboard_monitoring: !settings.clipboard_monitoring,
+            mcp_config_path: expand_home(&paths.mcp_config)?,
+            routines_path: expand_home(&paths.routines)?,
+            command_history_path: expand_home(&paths.command_history)?,
+            settings,
         })
     }
 }
@@ -0,0 +1,313 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::history::TaskStatus;
+use crate::task::ControlSource;
+
+const EVENT_CAPACITY: usize = 256;
+const MAX_TRANSCRIPT_CHARS: usize = 20_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentEvent {
+    TaskStarted {
+        command: String,
+        max_steps: usize,
+    },
+    Step {
+        step: usize,
+        max_steps: usize,
+    },
+    AssistantText(String),
+    ToolUse {
+        tool: String,
+        action: Option<String>,
+        point: Option<(i32, i32)>,
+    },
+    Screenshot(Arc<Vec<u8>>),
+    TaskFinished {
+        status: TaskStatus,
+        message: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LiveView {
+    pub command: Option<String>,
+    pub status: Option<TaskStatus>,
+    pub step: usize,
+    pub max_steps: usize,
+    pub transcript: String,
+    pub last_action: Option<String>,
+    pub last_click: Option<(i32, i32)>,
+    pub screenshot: Option<Arc<Vec<u8>>>,
+    pub outcome: Option<String>,
+}
+
+impl LiveView {
+    pub fn apply(&mut self, event: &AgentEvent) {
+        match event {
+            AgentEvent::TaskStarted { command, max_steps } => {
+                *self = Self {
+                    command: Some(command.clone()),
+                    status: Some(TaskStatus::Running),
+                    max_steps: *max_steps,
+                    ..Self::default()
+                };
+            }
+            AgentEvent::Step { step, max_steps } => {
+                self.step = *step;
+                self.max_steps = *max_steps;
+            }
+            AgentEvent::AssistantText(text) => {
+                if !self.transcript.is_empty() {
+                    self.transcript.push_str("\n\n");
+                }
+                self.transcript.push_str(text);
+
+                let excess = self
+                    .transcript
+                    .chars()
+                    .count()
+                    .saturating_sub(MAX_TRANSCRIPT_CHARS);
+                if excess > 0 {
+                    self.transcript = self.transcript.chars().skip(excess).collect();
+                }
+            }
+            AgentEvent::ToolUse {
+                tool,
+                action,
+                point,
+            } => {
+                self.last_action = Some(match action {
+                    Some(action) => format!("{}: {}", tool, action),
+                    None => tool.clone(),
+                });
+                if point.is_some() {
+                    self.last_click = *point;
+                }
+            }
+            AgentEvent::Screenshot(data) => self.screenshot = Some(data.clone()),
+            AgentEvent::TaskFinished { status, message } => {
+                self.status = Some(*status);
+                self.outcome = message.clone();
+            }
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.status == Some(TaskStatus::Running)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DaemonEvent {
+    TaskQueued {
+        command: String,
+        mode: String,
+    },
+    TaskStarted {
+        command: String,
+        max_steps: usize,
+    },
+    TaskStep {
+        command: String,
+        step: usize,
+        max_steps: usize,
+    },
+    TaskCompleted {
+        command: String,
+    },
+    TaskFailed {
+        command: String,
+        error: String,
+    },
+    TaskStopped {
+        command: String,
+    },
+    Stop {
+        source: ControlSource,
+        tasks: usize,
+    },
+    Paused {
+        source: ControlSource,
+        tasks: usize,
+    },
+    Resumed {
+        source: ControlSource,
+        tasks: usize,
+    },
+    LearningStarted,
+    LearningStopped,
+    ConfigReloaded {
+        changed: Vec<String>,
+        restart_required: Vec<String>,
+    },
+    ConfigReloadFailed {
+        error: String,
+    },
+}
+
+impl DaemonEvent {
+    pub fn describe(&self) -> String {
+        match self {
+            DaemonEvent::TaskQueued { command, mode } => {
+                format!("Task queued ({}): {}", mode, command)
+            }
+            DaemonEvent::TaskStarted { command, max_steps } => {
+                format!("Task started (max {} steps): {}", max_steps, command)
+            }
+            DaemonEvent::TaskStep {
+                command,
+                step,
+                max_steps,
+            } => format!("Step {}/{}: {}", step, max_steps, command),
+            DaemonEvent::TaskCompleted { command } => format!("Task completed: {}", command),
+            DaemonEvent::TaskFailed { command, error } => {
+                format!("Task failed: {} ({})", command, error)
+            }
+            DaemonEvent::TaskStopped { command } => format!("Task stopped: {}", command),
+            DaemonEvent::Stop { source, tasks } => {
+                format!("Stop via {} ({} running task(s))", source, tasks)
+            }
+            DaemonEvent::Paused { source, tasks } => {
+                format!("Paused via {} ({} running task(s))", source, tasks)
+            }
+            DaemonEvent::Resumed { source, tasks } => {
+                format!("Resumed via {} ({} running task(s))", source, tasks)
+            }
+            DaemonEvent::LearningStarted => "Learning started".to_string(),
+            DaemonEvent::LearningStopped => "Learning stopped".to_string(),
+            DaemonEvent::ConfigReloaded {
+                changed,
+                restart_required,
+            } => {
+                let mut text = if changed.is_empty() {
+                    "Config reloaded (no changes)".to_string()
+                } else {
+                    format!("Config reloaded: {}", changed.join(", "))
+                };
+                if !restart_required.is_empty() {
+                    text.push_str(&format!(
+                        "; restart required for {}",
+                        restart_required.join(", ")
+                    ));
+                }
+                text
+            }
+            DaemonEvent::ConfigReloadFailed { error } => {
+                format!("Config reload failed: {}", error)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimedEvent {
+    pub at: DateTime<Local>,
+    #[serde(flatten)]
+    pub event: DaemonEvent,
+}
+
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<TimedEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CAPACITY);
+        Self { sender }
+    }
+
+    pub fn publish(&self, event: DaemonEvent) {
+        tracing::debug!("Daemon event: {:?}", event);
+        let _ = self.sender.send(TimedEvent {
+            at: Local::now(),
+            event,
+        });
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<TimedEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone)]
+pub struct AgentEvents {
+    sender: broadcast::Sender<AgentEvent>,
+    current: Arc<Mutex<LiveView>>,
+    bus: EventBus,
+}
+
+impl AgentEvents {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CAPACITY);
+        Self {
+            sender,
+            current: Arc::new(Mutex::new(LiveView::default())),
+            bus: EventBus::new(),
+        }
+    }
+
+    pub fn with_bus(mut self, bus: EventBus) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    pub fn emit(&self, event: AgentEvent) {
+        let mut current = self.current.lock().unwrap();
+        current.apply(&event);
+        let command = current.command.clone().unwrap_or_default();
+        let daemon_event = match &event {
+            AgentEvent::TaskStarted { command, max_steps } => Some(DaemonEvent::TaskStarted {
+                command: command.clone(),
+                max_steps: *max_steps,
+            }),
+            AgentEvent::Step { step, max_steps } => Some(DaemonEvent::TaskStep {
+                command,
+                step: *step,
+                max_steps: *max_steps,
+            }),
+            AgentEvent::TaskFinished { status, message } => match status {
+                TaskStatus::Running => None,
+                TaskStatus::Completed => Some(DaemonEvent::TaskCompleted { command }),
+                TaskStatus::Stopped => Some(DaemonEvent::TaskStopped { command }),
+                TaskStatus::Failed => Some(DaemonEvent::TaskFailed {
+                    command,
+                    error: message.clone().unwrap_or_default(),
+                }),
+            },
+            _ => None,
+        };
+        let _ = self.sender.send(event);
+        drop(current);
+
+        if let Some(daemon_event) = daemon_event {
+            self.bus.publish(daemon_event);
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<AgentEvent> {
+        self.sender.subscribe()
+    }
+
+    pub fn current(&self) -> LiveView {
+        self.current.lock().unwrap().clone()
+    }
+}
+
+impl Default for AgentEvents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
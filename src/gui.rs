@@ -1,9 +1,13 @@
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc, Mutex,
-};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::events::{AgentEvents, EventBus};
+use crate::hotkey::HotkeyBindings;
+use crate::reload::ReloadStatus;
+use crate::status::{ApiStatus, ErrorRecord};
+use crate::task::{ControlSource, TaskRunner};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AppState {
@@ -60,18 +64,39 @@ pub struct GuiState {
     pub app_state: AppState,
     pub action_history: Vec<ActionRecord>,
     pub max_history: usize,
-    pub stop_flag: Arc<AtomicBool>,
+    pub tasks: TaskRunner,
     pub learning_enabled: bool,
+    pub hotkeys: HotkeyBindings,
+    pub last_command: Option<String>,
+    pub agent_events: AgentEvents,
+    pub event_bus: EventBus,
+    pub settings_path: Option<PathBuf>,
+    pub config_reload: Option<ReloadStatus>,
+    pub started_at: DateTime<Local>,
+    pub last_error: Option<ErrorRecord>,
+    pub hotkey_error: Option<String>,
+    pub api_status: Option<ApiStatus>,
 }
 
 impl Default for GuiState {
     fn default() -> Self {
+        let event_bus = EventBus::new();
         Self {
             app_state: AppState::Idle,
             action_history: Vec::new(),
             max_history: 5,
-            stop_flag: Arc::new(AtomicBool::new(false)),
+            tasks: TaskRunner::new().with_events(event_bus.clone()),
             learning_enabled: true,
+            hotkeys: HotkeyBindings::default(),
+            last_command: None,
+            agent_events: AgentEvents::new().with_bus(event_bus.clone()),
+            event_bus,
+            settings_path: None,
+            config_reload: None,
+            started_at: Local::now(),
+            last_error: None,
+            hotkey_error: None,
+            api_status: None,
         }
     }
 }
@@ -85,6 +110,11 @@ impl GuiState {
         self.app_state = state;
     }
 
+    pub fn record_error(&mut self, message: String) {
+        self.last_error = Some(ErrorRecord::now(message.clone()));
+        self.app_state = AppState::Error(message);
+    }
+
     pub fn add_action(&mut self, action: ActionRecord) {
         self.action_history.push(action);
         if self.action_history.len() > self.max_history {
@@ -101,13 +131,24 @@ impl GuiState {
             .collect()
     }
 
-    pub fn trigger_stop(&self) {
-        self.stop_flag.store(true, Ordering::Release);
-        tracing::info!("Emergency stop flag set");
+    pub fn trigger_stop(&self, source: ControlSource) -> usize {
+        self.tasks.stop(source)
+    }
+
+    pub fn trigger_pause(&self, source: ControlSource) -> usize {
+        self.tasks.pause(source)
+    }
+
+    pub fn trigger_resume(&self, source: ControlSource) -> usize {
+        self.tasks.resume(source)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.tasks.is_paused()
     }
 
-    pub fn get_stop_flag(&self) -> Arc<AtomicBool> {
-        self.stop_flag.clone()
+    pub fn task_runner(&self) -> TaskRunner {
+        self.tasks.clone()
     }
 
     pub fn is_learning_enabled(&self) -> bool {
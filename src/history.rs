@@ -0,0 +1,377 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, Local, TimeZone};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const MAX_TASKS: i64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Running,
+    Completed,
+    Failed,
+    Stopped,
+}
+
+impl TaskStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Running => "running",
+            TaskStatus::Completed => "completed",
+            TaskStatus::Failed => "failed",
+            TaskStatus::Stopped => "stopped",
+        }
+    }
+}
+
+impl std::fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for TaskStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "running" => Ok(TaskStatus::Running),
+            "completed" => Ok(TaskStatus::Completed),
+            "failed" => Ok(TaskStatus::Failed),
+            "stopped" => Ok(TaskStatus::Stopped),
+            other => anyhow::bail!("Unknown task status '{}'", other),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskRecord {
+    pub id: i64,
+    pub command: String,
+    pub mode: String,
+    pub status: TaskStatus,
+    pub started_at: DateTime<Local>,
+    pub finished_at: Option<DateTime<Local>>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub result: Option<String>,
+    pub error: Option<String>,
+}
+
+impl TaskRecord {
+    pub fn duration(&self) -> chrono::Duration {
+        self.finished_at.unwrap_or_else(Local::now) - self.started_at
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let status: String = row.get(3)?;
+        Ok(Self {
+            id: row.get(0)?,
+            command: row.get(1)?,
+            mode: row.get(2)?,
+            status: status.parse().unwrap_or(TaskStatus::Failed),
+            started_at: from_millis(row.get(4)?),
+            finished_at: row.get::<_, Option<i64>>(5)?.map(from_millis),
+            input_tokens: row.get::<_, i64>(6)? as u64,
+            output_tokens: row.get::<_, i64>(7)? as u64,
+            result: row.get(8)?,
+            error: row.get(9)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskStep {
+    pub index: u32,
+    pub timestamp: DateTime<Local>,
+    pub tool: String,
+    pub input: Value,
+    pub screenshot: Option<Vec<u8>>,
+    pub error: Option<String>,
+}
+
+fn from_millis(millis: i64) -> DateTime<Local> {
+    Local
+        .timestamp_millis_opt(millis)
+        .single()
+        .unwrap_or_else(Local::now)
+}
+
+pub struct TaskHistory {
+    conn: Connection,
+}
+
+impl TaskHistory {
+    pub fn new(path: PathBuf) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("Failed to create history directory")?;
+        }
+
+        let conn = Connection::open(&path)
+            .with_context(|| format!("Failed to open task history at {:?}", path))?;
+
+        let mut history = Self { conn };
+        history.init_schema()?;
+        history.close_interrupted_tasks()?;
+
+        Ok(history)
+    }
+
+    fn init_schema(&mut self) -> Result<()> {
+        self.conn.pragma_update(None, "journal_mode", "WAL")?;
+        self.conn.pragma_update(None, "foreign_keys", "ON")?;
+
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS tasks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                command TEXT NOT NULL,
+                mode TEXT NOT NULL,
+                status TEXT NOT NULL,
+                started_at INTEGER NOT NULL,
+                finished_at INTEGER,
+                input_tokens INTEGER NOT NULL DEFAULT 0,
+                output_tokens INTEGER NOT NULL DEFAULT 0,
+                result TEXT,
+                error TEXT
+            )",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS task_steps (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                task_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                step_index INTEGER NOT NULL,
+                timestamp INTEGER NOT NULL,
+                tool TEXT NOT NULL,
+                input_json TEXT NOT NULL,
+                screenshot BLOB,
+                error TEXT
+            )",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_task_steps_task ON task_steps(task_id, step_index)",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    fn close_interrupted_tasks(&mut self) -> Result<()> {
+        let closed = self.conn.execute(
+            "UPDATE tasks SET status = ?1, error = ?2, finished_at = started_at
+             WHERE status = ?3",
+            params![
+                TaskStatus::Failed.as_str(),
+                "superctrl exited while the task was running",
+                TaskStatus::Running.as_str()
+            ],
+        )?;
+        if closed > 0 {
+            tracing::warn!("Marked {} interrupted task(s) as failed", closed);
+        }
+        Ok(())
+    }
+
+    pub fn start_task(&mut self, command: &str, mode: &str) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO tasks (command, mode, status, started_at) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                command,
+                mode,
+                TaskStatus::Running.as_str(),
+                Local::now().timestamp_millis()
+            ],
+        )?;
+        let id = self.conn.last_insert_rowid();
+
+        self.conn.execute(
+            "DELETE FROM tasks WHERE id <= ?1 - ?2",
+            params![id, MAX_TASKS],
+        )?;
+
+        Ok(id)
+    }
+
+    pub fn record_step(&mut self, task_id: i64, step: &TaskStep) -> Result<()> {
+        let input_json =
+            serde_json::to_string(&step.input).context("Failed to serialize step input")?;
+
+        self.conn.execute(
+            "INSERT INTO task_steps (task_id, step_index, timestamp, tool, input_json, screenshot, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                task_id,
+                step.index,
+                step.timestamp.timestamp_millis(),
+                step.tool,
+                input_json,
+                step.screenshot,
+                step.error
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn add_usage(&mut self, task_id: i64, input_tokens: u64, output_tokens: u64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET input_tokens = input_tokens + ?2, output_tokens = output_tokens + ?3
+             WHERE id = ?1",
+            params![task_id, input_tokens as i64, output_tokens as i64],
+        )?;
+        Ok(())
+    }
+
+    pub fn finish_task(
+        &mut self,
+        task_id: i64,
+        status: TaskStatus,
+        result: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET status = ?2, finished_at = ?3, result = ?4, error = ?5 WHERE id = ?1",
+            params![
+                task_id,
+                status.as_str(),
+                Local::now().timestamp_millis(),
+                result,
+                error
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn recent_tasks(&self, limit: usize) -> Result<Vec<TaskRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, command, mode, status, started_at, finished_at, input_tokens, output_tokens, result, error
+             FROM tasks ORDER BY id DESC LIMIT ?1",
+        )?;
+        let tasks = stmt
+            .query_map(params![limit as i64], TaskRecord::from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(tasks)
+    }
+
+    pub fn task(&self, task_id: i64) -> Result<Option<TaskRecord>> {
+        let task = self
+            .conn
+            .query_row(
+                "SELECT id, command, mode, status, started_at, finished_at, input_tokens, output_tokens, result, error
+                 FROM tasks WHERE id = ?1",
+                params![task_id],
+                TaskRecord::from_row,
+            )
+            .optional()?;
+        Ok(task)
+    }
+
+    pub fn steps(&self, task_id: i64) -> Result<Vec<TaskStep>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT step_index, timestamp, tool, input_json, screenshot, error
+             FROM task_steps WHERE task_id = ?1 ORDER BY step_index",
+        )?;
+        let steps = stmt
+            .query_map(params![task_id], |row| {
+                let input_json: String = row.get(3)?;
+                Ok(TaskStep {
+                    index: row.get(0)?,
+                    timestamp: from_millis(row.get(1)?),
+                    tool: row.get(2)?,
+                    input: serde_json::from_str(&input_json).unwrap_or(Value::Null),
+                    screenshot: row.get(4)?,
+                    error: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(steps)
+    }
+}
+
+pub type SharedTaskHistory = Arc<Mutex<TaskHistory>>;
+
+#[derive(Clone)]
+pub struct TaskRecorder {
+    history: SharedTaskHistory,
+    task_id: i64,
+    next_step: Arc<Mutex<u32>>,
+}
+
+impl TaskRecorder {
+    pub fn new(history: SharedTaskHistory, task_id: i64) -> Self {
+        Self {
+            history,
+            task_id,
+            next_step: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    pub fn record_step(&self, tool: &str, input: &Value, result: &Value, error: Option<&str>) {
+        let index = {
+            let mut next_step = self.next_step.lock().unwrap();
+            *next_step += 1;
+            *next_step
+        };
+        let step = TaskStep {
+            index,
+            timestamp: Local::now(),
+            tool: tool.to_string(),
+            input: input.clone(),
+            screenshot: screenshot_from_result(result),
+            error: error.map(str::to_string),
+        };
+
+        if let Err(e) = self
+            .history
+            .lock()
+            .unwrap()
+            .record_step(self.task_id, &step)
+        {
+            tracing::warn!("Failed to record task step: {:#}", e);
+        }
+    }
+
+    pub fn record_usage(&self, input_tokens: u64, output_tokens: u64) {
+        if let Err(e) =
+            self.history
+                .lock()
+                .unwrap()
+                .add_usage(self.task_id, input_tokens, output_tokens)
+        {
+            tracing::warn!("Failed to record token usage: {:#}", e);
+        }
+    }
+
+    pub fn finish(&self, status: TaskStatus, result: Option<&str>, error: Option<&str>) {
+        if let Err(e) =
+            self.history
+                .lock()
+                .unwrap()
+                .finish_task(self.task_id, status, result, error)
+        {
+            tracing::warn!("Failed to record task result: {:#}", e);
+        }
+    }
+}
+
+pub fn screenshot_from_result(result: &Value) -> Option<Vec<u8>> {
+    result
+        .as_array()?
+        .iter()
+        .filter(|block| block["type"] == "image")
+        .find_map(|block| block["source"]["data"].as_str())
+        .and_then(|data| STANDARD.decode(data).ok())
+}
@@ -0,0 +1,281 @@
+use iced::widget::{button, column, container, image, row, scrollable, text, vertical_space};
+use iced::{window, Color, Element, Font, Length, Size, Task};
+
+use crate::history::{SharedTaskHistory, TaskRecord, TaskStatus, TaskStep};
+
+const MAX_LISTED_TASKS: usize = 100;
+
+pub const WINDOW_TITLE: &str = "superctrl - Task History";
+
+#[derive(Debug, Clone)]
+pub enum HistoryMessage {
+    Refresh,
+    OpenTask(i64),
+    Back,
+}
+
+struct TaskDetail {
+    task: TaskRecord,
+    steps: Vec<(TaskStep, Option<image::Handle>)>,
+}
+
+pub struct HistoryWindow {
+    history: SharedTaskHistory,
+    tasks: Vec<TaskRecord>,
+    detail: Option<TaskDetail>,
+    error: Option<String>,
+}
+
+impl HistoryWindow {
+    pub fn new(history: SharedTaskHistory) -> (Self, Task<HistoryMessage>) {
+        let mut window = Self {
+            history,
+            tasks: Vec::new(),
+            detail: None,
+            error: None,
+        };
+        window.load_tasks();
+        (window, Task::none())
+    }
+
+    fn load_tasks(&mut self) {
+        match self.history.lock().unwrap().recent_tasks(MAX_LISTED_TASKS) {
+            Ok(tasks) => {
+                self.tasks = tasks;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("Failed to load task history: {:#}", e)),
+        }
+    }
+
+    fn load_detail(&mut self, task_id: i64) {
+        let history = self.history.lock().unwrap();
+        let detail = history.task(task_id).and_then(|task| {
+            let steps = history.steps(task_id)?;
+            Ok(task.map(|task| TaskDetail {
+                task,
+                steps: steps
+                    .into_iter()
+                    .map(|step| {
+                        let screenshot = step.screenshot.clone().map(image::Handle::from_bytes);
+                        (step, screenshot)
+                    })
+                    .collect(),
+            }))
+        });
+
+        match detail {
+            Ok(Some(detail)) => self.detail = Some(detail),
+            Ok(None) => self.error = Some(format!("Task {} no longer exists", task_id)),
+            Err(e) => self.error = Some(format!("Failed to load task: {:#}", e)),
+        }
+    }
+
+    pub fn update(&mut self, message: HistoryMessage) -> Task<HistoryMessage> {
+        match message {
+            HistoryMessage::Refresh => {
+                match self.detail.as_ref().map(|detail| detail.task.id) {
+                    Some(task_id) => self.load_detail(task_id),
+                    None => self.load_tasks(),
+                }
+                Task::none()
+            }
+            HistoryMessage::OpenTask(task_id) => {
+                self.load_detail(task_id);
+                Task::none()
+            }
+            HistoryMessage::Back => {
+                self.detail = None;
+                self.load_tasks();
+                Task::none()
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, HistoryMessage> {
+        let body = match &self.detail {
+            Some(detail) => Self::detail_view(detail),
+            None => self.list_view(),
+        };
+
+        let mut content = column![].spacing(10).padding(20);
+        if let Some(error) = &self.error {
+            content = content.push(text(error).size(13).color(Color::from_rgb(0.8, 0.0, 0.0)));
+        }
+        content = content.push(body);
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    fn list_view(&self) -> Element<'_, HistoryMessage> {
+        let header = row![
+            text("Task History").size(28).width(Length::Fill),
+            button("  Refresh  ")
+                .on_press(HistoryMessage::Refresh)
+                .padding(8),
+        ]
+        .spacing(10);
+
+        if self.tasks.is_empty() {
+            return column![
+                header,
+                text("No tasks yet").color(Color::from_rgb(0.5, 0.5, 0.5))
+            ]
+            .spacing(15)
+            .into();
+        }
+
+        let rows = self.tasks.iter().fold(column![].spacing(4), |rows, task| {
+            let summary = row![
+                text(status_label(task.status))
+                    .color(status_color(task.status))
+                    .width(Length::Fixed(110.0)),
+                text(&task.command).width(Length::Fill),
+                text(task.started_at.format("%b %d %H:%M").to_string())
+                    .size(12)
+                    .width(Length::Fixed(95.0)),
+                text(format_duration(task))
+                    .size(12)
+                    .width(Length::Fixed(60.0)),
+                text(format!("{} tok", task.total_tokens()))
+                    .size(12)
+                    .width(Length::Fixed(80.0)),
+            ]
+            .spacing(10);
+
+            rows.push(
+                button(summary)
+                    .on_press(HistoryMessage::OpenTask(task.id))
+                    .width(Length::Fill)
+                    .style(button::text),
+            )
+        });
+
+        column![header, scrollable(rows).height(Length::Fill)]
+            .spacing(15)
+            .into()
+    }
+
+    fn detail_view(detail: &TaskDetail) -> Element<'_, HistoryMessage> {
+        let task = &detail.task;
+        let grey = Color::from_rgb(0.5, 0.5, 0.5);
+
+        let header = row![
+            button("  ← Back  ")
+                .on_press(HistoryMessage::Back)
+                .padding(8),
+            text(&task.command).size(20).width(Length::Fill),
+            button("  Refresh  ")
+                .on_press(HistoryMessage::Refresh)
+                .padding(8),
+        ]
+        .spacing(10);
+
+        let mut summary = column![
+            row![
+                text(status_label(task.status)).color(status_color(task.status)),
+                text(format!(
+                    "Mode: {}  ·  Started: {}  ·  Duration: {}",
+                    task.mode,
+                    task.started_at.format("%Y-%m-%d %H:%M:%S"),
+                    format_duration(task)
+                ))
+                .color(grey),
+            ]
+            .spacing(15),
+            text(format!(
+                "Tokens: {} in / {} out",
+                task.input_tokens, task.output_tokens
+            ))
+            .color(grey),
+        ]
+        .spacing(6);
+        if let Some(result) = &task.result {
+            summary = summary.push(text(format!("Result: {}", result)));
+        }
+        if let Some(error) = &task.error {
+            summary = summary
+                .push(text(format!("Error: {}", error)).color(status_color(TaskStatus::Failed)));
+        }
+
+        let steps = if detail.steps.is_empty() {
+            column![text("No steps were recorded for this task").color(grey)]
+        } else {
+            detail
+                .steps
+                .iter()
+                .fold(column![].spacing(20), |steps, (step, screenshot)| {
+                    let input = serde_json::to_string_pretty(&step.input).unwrap_or_default();
+                    let mut entry = column![
+                        text(format!(
+                            "Step {}  ·  {}  ·  {}",
+                            step.index,
+                            step.tool,
+                            step.timestamp.format("%H:%M:%S")
+                        ))
+                        .size(16),
+                        text(input).size(12).font(Font::MONOSPACE),
+                    ]
+                    .spacing(6);
+
+                    if let Some(error) = &step.error {
+                        entry = entry.push(
+                            text(format!("✗ {}", error))
+                                .size(13)
+                                .color(status_color(TaskStatus::Failed)),
+                        );
+                    }
+                    if let Some(screenshot) = screenshot {
+                        entry = entry.push(image(screenshot.clone()).width(Length::Fixed(560.0)));
+                    }
+                    steps.push(entry)
+                })
+        };
+
+        column![
+            header,
+            summary,
+            vertical_space().height(Length::Fixed(10.0)),
+            scrollable(steps).height(Length::Fill),
+        ]
+        .spacing(10)
+        .into()
+    }
+}
+
+fn status_label(status: TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Running => "🔵 Running",
+        TaskStatus::Completed => "✓ Completed",
+        TaskStatus::Failed => "✗ Failed",
+        TaskStatus::Stopped => "🛑 Stopped",
+    }
+}
+
+fn status_color(status: TaskStatus) -> Color {
+    match status {
+        TaskStatus::Running => Color::from_rgb(0.0, 0.48, 1.0),
+        TaskStatus::Completed => Color::from_rgb(0.0, 0.6, 0.0),
+        TaskStatus::Failed => Color::from_rgb(0.8, 0.0, 0.0),
+        TaskStatus::Stopped => Color::from_rgb(0.8, 0.5, 0.0),
+    }
+}
+
+fn format_duration(task: &TaskRecord) -> String {
+    let seconds = task.duration().num_milliseconds() as f64 / 1000.0;
+    if seconds < 60.0 {
+        format!("{:.1}s", seconds)
+    } else {
+        format!("{}m {:02}s", seconds as u64 / 60, seconds as u64 % 60)
+    }
+}
+
+pub fn window_settings() -> window::Settings {
+    window::Settings {
+        size: Size::new(900.0, 700.0),
+        ..window::Settings::default()
+    }
+}
@@ -1,23 +1,288 @@
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
-};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
+use enigo::{Enigo, Mouse, Settings as EnigoSettings};
 use global_hotkey::{
     hotkey::{Code, HotKey, Modifiers},
-    GlobalHotKeyEvent, GlobalHotKeyManager,
+    GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState,
 };
 
-pub struct EmergencyStop {
-    stop_flag: Arc<AtomicBool>,
+use serde::{Deserialize, Serialize};
+
+use crate::input::{synthetic_input, SyntheticInput, SYNTHETIC_WINDOW};
+use crate::task::{ControlSource, TaskRunner};
+
+const FAILSAFE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+const FAILSAFE_CORNER_SIZE: i32 = 3;
+
+pub const DEFAULT_STOP_HOTKEY: &str = "cmd+shift+esc";
+pub const DEFAULT_PAUSE_HOTKEY: &str = "ctrl+cmd+p";
+pub const DEFAULT_REPEAT_LAST_HOTKEY: &str = "ctrl+cmd+r";
+pub const DEFAULT_QUICK_COMMAND_HOTKEY: &str = "ctrl+cmd+k";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HotkeyAction {
+    Stop,
+    TogglePause,
+    RepeatLast,
+    QuickCommand,
+}
+
+impl HotkeyAction {
+    pub const ALL: [HotkeyAction; 4] = [
+        HotkeyAction::Stop,
+        HotkeyAction::TogglePause,
+        HotkeyAction::RepeatLast,
+        HotkeyAction::QuickCommand,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HotkeyAction::Stop => "Emergency Stop",
+            HotkeyAction::TogglePause => "Pause / Resume",
+            HotkeyAction::RepeatLast => "Repeat Last Command",
+            HotkeyAction::QuickCommand => "Quick Command",
+        }
+    }
+}
+
+impl std::fmt::Display for HotkeyAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HotkeyAction::Stop => write!(f, "stop"),
+            HotkeyAction::TogglePause => write!(f, "pause"),
+            HotkeyAction::RepeatLast => write!(f, "repeat-last"),
+            HotkeyAction::QuickCommand => write!(f, "quick-command"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HotkeyConfig {
+    #[serde(with = "binding")]
+    pub stop: Option<String>,
+    #[serde(with = "binding")]
+    pub pause: Option<String>,
+    #[serde(with = "binding")]
+    pub repeat_last: Option<String>,
+    #[serde(with = "binding")]
+    pub quick_command: Option<String>,
+}
+
+mod binding {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        binding: &Option<String>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(binding.as_deref().unwrap_or(""))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<String>, D::Error> {
+        let spec = String::deserialize(deserializer)?;
+        Ok(Some(spec).filter(|spec| !spec.trim().is_empty()))
+    }
+}
+
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        Self {
+            stop: Some(DEFAULT_STOP_HOTKEY.to_string()),
+            pause: Some(DEFAULT_PAUSE_HOTKEY.to_string()),
+            repeat_last: Some(DEFAULT_REPEAT_LAST_HOTKEY.to_string()),
+            quick_command: Some(DEFAULT_QUICK_COMMAND_HOTKEY.to_string()),
+        }
+    }
+}
+
+impl HotkeyConfig {
+    pub fn get(&self, action: HotkeyAction) -> Option<&str> {
+        let binding = match action {
+            HotkeyAction::Stop => &self.stop,
+            HotkeyAction::TogglePause => &self.pause,
+            HotkeyAction::RepeatLast => &self.repeat_last,
+            HotkeyAction::QuickCommand => &self.quick_command,
+        };
+        binding.as_deref().filter(|spec| !spec.trim().is_empty())
+    }
+
+    pub fn set(&mut self, action: HotkeyAction, spec: Option<String>) {
+        let binding = match action {
+            HotkeyAction::Stop => &mut self.stop,
+            HotkeyAction::TogglePause => &mut self.pause,
+            HotkeyAction::RepeatLast => &mut self.repeat_last,
+            HotkeyAction::QuickCommand => &mut self.quick_command,
+        };
+        *binding = spec.filter(|spec| !spec.trim().is_empty());
+    }
+}
+
+pub fn parse_hotkey(spec: &str) -> Result<HotKey> {
+    let normalized = spec
+        .split('+')
+        .map(|token| match token.trim().to_lowercase().as_str() {
+            "opt" => "alt".to_string(),
+            "ctl" => "ctrl".to_string(),
+            "return" => "enter".to_string(),
+            other => other.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("+");
+
+    let hotkey: HotKey = normalized
+        .parse()
+        .with_context(|| format!("Invalid hotkey '{}'", spec))?;
+    if hotkey.mods.is_empty() {
+        anyhow::bail!(
+            "Hotkey '{}' needs at least one modifier (cmd, ctrl, alt or shift)",
+            spec
+        );
+    }
+
+    Ok(hotkey)
+}
+
+pub fn format_hotkey(hotkey: &HotKey) -> String {
+    let mut formatted = String::new();
+    for (modifier, symbol) in [
+        (Modifiers::CONTROL, "⌃"),
+        (Modifiers::ALT, "⌥"),
+        (Modifiers::SHIFT, "⇧"),
+        (Modifiers::SUPER, "⌘"),
+    ] {
+        if hotkey.mods.contains(modifier) {
+            formatted.push_str(symbol);
+        }
+    }
+
+    let key = match hotkey.key {
+        Code::Escape => "⎋".to_string(),
+        Code::Enter => "↩".to_string(),
+        Code::Tab => "⇥".to_string(),
+        Code::Backspace => "⌫".to_string(),
+        Code::Space => "Space".to_string(),
+        Code::ArrowUp => "↑".to_string(),
+        Code::ArrowDown => "↓".to_string(),
+        Code::ArrowLeft => "←".to_string(),
+        Code::ArrowRight => "→".to_string(),
+        code => {
+            let name = format!("{:?}", code);
+            name.strip_prefix("Key")
+                .or_else(|| name.strip_prefix("Digit"))
+                .unwrap_or(&name)
+                .to_string()
+        }
+    };
+    formatted.push_str(&key);
+    formatted
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HotkeyBindings {
+    bindings: Vec<(HotkeyAction, HotKey)>,
+}
+
+impl HotkeyBindings {
+    pub fn from_config(config: &HotkeyConfig) -> Result<Self> {
+        let mut bindings: Vec<(HotkeyAction, HotKey)> = Vec::new();
+
+        for action in HotkeyAction::ALL {
+            let Some(spec) = config.get(action) else {
+                continue;
+            };
+            let hotkey =
+                parse_hotkey(spec).with_context(|| format!("Invalid {} hotkey", action))?;
+
+            if let Some((existing, _)) = bindings.iter().find(|(_, bound)| *bound == hotkey) {
+                anyhow::bail!(
+                    "Hotkey '{}' is bound to both {} and {}",
+                    spec,
+                    existing,
+                    action
+                );
+            }
+            bindings.push((action, hotkey));
+        }
+
+        Ok(Self { bindings })
+    }
+
+    pub fn hotkey(&self, action: HotkeyAction) -> Option<HotKey> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map(|(_, hotkey)| *hotkey)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (HotkeyAction, HotKey)> + '_ {
+        self.bindings.iter().copied()
+    }
+
+    pub fn action_for(&self, event: &GlobalHotKeyEvent) -> Option<HotkeyAction> {
+        if event.state != HotKeyState::Pressed {
+            return None;
+        }
+
+        self.bindings
+            .iter()
+            .find(|(_, hotkey)| hotkey.id() == event.id)
+            .map(|(action, _)| *action)
+    }
+}
+
+pub fn swap_bindings(
+    current: &HotkeyBindings,
+    requested: HotkeyBindings,
+    mut register: impl FnMut(HotKey) -> Result<()>,
+    mut unregister: impl FnMut(HotKey),
+) -> Result<HotkeyBindings> {
+    let is_current = |hotkey: HotKey| current.iter().any(|(_, bound)| bound == hotkey);
+
+    let mut added = Vec::new();
+    let mut failed = Vec::new();
+    for (action, hotkey) in requested.iter() {
+        if is_current(hotkey) {
+            continue;
+        }
+        match register(hotkey) {
+            Ok(()) => added.push(hotkey),
+            Err(e) => failed.push(format!("{} ({}): {:#}", action, format_hotkey(&hotkey), e)),
+        }
+    }
+
+    if !failed.is_empty() {
+        for hotkey in added {
+            unregister(hotkey);
+        }
+        anyhow::bail!(
+            "Failed to register hotkeys, keeping the previous bindings: {} \
+             (they may already be used by another application)",
+            failed.join(", ")
+        );
+    }
+
+    for (_, hotkey) in current.iter() {
+        if !requested.iter().any(|(_, wanted)| wanted == hotkey) {
+            unregister(hotkey);
+        }
+    }
+    Ok(requested)
+}
+
+pub struct HotkeyRegistry {
     manager: GlobalHotKeyManager,
-    hotkey: HotKey,
+    bindings: Arc<RwLock<HotkeyBindings>>,
 }
 
-impl EmergencyStop {
+impl HotkeyRegistry {
     pub fn new() -> Result<Self> {
-        let stop_flag = Arc::new(AtomicBool::new(false));
         let manager = GlobalHotKeyManager::new().context(
             "Failed to create GlobalHotKeyManager. \
              On macOS, this requires Accessibility permissions. \
@@ -25,62 +290,263 @@ impl EmergencyStop {
              and add superctrl to the allowed apps.",
         )?;
 
-        let hotkey = HotKey::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::Escape);
-
         Ok(Self {
-            stop_flag,
             manager,
-            hotkey,
+            bindings: Arc::new(RwLock::new(HotkeyBindings::default())),
         })
     }
 
-    pub fn register_hotkey(&self) -> Result<()> {
-        self.manager
-            .register(self.hotkey)
-            .context("Failed to register global hotkey (⌘⇧⎋)")?;
-
-        eprintln!("✓ Emergency stop hotkey registered: ⌘⇧⎋ (Command+Shift+Escape)");
+    pub fn apply(&self, config: &HotkeyConfig) -> Result<()> {
+        let requested = HotkeyBindings::from_config(config)?;
 
+        let mut bindings = self.bindings.write().unwrap();
+        *bindings = swap_bindings(
+            &bindings,
+            requested,
+            |hotkey| self.manager.register(hotkey).map_err(Into::into),
+            |hotkey| {
+                let _ = self.manager.unregister(hotkey);
+            },
+        )?;
+        for (action, hotkey) in bindings.iter() {
+            tracing::info!(
+                "{} hotkey registered: {}",
+                action.label(),
+                format_hotkey(&hotkey)
+            );
+        }
         Ok(())
     }
 
-    pub fn unregister_hotkey(&self) -> Result<()> {
-        self.manager
-            .unregister(self.hotkey)
-            .context("Failed to unregister hotkey")?;
+    pub fn unregister_all(&self) -> Result<()> {
+        let mut bindings = self.bindings.write().unwrap();
+        for (_, hotkey) in bindings.iter() {
+            self.manager
+                .unregister(hotkey)
+                .context("Failed to unregister hotkey")?;
+        }
+        *bindings = HotkeyBindings::default();
         Ok(())
     }
 
-    pub fn get_stop_flag(&self) -> Arc<AtomicBool> {
-        Arc::clone(&self.stop_flag)
+    pub fn bindings(&self) -> HotkeyBindings {
+        self.bindings.read().unwrap().clone()
     }
 
-    pub fn start_listener(stop_flag: Arc<AtomicBool>) {
-        tokio::spawn(async move {
+    pub fn handle_event(
+        bindings: &HotkeyBindings,
+        event: &GlobalHotKeyEvent,
+        tasks: &TaskRunner,
+    ) -> Option<HotkeyAction> {
+        let action = bindings.action_for(event)?;
+
+        match action {
+            HotkeyAction::Stop => {
+                tracing::warn!("Emergency stop activated (hotkey)");
+                tasks.stop(ControlSource::Hotkey);
+            }
+            HotkeyAction::TogglePause if tasks.is_paused() => {
+                tasks.resume(ControlSource::Hotkey);
+            }
+            HotkeyAction::TogglePause => {
+                tasks.pause(ControlSource::Hotkey);
+            }
+            HotkeyAction::RepeatLast | HotkeyAction::QuickCommand => {}
+        }
+
+        Some(action)
+    }
+
+    pub fn start_listener(
+        &self,
+        tasks: TaskRunner,
+        on_action: impl Fn(HotkeyAction) + Send + 'static,
+    ) {
+        let bindings = self.bindings.clone();
+        std::thread::spawn(move || {
             let receiver = GlobalHotKeyEvent::receiver();
 
-            loop {
-                if let Ok(event) = receiver.try_recv() {
-                    if event.state == global_hotkey::HotKeyState::Pressed {
-                        stop_flag.store(true, Ordering::Release);
-                        eprintln!("🛑 EMERGENCY STOP ACTIVATED (⌘⇧⎋)");
-                    }
+            while let Ok(event) = receiver.recv() {
+                let bindings = bindings.read().unwrap().clone();
+                if let Some(action) = Self::handle_event(&bindings, &event, &tasks) {
+                    on_action(action);
                 }
-
-                tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
             }
         });
     }
 }
 
-impl Default for EmergencyStop {
+impl Default for HotkeyRegistry {
     fn default() -> Self {
-        Self::new().expect("Failed to create EmergencyStop")
+        Self::new().expect("Failed to create HotkeyRegistry")
     }
 }
 
-impl Drop for EmergencyStop {
+impl Drop for HotkeyRegistry {
     fn drop(&mut self) {
-        let _ = self.unregister_hotkey();
+        let _ = self.unregister_all();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScreenCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl ScreenCorner {
+    pub fn contains(&self, cursor: (i32, i32), display: (i32, i32)) -> bool {
+        let (x, y) = cursor;
+        let (width, height) = display;
+        let left = x < FAILSAFE_CORNER_SIZE;
+        let right = x >= width - FAILSAFE_CORNER_SIZE;
+        let top = y < FAILSAFE_CORNER_SIZE;
+        let bottom = y >= height - FAILSAFE_CORNER_SIZE;
+
+        match self {
+            ScreenCorner::TopLeft => top && left,
+            ScreenCorner::TopRight => top && right,
+            ScreenCorner::BottomLeft => bottom && left,
+            ScreenCorner::BottomRight => bottom && right,
+        }
+    }
+}
+
+impl std::fmt::Display for ScreenCorner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScreenCorner::TopLeft => write!(f, "top-left"),
+            ScreenCorner::TopRight => write!(f, "top-right"),
+            ScreenCorner::BottomLeft => write!(f, "bottom-left"),
+            ScreenCorner::BottomRight => write!(f, "bottom-right"),
+        }
+    }
+}
+
+impl FromStr for ScreenCorner {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().replace('_', "-").as_str() {
+            "top-left" => Ok(ScreenCorner::TopLeft),
+            "top-right" => Ok(ScreenCorner::TopRight),
+            "bottom-left" => Ok(ScreenCorner::BottomLeft),
+            "bottom-right" => Ok(ScreenCorner::BottomRight),
+            other => anyhow::bail!(
+                "Unknown screen corner '{}' (expected top-left, top-right, bottom-left or bottom-right)",
+                other
+            ),
+        }
+    }
+}
+
+pub struct CornerFailsafe {
+    corner: ScreenCorner,
+    armed: bool,
+    agent_pointer: Option<(i32, i32)>,
+    seen_injection: Option<Instant>,
+}
+
+impl CornerFailsafe {
+    pub fn new(corner: ScreenCorner) -> Self {
+        Self {
+            corner,
+            armed: false,
+            agent_pointer: None,
+            seen_injection: None,
+        }
+    }
+
+    fn moved_by_agent(
+        &mut self,
+        cursor: (i32, i32),
+        synthetic: &SyntheticInput,
+        at: Instant,
+    ) -> bool {
+        let Some((pointer, injected_at)) = synthetic.last_pointer() else {
+            return false;
+        };
+        if self.seen_injection != Some(injected_at) {
+            self.seen_injection = Some(injected_at);
+            self.agent_pointer = Some(pointer);
+        }
+
+        let Some((x, y)) = self.agent_pointer else {
+            return false;
+        };
+        if (cursor.0 - x).abs() <= 2 && (cursor.1 - y).abs() <= 2 {
+            return true;
+        }
+        if at.saturating_duration_since(injected_at) > SYNTHETIC_WINDOW {
+            self.agent_pointer = None;
+        }
+        false
+    }
+
+    pub fn check(
+        &mut self,
+        cursor: (i32, i32),
+        display: (i32, i32),
+        tasks: &TaskRunner,
+        synthetic: &SyntheticInput,
+        at: Instant,
+    ) -> bool {
+        let moved_by_agent = self.moved_by_agent(cursor, synthetic, at);
+        let armed = tasks.is_running() && self.corner.contains(cursor, display) && !moved_by_agent;
+
+        let triggered = armed && !self.armed;
+        self.armed = armed;
+
+        if triggered {
+            tracing::warn!("Failsafe activated (cursor in {} corner)", self.corner);
+            tasks.stop(ControlSource::Failsafe);
+        }
+        triggered
+    }
+
+    fn set_corner(&mut self, corner: ScreenCorner) {
+        if corner != self.corner {
+            tracing::info!("Screen-corner failsafe moved to the {} corner", corner);
+            self.corner = corner;
+            self.armed = false;
+        }
+    }
+
+    pub fn start(
+        mut self,
+        tasks: TaskRunner,
+        corner: impl Fn() -> Option<ScreenCorner> + Send + 'static,
+    ) {
+        tracing::info!(
+            "Screen-corner failsafe armed: move the cursor into the {} corner to abort",
+            self.corner
+        );
+
+        std::thread::spawn(move || {
+            let enigo = match Enigo::new(&EnigoSettings::default()) {
+                Ok(enigo) => enigo,
+                Err(e) => {
+                    tracing::error!("Failed to start screen-corner failsafe: {}", e);
+                    return;
+                }
+            };
+
+            loop {
+                match corner() {
+                    Some(corner) => {
+                        self.set_corner(corner);
+                        if let (Ok(cursor), Ok(display)) = (enigo.location(), enigo.main_display())
+                        {
+                            self.check(cursor, display, &tasks, synthetic_input(), Instant::now());
+                        }
+                    }
+                    None => self.armed = false,
+                }
+                std::thread::sleep(FAILSAFE_POLL_INTERVAL);
+            }
+        });
     }
 }
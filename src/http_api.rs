@@ -0,0 +1,395 @@
+use std::convert::Infallible;
+use std::net::{Ipv4Addr, TcpListener};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use hyper::body::HttpBody;
+use hyper::header::{self, HeaderMap, HeaderValue};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::broadcast;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::{Message, Role, WebSocketConfig};
+use tokio_tungstenite::WebSocketStream;
+
+use crate::events::TimedEvent;
+use crate::ipc::{DaemonHandler, IpcCommand, IpcResponse, IpcServer};
+
+const EVENTS_PATH: &str = "/v1/events";
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+const MAX_MESSAGE_BYTES: usize = 64 * 1024;
+
+const GET_ROUTES: &[&str] = &["/v1/status", "/v1/learn/status", EVENTS_PATH];
+const POST_ROUTES: &[&str] = &[
+    "/v1/execute",
+    "/v1/continue",
+    "/v1/ask",
+    "/v1/plan",
+    "/v1/stop",
+    "/v1/pause",
+    "/v1/resume",
+    "/v1/learn/start",
+    "/v1/learn/stop",
+    "/v1/learn/finish",
+    "/v1/learn/clear",
+];
+
+#[derive(Deserialize)]
+struct ExecuteRequest {
+    command: String,
+    #[serde(default)]
+    output_schema: Option<serde_json::Value>,
+    #[serde(default)]
+    wait: bool,
+}
+
+#[derive(Deserialize)]
+struct CommandRequest {
+    command: String,
+}
+
+#[derive(Deserialize)]
+struct AskRequest {
+    question: String,
+}
+
+pub fn bind(port: u16) -> Result<TcpListener> {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .with_context(|| format!("Failed to bind the HTTP API to 127.0.0.1:{}", port))
+}
+
+pub fn route(method: &Method, path: &str, body: &[u8]) -> Result<IpcCommand, (StatusCode, String)> {
+    let path = match path.trim_end_matches('/') {
+        "" => "/",
+        path => path,
+    };
+    let allowed = if GET_ROUTES.contains(&path) {
+        Method::GET
+    } else if POST_ROUTES.contains(&path) {
+        Method::POST
+    } else {
+        return Err((StatusCode::NOT_FOUND, format!("Unknown endpoint {}", path)));
+    };
+    if *method != allowed {
+        return Err((
+            StatusCode::METHOD_NOT_ALLOWED,
+            format!("{} only accepts {}", path, allowed),
+        ));
+    }
+
+    let command = match path {
+        "/v1/status" => IpcCommand::Status,
+        "/v1/learn/status" => IpcCommand::LearnStatus,
+        EVENTS_PATH => IpcCommand::Events,
+        "/v1/execute" => {
+            let request: ExecuteRequest = parse_body(body)?;
+            IpcCommand::Execute {
+                command: request.command,
+                output_schema: request.output_schema,
+                wait: request.wait,
+            }
+        }
+        "/v1/continue" => IpcCommand::Continue {
+            command: parse_body::<CommandRequest>(body)?.command,
+        },
+        "/v1/ask" => IpcCommand::Ask {
+            question: parse_body::<AskRequest>(body)?.question,
+        },
+        "/v1/plan" => IpcCommand::Plan {
+            command: parse_body::<CommandRequest>(body)?.command,
+        },
+        "/v1/stop" => IpcCommand::Stop,
+        "/v1/pause" => IpcCommand::Pause,
+        "/v1/resume" => IpcCommand::Resume,
+        "/v1/learn/start" => IpcCommand::LearnStart,
+        "/v1/learn/stop" => IpcCommand::LearnStop,
+        "/v1/learn/finish" => IpcCommand::LearnFinish,
+        "/v1/learn/clear" => IpcCommand::LearnClear,
+        _ => return Err((StatusCode::NOT_FOUND, format!("Unknown endpoint {}", path))),
+    };
+    Ok(command)
+}
+
+fn parse_body<T: DeserializeOwned>(body: &[u8]) -> Result<T, (StatusCode, String)> {
+    serde_json::from_slice(body).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid request body: {}", e),
+        )
+    })
+}
+
+pub fn is_authorized(headers: &HeaderMap, query: Option<&str>, token: &str) -> bool {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let from_query = query.and_then(|query| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+    });
+
+    [bearer, from_query]
+        .into_iter()
+        .flatten()
+        .any(|candidate| tokens_match(candidate.trim(), token))
+}
+
+fn tokens_match(candidate: &str, token: &str) -> bool {
+    candidate.len() == token.len()
+        && candidate
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn stream_websocket_events<S>(
+    stream: S,
+    mut events: broadcast::Receiver<TimedEvent>,
+) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let config = WebSocketConfig {
+        max_message_size: Some(MAX_MESSAGE_BYTES),
+        max_frame_size: Some(MAX_MESSAGE_BYTES),
+        ..WebSocketConfig::default()
+    };
+    let mut socket = WebSocketStream::from_raw_socket(stream, Role::Server, Some(config)).await;
+
+    loop {
+        tokio::select! {
+            message = socket.next() => match message {
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e.into()),
+                None => return Ok(()),
+            },
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let text = serde_json::to_string(&event)?;
+                    socket.send(Message::text(text)).await?;
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    tracing::warn!("WebSocket subscriber fell behind, skipped {} event(s)", missed);
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    let _ = socket.close(None).await;
+                    return Ok(());
+                }
+            },
+        }
+    }
+}
+
+fn websocket_key(headers: &HeaderMap) -> Option<String> {
+    let header_is = |name: header::HeaderName, expected: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| {
+                value
+                    .split(',')
+                    .any(|part| part.trim().eq_ignore_ascii_case(expected))
+            })
+    };
+    if !header_is(header::UPGRADE, "websocket")
+        || !header_is(header::CONNECTION, "upgrade")
+        || !header_is(header::SEC_WEBSOCKET_VERSION, "13")
+    {
+        return None;
+    }
+    headers
+        .get(header::SEC_WEBSOCKET_KEY)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+fn json_response(status: StatusCode, body: &IpcResponse) -> Response<Body> {
+    let mut response = Response::new(Body::from(serde_json::to_vec(body).unwrap_or_default()));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+async fn read_body(body: &mut Body) -> Result<Vec<u8>, (StatusCode, String)> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Failed to read request body: {}", e),
+            )
+        })?;
+        if bytes.len() + chunk.len() > MAX_BODY_BYTES {
+            return Err((
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "Request body is too large".to_string(),
+            ));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+pub struct HttpApi {
+    token: String,
+    handler: Arc<dyn DaemonHandler>,
+    allowed_origins: Vec<String>,
+}
+
+impl HttpApi {
+    pub fn new(token: String, handler: Arc<dyn DaemonHandler>) -> Self {
+        Self {
+            token,
+            handler,
+            allowed_origins: Vec::new(),
+        }
+    }
+
+    pub fn with_allowed_origins(mut self, origins: Vec<String>) -> Self {
+        self.allowed_origins = origins
+            .into_iter()
+            .map(|origin| origin.trim_end_matches('/').to_string())
+            .collect();
+        self
+    }
+
+    fn allowed_origin(&self, headers: &HeaderMap) -> Option<HeaderValue> {
+        let origin = headers.get(header::ORIGIN)?;
+        let value = origin.to_str().ok()?;
+        self.allowed_origins
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(value))
+            .then(|| origin.clone())
+    }
+
+    pub async fn serve(self, listener: TcpListener) -> Result<()> {
+        listener.set_nonblocking(true)?;
+        let address = listener.local_addr()?;
+        let api = Arc::new(self);
+        let make_service = make_service_fn(move |_| {
+            let api = api.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let api = api.clone();
+                    async move { Ok::<_, Infallible>(api.handle(request).await) }
+                }))
+            }
+        });
+
+        tracing::info!("HTTP API listening on http://{}", address);
+        Server::from_tcp(listener)?
+            .serve(make_service)
+            .await
+            .context("HTTP API server failed")
+    }
+
+    pub async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        let origin = self.allowed_origin(request.headers());
+        let mut response = match (&origin, request.method()) {
+            (Some(_), &Method::OPTIONS) => {
+                let mut response = Response::new(Body::empty());
+                *response.status_mut() = StatusCode::NO_CONTENT;
+                let headers = response.headers_mut();
+                headers.insert(
+                    header::ACCESS_CONTROL_ALLOW_METHODS,
+                    HeaderValue::from_static("GET, POST, OPTIONS"),
+                );
+                headers.insert(
+                    header::ACCESS_CONTROL_ALLOW_HEADERS,
+                    HeaderValue::from_static("authorization, content-type"),
+                );
+                headers.insert(
+                    header::ACCESS_CONTROL_MAX_AGE,
+                    HeaderValue::from_static("600"),
+                );
+                response
+            }
+            _ => self.respond(request).await,
+        };
+
+        let headers = response.headers_mut();
+        headers.insert(header::VARY, HeaderValue::from_static("Origin"));
+        if let Some(origin) = origin {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+        }
+        response
+    }
+
+    async fn respond(&self, mut request: Request<Body>) -> Response<Body> {
+        let path = request.uri().path().to_string();
+        let query = match path.as_str() {
+            EVENTS_PATH => request.uri().query(),
+            _ => None,
+        };
+        if !is_authorized(request.headers(), query, &self.token) {
+            return json_response(
+                StatusCode::UNAUTHORIZED,
+                &IpcResponse::error("Missing or invalid API token"),
+            );
+        }
+
+        let method = request.method().clone();
+        let command = match read_body(request.body_mut())
+            .await
+            .and_then(|body| route(&method, &path, &body))
+        {
+            Ok(command) => command,
+            Err((status, message)) => return json_response(status, &IpcResponse::error(message)),
+        };
+
+        if let IpcCommand::Events = command {
+            return self.upgrade(&mut request);
+        }
+
+        tracing::info!("HTTP API request: {} {}", method, path);
+        let response = IpcServer::dispatch(self.handler.as_ref(), command).await;
+        let status = if response.success {
+            StatusCode::OK
+        } else {
+            StatusCode::UNPROCESSABLE_ENTITY
+        };
+        json_response(status, &response)
+    }
+
+    fn upgrade(&self, request: &mut Request<Body>) -> Response<Body> {
+        let Some(key) = websocket_key(request.headers()) else {
+            return json_response(
+                StatusCode::BAD_REQUEST,
+                &IpcResponse::error("Expected a WebSocket upgrade"),
+            );
+        };
+
+        let events = self.handler.events();
+        let on_upgrade = hyper::upgrade::on(request);
+        tokio::spawn(async move {
+            match on_upgrade.await {
+                Ok(upgraded) => {
+                    if let Err(e) = stream_websocket_events(upgraded, events).await {
+                        tracing::debug!("WebSocket event stream ended: {:#}", e);
+                    }
+                }
+                Err(e) => tracing::warn!("WebSocket upgrade failed: {}", e),
+            }
+        });
+
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::SWITCHING_PROTOCOLS;
+        let headers = response.headers_mut();
+        headers.insert(header::UPGRADE, HeaderValue::from_static("websocket"));
+        headers.insert(header::CONNECTION, HeaderValue::from_static("Upgrade"));
+        if let Ok(accept) = HeaderValue::from_str(&derive_accept_key(key.trim().as_bytes())) {
+            headers.insert(header::SEC_WEBSOCKET_ACCEPT, accept);
+        }
+        response
+    }
+}
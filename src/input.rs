@@ -0,0 +1,193 @@
+use std::collections::VecDeque;
+use std::sync::{Mutex, Once, OnceLock};
+use std::time::{Duration, Instant};
+
+use rdev::EventType;
+
+use crate::task::{ControlSource, TaskRunner};
+
+pub const SYNTHETIC_WINDOW: Duration = Duration::from_millis(250);
+const SYNTHETIC_POINTER_TOLERANCE: f64 = 2.0;
+const RESUME_GRACE: Duration = Duration::from_secs(1);
+
+type Subscriber = Box<dyn Fn(&rdev::Event, bool) + Send + Sync>;
+
+fn subscribers() -> &'static Mutex<Vec<Subscriber>> {
+    static SUBSCRIBERS: OnceLock<Mutex<Vec<Subscriber>>> = OnceLock::new();
+    SUBSCRIBERS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+pub fn subscribe(handler: impl Fn(&rdev::Event, bool) + Send + Sync + 'static) {
+    subscribers().lock().unwrap().push(Box::new(handler));
+
+    static LISTENER: Once = Once::new();
+    LISTENER.call_once(|| {
+        std::thread::spawn(|| {
+            let callback = |event: rdev::Event| {
+                let synthetic = synthetic_input().is_synthetic(&event.event_type, Instant::now());
+                for subscriber in subscribers().lock().unwrap().iter() {
+                    subscriber(&event, synthetic);
+                }
+            };
+
+            if let Err(e) = rdev::listen(callback) {
+                tracing::error!("Input monitoring error: {:?}", e);
+            }
+            tracing::warn!("Input monitor thread exited (rdev::listen() terminated)");
+        });
+    });
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Injection {
+    at: Instant,
+    pointer: Option<((f64, f64), Instant)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InjectedEvent {
+    Key,
+    Button(rdev::Button),
+    Wheel,
+}
+
+impl InjectedEvent {
+    fn matches(&self, event: &EventType) -> bool {
+        match (self, event) {
+            (InjectedEvent::Key, EventType::KeyPress(_)) => true,
+            (InjectedEvent::Button(expected), EventType::ButtonPress(button)) => expected == button,
+            (InjectedEvent::Wheel, EventType::Wheel { .. }) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SyntheticInput {
+    last: Mutex<Option<Injection>>,
+    expected: Mutex<VecDeque<(InjectedEvent, Instant)>>,
+}
+
+impl SyntheticInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn expect(&self, events: impl IntoIterator<Item = InjectedEvent>) {
+        self.expect_at(events, Instant::now());
+    }
+
+    pub fn expect_at(&self, events: impl IntoIterator<Item = InjectedEvent>, at: Instant) {
+        self.expected
+            .lock()
+            .unwrap()
+            .extend(events.into_iter().map(|event| (event, at)));
+    }
+
+    pub fn record(&self, pointer: Option<(i32, i32)>) {
+        self.record_at(pointer, Instant::now());
+    }
+
+    pub fn record_at(&self, pointer: Option<(i32, i32)>, at: Instant) {
+        let mut last = self.last.lock().unwrap();
+        let pointer = pointer
+            .map(|(x, y)| ((x as f64, y as f64), at))
+            .or_else(|| last.and_then(|injection| injection.pointer));
+        *last = Some(Injection { at, pointer });
+
+        for (_, expected_at) in self.expected.lock().unwrap().iter_mut() {
+            *expected_at = (*expected_at).max(at);
+        }
+    }
+
+    pub fn last_pointer(&self) -> Option<((i32, i32), Instant)> {
+        self.last
+            .lock()
+            .unwrap()
+            .and_then(|injection| injection.pointer)
+            .map(|((x, y), at)| ((x as i32, y as i32), at))
+    }
+
+    pub fn is_synthetic(&self, event: &EventType, at: Instant) -> bool {
+        match event {
+            EventType::KeyPress(_) | EventType::ButtonPress(_) | EventType::Wheel { .. } => {
+                self.consume(event, at)
+            }
+            _ => {
+                let Some(injection) = *self.last.lock().unwrap() else {
+                    return false;
+                };
+                if at.saturating_duration_since(injection.at) > SYNTHETIC_WINDOW {
+                    return false;
+                }
+                match event {
+                    EventType::MouseMove { x, y } => {
+                        injection.pointer.is_some_and(|((px, py), _)| {
+                            (px - x).abs() <= SYNTHETIC_POINTER_TOLERANCE
+                                && (py - y).abs() <= SYNTHETIC_POINTER_TOLERANCE
+                        })
+                    }
+                    _ => true,
+                }
+            }
+        }
+    }
+
+    fn consume(&self, event: &EventType, at: Instant) -> bool {
+        let mut expected = self.expected.lock().unwrap();
+        expected.retain(|(_, expected_at)| {
+            at.saturating_duration_since(*expected_at) <= SYNTHETIC_WINDOW
+        });
+        match expected
+            .iter()
+            .position(|(injected, _)| injected.matches(event))
+        {
+            Some(index) => {
+                expected.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+pub fn synthetic_input() -> &'static SyntheticInput {
+    static SYNTHETIC: OnceLock<SyntheticInput> = OnceLock::new();
+    SYNTHETIC.get_or_init(SyntheticInput::new)
+}
+
+pub struct UserInputDetector;
+
+impl UserInputDetector {
+    pub fn should_pause(event: &EventType, synthetic: bool, tasks: &TaskRunner) -> bool {
+        let is_activity = matches!(
+            event,
+            EventType::KeyPress(_)
+                | EventType::ButtonPress(_)
+                | EventType::MouseMove { .. }
+                | EventType::Wheel { .. }
+        );
+
+        is_activity
+            && !synthetic
+            && tasks.is_running()
+            && !tasks.is_paused()
+            && !tasks.resumed_within(RESUME_GRACE)
+    }
+
+    pub fn start(
+        tasks: TaskRunner,
+        enabled: impl Fn() -> bool + Send + Sync + 'static,
+        on_pause: impl Fn() + Send + Sync + 'static,
+    ) {
+        subscribe(move |event, synthetic| {
+            if Self::should_pause(&event.event_type, synthetic, &tasks)
+                && enabled()
+                && tasks.pause(ControlSource::UserInput) > 0
+            {
+                on_pause();
+            }
+        });
+        tracing::info!("Auto-pause on user input enabled");
+    }
+}
@@ -1,16 +1,41 @@
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::os::unix::fs::{FileTypeExt, MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::broadcast::{self, error::RecvError};
 
-const SOCKET_PATH: &str = "/tmp/superctrl.sock";
+use crate::events::TimedEvent;
+use crate::status::DaemonStatus;
+
+const SOCKET_NAME: &str = "superctrl.sock";
+const MAX_REQUEST_BYTES: u64 = 1024 * 1024;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum IpcCommand {
-    Execute { command: String },
+    Execute {
+        command: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output_schema: Option<serde_json::Value>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        wait: bool,
+    },
+    Continue {
+        command: String,
+    },
+    Ask {
+        question: String,
+    },
+    Plan {
+        command: String,
+    },
     Status,
+    Events,
     Stop,
+    Pause,
+    Resume,
     LearnStart,
     LearnStop,
     LearnStatus,
@@ -22,6 +47,8 @@ pub enum IpcCommand {
 pub struct IpcResponse {
     pub success: bool,
     pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
 }
 
 impl IpcResponse {
@@ -29,6 +56,7 @@ impl IpcResponse {
         Self {
             success: true,
             message: message.into(),
+            data: None,
         }
     }
 
@@ -36,79 +64,159 @@ impl IpcResponse {
         Self {
             success: false,
             message: message.into(),
+            data: None,
         }
     }
+
+    pub fn with_data(mut self, data: serde_json::Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskOutput {
+    pub text: String,
+    pub result: Option<serde_json::Value>,
+}
+
+#[async_trait]
+pub trait DaemonHandler: Send + Sync {
+    async fn execute(
+        &self,
+        _command: String,
+        _output_schema: Option<serde_json::Value>,
+        _wait: bool,
+    ) -> Result<Option<TaskOutput>> {
+        Err(unsupported("Execute"))
+    }
+
+    async fn continue_conversation(&self, _command: String) -> Result<()> {
+        Err(unsupported("Continue"))
+    }
+
+    async fn ask(&self, _question: String) -> Result<String> {
+        Err(unsupported("Ask"))
+    }
+
+    async fn plan(&self, _command: String) -> Result<String> {
+        Err(unsupported("Plan"))
+    }
+
+    async fn status(&self) -> Result<DaemonStatus> {
+        Err(unsupported("Status"))
+    }
+
+    fn events(&self) -> broadcast::Receiver<TimedEvent> {
+        broadcast::channel(1).1
+    }
+
+    async fn stop(&self) -> Result<()> {
+        Err(unsupported("Stop"))
+    }
+
+    async fn pause(&self) -> Result<()> {
+        Err(unsupported("Pause"))
+    }
+
+    async fn resume(&self) -> Result<()> {
+        Err(unsupported("Resume"))
+    }
+
+    async fn learn_start(&self) -> Result<()> {
+        Err(unsupported("LearnStart"))
+    }
+
+    async fn learn_stop(&self) -> Result<()> {
+        Err(unsupported("LearnStop"))
+    }
+
+    async fn learn_status(&self) -> Result<String> {
+        Err(unsupported("LearnStatus"))
+    }
+
+    async fn learn_finish(&self) -> Result<()> {
+        Err(unsupported("LearnFinish"))
+    }
+
+    async fn learn_clear(&self) -> Result<()> {
+        Err(unsupported("LearnClear"))
+    }
+}
+
+fn unsupported(command: &str) -> anyhow::Error {
+    anyhow::anyhow!("{} is not supported by this daemon", command)
 }
 
 pub struct IpcServer {
     listener: UnixListener,
+    path: PathBuf,
 }
 
 impl IpcServer {
     pub async fn new() -> Result<Self> {
-        let socket_path = Path::new(SOCKET_PATH);
+        Self::bind(&socket_path()).await
+    }
 
-        if socket_path.exists() {
-            std::fs::remove_file(socket_path).context("Failed to remove existing socket file")?;
+    pub async fn bind(socket_path: &Path) -> Result<Self> {
+        if let Some(dir) = socket_path.parent() {
+            ensure_private_dir(dir)?;
         }
+        remove_stale_socket(socket_path)?;
 
         let listener = UnixListener::bind(socket_path).context("Failed to bind Unix socket")?;
+        std::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(0o600))?;
 
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let metadata = std::fs::metadata(socket_path)?;
-            let mut perms = metadata.permissions();
-            perms.set_mode(0o600);
-            std::fs::set_permissions(socket_path, perms)?;
-        }
+        tracing::info!("IPC server listening on {}", socket_path.display());
 
-        tracing::info!("IPC server listening on {}", SOCKET_PATH);
-
-        Ok(Self { listener })
+        Ok(Self {
+            listener,
+            path: socket_path.to_path_buf(),
+        })
     }
 
     pub async fn accept_connection(&self) -> Result<UnixStream> {
-        let (stream, _addr) = self
-            .listener
-            .accept()
-            .await
-            .context("Failed to accept connection")?;
-        Ok(stream)
+        loop {
+            let (stream, _addr) = self
+                .listener
+                .accept()
+                .await
+                .context("Failed to accept connection")?;
+            match verify_peer(&stream) {
+                Ok(()) => return Ok(stream),
+                Err(e) => tracing::warn!("Rejected IPC connection: {:#}", e),
+            }
+        }
     }
 
-    pub async fn handle_connection<F>(
+    pub async fn handle_connection(
         mut stream: UnixStream,
-        on_execute: impl Fn(String) -> Result<()>,
-        on_stop: impl Fn() -> Result<()>,
-        on_learn_start: impl Fn() -> Result<()>,
-        on_learn_stop: impl Fn() -> Result<()>,
-        on_learn_status: impl Fn() -> Result<String>,
-        on_learn_finish: F,
-        on_learn_clear: impl Fn() -> Result<()>,
-    ) -> Result<()>
-    where
-        F: std::future::Future<Output = Result<()>> + Send,
-    {
-        let mut buffer = vec![0u8; 4096];
-        let n = stream.read(&mut buffer).await?;
-
-        if n == 0 {
+        handler: &dyn DaemonHandler,
+    ) -> Result<()> {
+        let mut request = Vec::new();
+        BufReader::new(&mut stream)
+            .take(MAX_REQUEST_BYTES + 1)
+            .read_until(b'\n', &mut request)
+            .await?;
+
+        if request.iter().all(u8::is_ascii_whitespace) {
             return Ok(());
         }
 
-        let request = String::from_utf8_lossy(&buffer[..n]);
-        let response = Self::process_command_async(
-            &request,
-            on_execute,
-            on_stop,
-            on_learn_start,
-            on_learn_stop,
-            on_learn_status,
-            on_learn_finish,
-            on_learn_clear,
-        )
-        .await;
+        let response = if request.len() as u64 > MAX_REQUEST_BYTES {
+            IpcResponse::error(format!(
+                "Request is larger than {} MiB",
+                MAX_REQUEST_BYTES / (1024 * 1024)
+            ))
+        } else {
+            match serde_json::from_slice(&request) {
+                Ok(IpcCommand::Events) => {
+                    return Self::stream_events(stream, handler.events()).await
+                }
+                Ok(command) => Self::dispatch(handler, command).await,
+                Err(e) => IpcResponse::error(format!("Invalid command: {}", e)),
+            }
+        };
 
         let response_json = serde_json::to_string(&response)?;
         stream.write_all(response_json.as_bytes()).await?;
@@ -117,179 +225,351 @@ impl IpcServer {
         Ok(())
     }
 
-    async fn process_command_async<F>(
-        request: &str,
-        on_execute: impl Fn(String) -> Result<()>,
-        on_stop: impl Fn() -> Result<()>,
-        on_learn_start: impl Fn() -> Result<()>,
-        on_learn_stop: impl Fn() -> Result<()>,
-        on_learn_status: impl Fn() -> Result<String>,
-        on_learn_finish: F,
-        on_learn_clear: impl Fn() -> Result<()>,
-    ) -> IpcResponse
-    where
-        F: std::future::Future<Output = Result<()>> + Send,
-    {
-        let command: Result<IpcCommand, _> = serde_json::from_str(request);
+    async fn stream_events(
+        mut stream: UnixStream,
+        mut events: broadcast::Receiver<TimedEvent>,
+    ) -> Result<()> {
+        let (mut reader, mut writer) = stream.split();
+        let subscribed = serde_json::to_string(&IpcResponse::success("Subscribed to events"))?;
+        writer
+            .write_all(format!("{}\n", subscribed).as_bytes())
+            .await?;
+
+        let mut buffer = [0u8; 64];
+        loop {
+            let event = tokio::select! {
+                read = reader.read(&mut buffer) => match read {
+                    Ok(0) | Err(_) => return Ok(()),
+                    Ok(_) => continue,
+                },
+                event = events.recv() => event,
+            };
+
+            match event {
+                Ok(event) => {
+                    let line = serde_json::to_string(&event)?;
+                    if writer
+                        .write_all(format!("{}\n", line).as_bytes())
+                        .await
+                        .is_err()
+                    {
+                        return Ok(());
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    tracing::warn!("Event subscriber fell behind, skipped {} event(s)", missed);
+                }
+                Err(RecvError::Closed) => return Ok(()),
+            }
+        }
+    }
 
+    pub async fn dispatch(handler: &dyn DaemonHandler, command: IpcCommand) -> IpcResponse {
         match command {
-            Ok(IpcCommand::Execute { command }) => match on_execute(command) {
-                Ok(_) => IpcResponse::success("Command execution started"),
+            IpcCommand::Execute {
+                command,
+                output_schema,
+                wait,
+            } => match handler.execute(command, output_schema, wait).await {
+                Ok(Some(TaskOutput {
+                    text,
+                    result: Some(result),
+                })) => IpcResponse::success(text).with_data(result),
+                Ok(Some(TaskOutput { text, result: None })) => IpcResponse::success(text),
+                Ok(None) => IpcResponse::success("Command execution started"),
                 Err(e) => IpcResponse::error(format!("Failed to execute command: {}", e)),
             },
-            Ok(IpcCommand::Status) => IpcResponse::success("Daemon is running"),
-            Ok(IpcCommand::Stop) => match on_stop() {
+            IpcCommand::Continue { command } => {
+                match handler.continue_conversation(command).await {
+                    Ok(_) => IpcResponse::success("Follow-up command started"),
+                    Err(e) => IpcResponse::error(format!("Failed to continue: {}", e)),
+                }
+            }
+            IpcCommand::Ask { question } => match handler.ask(question).await {
+                Ok(answer) => IpcResponse::success(answer),
+                Err(e) => IpcResponse::error(format!("Failed to answer question: {}", e)),
+            },
+            IpcCommand::Plan { command } => match handler.plan(command).await {
+                Ok(plan) => IpcResponse::success(plan),
+                Err(e) => IpcResponse::error(format!("Failed to plan command: {}", e)),
+            },
+            IpcCommand::Status => match handler.status().await {
+                Ok(status) => match serde_json::to_value(&status) {
+                    Ok(data) => {
+                        IpcResponse::success(format!("Daemon is running ({})", status.state))
+                            .with_data(data)
+                    }
+                    Err(e) => IpcResponse::error(format!("Failed to encode status: {}", e)),
+                },
+                Err(e) => IpcResponse::error(format!("Failed to get status: {}", e)),
+            },
+            IpcCommand::Events => IpcResponse::error("Events are only available as a stream"),
+            IpcCommand::Stop => match handler.stop().await {
                 Ok(_) => IpcResponse::success("Emergency stop triggered"),
                 Err(e) => IpcResponse::error(format!("Failed to stop: {}", e)),
             },
-            Ok(IpcCommand::LearnStart) => match on_learn_start() {
+            IpcCommand::Pause => match handler.pause().await {
+                Ok(_) => IpcResponse::success("Task paused"),
+                Err(e) => IpcResponse::error(format!("Failed to pause: {}", e)),
+            },
+            IpcCommand::Resume => match handler.resume().await {
+                Ok(_) => IpcResponse::success("Task resumed"),
+                Err(e) => IpcResponse::error(format!("Failed to resume: {}", e)),
+            },
+            IpcCommand::LearnStart => match handler.learn_start().await {
                 Ok(_) => IpcResponse::success("Learning mode started"),
                 Err(e) => IpcResponse::error(format!("Failed to start learning: {}", e)),
             },
-            Ok(IpcCommand::LearnStop) => match on_learn_stop() {
+            IpcCommand::LearnStop => match handler.learn_stop().await {
                 Ok(_) => IpcResponse::success("Learning mode stopped"),
                 Err(e) => IpcResponse::error(format!("Failed to stop learning: {}", e)),
             },
-            Ok(IpcCommand::LearnStatus) => match on_learn_status() {
+            IpcCommand::LearnStatus => match handler.learn_status().await {
                 Ok(status) => IpcResponse::success(status),
                 Err(e) => IpcResponse::error(format!("Failed to get learning status: {}", e)),
             },
-            Ok(IpcCommand::LearnFinish) => match on_learn_finish.await {
+            IpcCommand::LearnFinish => match handler.learn_finish().await {
                 Ok(_) => IpcResponse::success("Learning session finished"),
                 Err(e) => IpcResponse::error(format!("Failed to finish learning: {}", e)),
             },
-            Ok(IpcCommand::LearnClear) => match on_learn_clear() {
+            IpcCommand::LearnClear => match handler.learn_clear().await {
                 Ok(_) => IpcResponse::success("Learning history cleared"),
                 Err(e) => IpcResponse::error(format!("Failed to clear learning: {}", e)),
             },
-            Err(e) => IpcResponse::error(format!("Invalid command: {}", e)),
         }
     }
 }
 
 impl Drop for IpcServer {
     fn drop(&mut self) {
-        let _ = std::fs::remove_file(SOCKET_PATH);
+        let _ = std::fs::remove_file(&self.path);
     }
 }
 
-pub async fn send_execute_command(command: &str) -> Result<()> {
-    let ipc_command = IpcCommand::Execute {
-        command: command.to_string(),
+pub fn socket_path() -> PathBuf {
+    let dir = match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from("/tmp").join(format!("superctrl-{}", current_uid())),
     };
-    let response = send_command(&ipc_command).await?;
+    dir.join(SOCKET_NAME)
+}
 
-    if response.success {
-        println!("{}", response.message);
-        Ok(())
-    } else {
-        anyhow::bail!("{}", response.message)
-    }
+fn current_uid() -> u32 {
+    nix::unistd::getuid().as_raw()
 }
 
-pub async fn send_status_command() -> Result<String> {
-    let ipc_command = IpcCommand::Status;
-    let response = send_command(&ipc_command).await?;
+fn ensure_private_dir(dir: &Path) -> Result<()> {
+    if !dir.exists() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create socket directory {}", dir.display()))?;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
 
-    if response.success {
-        Ok(response.message)
-    } else {
-        anyhow::bail!("{}", response.message)
+    let metadata = std::fs::symlink_metadata(dir)
+        .with_context(|| format!("Failed to inspect socket directory {}", dir.display()))?;
+    if !metadata.is_dir() {
+        anyhow::bail!("Socket directory {} is not a directory", dir.display());
+    }
+    if metadata.uid() != current_uid() {
+        anyhow::bail!(
+            "Socket directory {} belongs to uid {}, not to the current user",
+            dir.display(),
+            metadata.uid()
+        );
+    }
+    if metadata.mode() & 0o077 != 0 {
+        anyhow::bail!(
+            "Socket directory {} is accessible by other users (mode {:o})",
+            dir.display(),
+            metadata.mode() & 0o777
+        );
     }
+    Ok(())
 }
 
-pub async fn send_stop_command() -> Result<()> {
-    let ipc_command = IpcCommand::Stop;
-    let response = send_command(&ipc_command).await?;
+fn remove_stale_socket(socket_path: &Path) -> Result<()> {
+    let metadata = match std::fs::symlink_metadata(socket_path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to inspect {}", socket_path.display()))
+        }
+    };
+    if !metadata.file_type().is_socket() {
+        anyhow::bail!("{} exists and is not a socket", socket_path.display());
+    }
+    if metadata.uid() != current_uid() {
+        anyhow::bail!("{} belongs to another user", socket_path.display());
+    }
 
-    if response.success {
-        Ok(())
-    } else {
-        anyhow::bail!("{}", response.message)
+    match std::os::unix::net::UnixStream::connect(socket_path) {
+        Ok(_) => anyhow::bail!(
+            "Another superctrl daemon is already listening on {}",
+            socket_path.display()
+        ),
+        Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => {
+            tracing::info!("Removing stale socket {}", socket_path.display());
+            std::fs::remove_file(socket_path).context("Failed to remove stale socket file")
+        }
+        Err(e) => Err(e)
+            .with_context(|| format!("Failed to check existing socket {}", socket_path.display())),
     }
 }
 
-pub async fn send_learn_start_command() -> Result<()> {
-    let ipc_command = IpcCommand::LearnStart;
-    let response = send_command(&ipc_command).await?;
-
-    if response.success {
-        Ok(())
-    } else {
-        anyhow::bail!("{}", response.message)
+pub fn verify_peer(stream: &UnixStream) -> Result<()> {
+    let credentials = stream
+        .peer_cred()
+        .context("Failed to read peer credentials")?;
+    let uid = current_uid();
+    if credentials.uid() != uid {
+        anyhow::bail!("peer uid {} does not match uid {}", credentials.uid(), uid);
     }
+    Ok(())
 }
 
-pub async fn send_learn_stop_command() -> Result<()> {
-    let ipc_command = IpcCommand::LearnStop;
-    let response = send_command(&ipc_command).await?;
+pub async fn connect(socket_path: &Path) -> Result<UnixStream> {
+    let stream = UnixStream::connect(socket_path)
+        .await
+        .context("Failed to connect to daemon. Is superctrl daemon running?")?;
+    verify_peer(&stream).context("Refusing to talk to the daemon socket")?;
+    Ok(stream)
+}
 
-    if response.success {
-        Ok(())
-    } else {
-        anyhow::bail!("{}", response.message)
-    }
+async fn write_request(command: &IpcCommand) -> Result<UnixStream> {
+    let mut stream = connect(&socket_path()).await?;
+
+    let command_json = serde_json::to_string(command)?;
+    stream.write_all(command_json.as_bytes()).await?;
+    stream.write_all(b"\n").await?;
+    stream.flush().await?;
+    Ok(stream)
 }
 
-pub async fn send_learn_status_command() -> Result<String> {
-    let ipc_command = IpcCommand::LearnStatus;
-    let response = send_command(&ipc_command).await?;
+pub async fn send_request(command: IpcCommand) -> Result<IpcResponse> {
+    let mut stream = write_request(&command).await?;
 
-    if response.success {
-        Ok(response.message)
-    } else {
-        anyhow::bail!("{}", response.message)
+    let mut buffer = Vec::new();
+    stream.read_to_end(&mut buffer).await?;
+    let response: IpcResponse =
+        serde_json::from_slice(&buffer).context("Failed to parse response from daemon")?;
+
+    if !response.success {
+        anyhow::bail!("{}", response.message);
     }
+    Ok(response)
 }
 
-pub async fn send_learn_finish_command() -> Result<()> {
-    let ipc_command = IpcCommand::LearnFinish;
-    let response = send_command(&ipc_command).await?;
+pub async fn send_execute_command(
+    command: &str,
+    output_schema: Option<serde_json::Value>,
+    wait: bool,
+) -> Result<IpcResponse> {
+    send_request(IpcCommand::Execute {
+        command: command.to_string(),
+        output_schema,
+        wait,
+    })
+    .await
+}
 
-    if response.success {
-        Ok(())
-    } else {
-        anyhow::bail!("{}", response.message)
-    }
+pub async fn send_continue_command(command: &str) -> Result<()> {
+    send_request(IpcCommand::Continue {
+        command: command.to_string(),
+    })
+    .await?;
+    Ok(())
 }
 
-pub async fn send_learn_clear_command() -> Result<()> {
-    let ipc_command = IpcCommand::LearnClear;
-    let response = send_command(&ipc_command).await?;
+pub async fn send_ask_command(question: &str) -> Result<String> {
+    let response = send_request(IpcCommand::Ask {
+        question: question.to_string(),
+    })
+    .await?;
+    Ok(response.message)
+}
 
-    if response.success {
-        Ok(())
-    } else {
-        anyhow::bail!("{}", response.message)
-    }
+pub async fn send_plan_command(command: &str) -> Result<String> {
+    let response = send_request(IpcCommand::Plan {
+        command: command.to_string(),
+    })
+    .await?;
+    Ok(response.message)
 }
 
-async fn send_command(command: &IpcCommand) -> Result<IpcResponse> {
-    let mut stream = UnixStream::connect(SOCKET_PATH)
-        .await
-        .context("Failed to connect to daemon. Is superctrl daemon running?")?;
+pub async fn send_status_command() -> Result<DaemonStatus> {
+    let response = send_request(IpcCommand::Status).await?;
+    let data = response.data.with_context(|| {
+        format!(
+            "Daemon did not return a detailed status: {}",
+            response.message
+        )
+    })?;
+    serde_json::from_value(data).context("Failed to parse the daemon status")
+}
 
-    let command_json = serde_json::to_string(command)?;
-    stream.write_all(command_json.as_bytes()).await?;
-    stream.flush().await?;
+pub async fn send_stop_command() -> Result<()> {
+    send_request(IpcCommand::Stop).await.map(drop)
+}
 
-    let mut buffer = vec![0u8; 4096];
-    let n = stream.read(&mut buffer).await?;
+pub async fn send_pause_command() -> Result<()> {
+    send_request(IpcCommand::Pause).await.map(drop)
+}
+
+pub async fn send_resume_command() -> Result<()> {
+    send_request(IpcCommand::Resume).await.map(drop)
+}
+
+pub async fn send_learn_start_command() -> Result<()> {
+    send_request(IpcCommand::LearnStart).await.map(drop)
+}
 
+pub async fn send_learn_stop_command() -> Result<()> {
+    send_request(IpcCommand::LearnStop).await.map(drop)
+}
+
+pub async fn send_learn_status_command() -> Result<String> {
+    Ok(send_request(IpcCommand::LearnStatus).await?.message)
+}
+
+pub async fn send_learn_finish_command() -> Result<()> {
+    send_request(IpcCommand::LearnFinish).await.map(drop)
+}
+
+pub async fn send_learn_clear_command() -> Result<()> {
+    send_request(IpcCommand::LearnClear).await.map(drop)
+}
+
+pub async fn send_events_command(mut on_event: impl FnMut(TimedEvent)) -> Result<()> {
+    let stream = write_request(&IpcCommand::Events).await?;
+
+    let mut lines = BufReader::new(stream).lines();
+    let Some(first) = lines.next_line().await? else {
+        anyhow::bail!("Daemon closed the connection");
+    };
     let response: IpcResponse =
-        serde_json::from_slice(&buffer[..n]).context("Failed to parse response from daemon")?;
+        serde_json::from_str(&first).context("Failed to parse response from daemon")?;
+    if !response.success {
+        anyhow::bail!("{}", response.message);
+    }
 
-    Ok(response)
+    while let Some(line) = lines.next_line().await? {
+        match serde_json::from_str(&line) {
+            Ok(event) => on_event(event),
+            Err(e) => tracing::warn!("Skipping unrecognized event from daemon: {}", e),
+        }
+    }
+    Ok(())
 }
 
 pub fn is_daemon_running() -> bool {
-    if !Path::new(SOCKET_PATH).exists() {
+    let socket_path = socket_path();
+    if !socket_path.exists() {
         return false;
     }
 
     let rt = tokio::runtime::Runtime::new().ok();
     if let Some(rt) = rt {
-        rt.block_on(async { UnixStream::connect(SOCKET_PATH).await.is_ok() })
+        rt.block_on(async { connect(&socket_path).await.is_ok() })
     } else {
         false
     }
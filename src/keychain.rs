@@ -0,0 +1,118 @@
+use anyhow::Result;
+
+use crate::secrets::SecretStore;
+
+pub struct KeychainStore;
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use anyhow::{Context, Result};
+    use security_framework::base::Error;
+    use security_framework::item::{ItemClass, ItemSearchOptions, Limit};
+    use security_framework::passwords::{
+        delete_generic_password, get_generic_password, set_generic_password,
+    };
+
+    const SERVICE: &str = "superctrl";
+    const ITEM_NOT_FOUND: i32 = -25300;
+
+    fn not_found(error: &Error) -> bool {
+        error.code() == ITEM_NOT_FOUND
+    }
+
+    pub fn get(name: &str) -> Result<Option<String>> {
+        let password = match get_generic_password(SERVICE, name) {
+            Ok(password) => password,
+            Err(e) if not_found(&e) => return Ok(None),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to read '{}' from the keychain", name))
+            }
+        };
+
+        let password = String::from_utf8(password).context("Keychain item is not valid UTF-8")?;
+        Ok(Some(password).filter(|password| !password.is_empty()))
+    }
+
+    pub fn set(name: &str, value: &str) -> Result<()> {
+        set_generic_password(SERVICE, name, value.as_bytes())
+            .with_context(|| format!("Failed to store '{}' in the keychain", name))
+    }
+
+    pub fn delete(name: &str) -> Result<()> {
+        match delete_generic_password(SERVICE, name) {
+            Err(e) if !not_found(&e) => {
+                Err(e).with_context(|| format!("Failed to remove '{}' from the keychain", name))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn names() -> Result<Vec<String>> {
+        let results = match ItemSearchOptions::new()
+            .class(ItemClass::generic_password())
+            .service(SERVICE)
+            .load_attributes(true)
+            .limit(Limit::All)
+            .search()
+        {
+            Ok(results) => results,
+            Err(e) if not_found(&e) => return Ok(Vec::new()),
+            Err(e) => return Err(e).context("Failed to list keychain items"),
+        };
+
+        let mut names: Vec<String> = results
+            .iter()
+            .filter_map(|result| result.simplify_dict()?.remove("acct"))
+            .collect();
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl SecretStore for KeychainStore {
+    fn description(&self) -> String {
+        "macOS keychain".to_string()
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>> {
+        macos::get(name)
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<()> {
+        macos::set(name, value)
+    }
+
+    fn delete(&self, name: &str) -> Result<()> {
+        macos::delete(name)
+    }
+
+    fn names(&self) -> Result<Vec<String>> {
+        macos::names()
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+impl SecretStore for KeychainStore {
+    fn description(&self) -> String {
+        "macOS keychain".to_string()
+    }
+
+    fn get(&self, _name: &str) -> Result<Option<String>> {
+        anyhow::bail!("The keychain secrets backend is only available on macOS")
+    }
+
+    fn set(&self, _name: &str, _value: &str) -> Result<()> {
+        anyhow::bail!("The keychain secrets backend is only available on macOS")
+    }
+
+    fn delete(&self, _name: &str) -> Result<()> {
+        anyhow::bail!("The keychain secrets backend is only available on macOS")
+    }
+
+    fn names(&self) -> Result<Vec<String>> {
+        anyhow::bail!("The keychain secrets backend is only available on macOS")
+    }
+}
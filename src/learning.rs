@@ -9,6 +9,8 @@ use std::sync::{
 };
 use std::time::Duration;
 
+use crate::input;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Event {
     KeyPress {
@@ -280,7 +282,7 @@ impl LearningDatabase {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SessionStats {
     pub total_events: i64,
     pub keypress_count: i64,
@@ -294,7 +296,7 @@ pub struct LearningCollector {
     state: LearningState,
     stop_flag: Arc<AtomicBool>,
     disable_clipboard_monitoring: bool,
-    keyboard_thread_handle: Option<std::thread::JoinHandle<()>>,
+    keyboard_subscribed: bool,
 }
 
 impl LearningCollector {
@@ -304,14 +306,10 @@ impl LearningCollector {
             state: LearningState::Stopped,
             stop_flag,
             disable_clipboard_monitoring: false,
-            keyboard_thread_handle: None,
+            keyboard_subscribed: false,
         }
     }
 
-    pub fn with_path(path: PathBuf, stop_flag: Arc<AtomicBool>) -> Result<Self> {
-        Self::with_path_and_clipboard_setting(path, stop_flag, false)
-    }
-
     pub fn with_path_and_clipboard_setting(
         path: PathBuf,
         stop_flag: Arc<AtomicBool>,
@@ -323,7 +321,7 @@ impl LearningCollector {
             state: LearningState::Stopped,
             stop_flag,
             disable_clipboard_monitoring,
-            keyboard_thread_handle: None,
+            keyboard_subscribed: false,
         })
     }
 
@@ -332,19 +330,13 @@ impl LearningCollector {
             anyhow::bail!("Learning collector is already active");
         }
 
-        if self.keyboard_thread_handle.is_some() {
-            anyhow::bail!("Keyboard monitor thread already exists");
-        }
-
         self.stop_flag.store(false, Ordering::Release);
         self.state = LearningState::Active;
 
-        let db_for_keyboard = self.database.clone();
-        let stop_flag_for_keyboard = self.stop_flag.clone();
-        let keyboard_handle = std::thread::spawn(move || {
-            Self::keyboard_monitor(db_for_keyboard, stop_flag_for_keyboard);
-        });
-        self.keyboard_thread_handle = Some(keyboard_handle);
+        if !self.keyboard_subscribed {
+            Self::keyboard_monitor(self.database.clone(), self.stop_flag.clone());
+            self.keyboard_subscribed = true;
+        }
 
         if !self.disable_clipboard_monitoring {
             tracing::warn!("⚠️  Clipboard monitoring is ENABLED. Clipboard content previews will be stored in the learning database.");
@@ -370,8 +362,8 @@ impl LearningCollector {
         let database_for_callback = database.clone();
         let stop_flag_for_callback = stop_flag.clone();
 
-        let callback = move |event: rdev::Event| {
-            if stop_flag_for_callback.load(Ordering::Acquire) {
+        let callback = move |event: &rdev::Event, synthetic: bool| {
+            if synthetic || stop_flag_for_callback.load(Ordering::Acquire) {
                 return;
             }
 
@@ -404,10 +396,7 @@ impl LearningCollector {
             }
         };
 
-        if let Err(e) = rdev::listen(callback) {
-            tracing::error!("Keyboard monitoring error: {:?}", e);
-        }
-        tracing::warn!("Keyboard monitor thread exited (rdev::listen() terminated)");
+        input::subscribe(callback);
     }
 
     fn clipboard_monitor(database: Arc<Mutex<LearningDatabase>>, stop_flag: Arc<AtomicBool>) {
@@ -470,12 +459,6 @@ impl LearningCollector {
         self.stop_flag.store(true, Ordering::Release);
         self.state = LearningState::Stopped;
 
-        if let Some(handle) = self.keyboard_thread_handle.take() {
-            if let Err(e) = handle.join() {
-                tracing::error!("Error joining keyboard monitor thread: {:?}", e);
-            }
-        }
-
         Ok(())
     }
 
@@ -491,75 +474,6 @@ impl LearningCollector {
         self.stop_flag.load(Ordering::Acquire)
     }
 
-    pub async fn generate_system_prompt(
-        &self,
-        api_key: &str,
-        system_prompt_path: PathBuf,
-    ) -> Result<String> {
-        let summary = {
-            let db = self.database.lock().unwrap();
-            db.aggregate_data()?
-        };
-
-        let prompt_text = format!(
-            "Analyze this workflow data and create a system prompt (max 2000 words) describing this user's working style, applications, patterns, and habits. Format as a system prompt for an AI assistant.\n\n{}",
-            summary
-        );
-
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .context("Failed to create HTTP client")?;
-
-        let request_body = serde_json::json!({
-            "model": "claude-sonnet-4-20250514",
-            "max_tokens": 4096,
-            "messages": [{
-                "role": "user",
-                "content": prompt_text
-            }]
-        });
-
-        let response = client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request_body)
-            .send()
-            .await
-            .context("Failed to call Anthropic API")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            anyhow::bail!("Anthropic API returned error: {} - {}", status, error_text);
-        }
-
-        let response_json: serde_json::Value = response
-            .json()
-            .await
-            .context("Failed to parse Anthropic response")?;
-
-        let generated_text = response_json["content"]
-            .as_array()
-            .and_then(|arr| arr.first())
-            .and_then(|block| block["text"].as_str())
-            .context("Failed to extract text from Anthropic response")?;
-
-        if let Some(parent) = system_prompt_path.parent() {
-            std::fs::create_dir_all(parent).context("Failed to create system prompt directory")?;
-        }
-
-        std::fs::write(&system_prompt_path, generated_text).with_context(|| {
-            format!("Failed to write system prompt to {:?}", system_prompt_path)
-        })?;
-
-        tracing::info!("System prompt saved to {:?}", system_prompt_path);
-
-        Ok(generated_text.to_string())
-    }
-
     pub fn clear_database(&mut self) -> Result<()> {
         let mut db = self.database.lock().unwrap();
         let conn = db.connection_mut();
@@ -575,6 +489,74 @@ impl LearningCollector {
     }
 }
 
+pub async fn generate_system_prompt(
+    database: Arc<Mutex<LearningDatabase>>,
+    api_key: &str,
+    system_prompt_path: PathBuf,
+) -> Result<String> {
+    let summary = {
+        let db = database.lock().unwrap();
+        db.aggregate_data()?
+    };
+
+    let prompt_text = format!(
+        "Analyze this workflow data and create a system prompt (max 2000 words) describing this user's working style, applications, patterns, and habits. Format as a system prompt for an AI assistant.\n\n{}",
+        summary
+    );
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let request_body = serde_json::json!({
+        "model": "claude-sonnet-4-20250514",
+        "max_tokens": 4096,
+        "messages": [{
+            "role": "user",
+            "content": prompt_text
+        }]
+    });
+
+    let response = client
+        .post("https://api.anthropic.com/v1/messages")
+        .header("x-api-key", api_key)
+        .header("anthropic-version", "2023-06-01")
+        .header("content-type", "application/json")
+        .json(&request_body)
+        .send()
+        .await
+        .context("Failed to call Anthropic API")?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        anyhow::bail!("Anthropic API returned error: {} - {}", status, error_text);
+    }
+
+    let response_json: serde_json::Value = response
+        .json()
+        .await
+        .context("Failed to parse Anthropic response")?;
+
+    let generated_text = response_json["content"]
+        .as_array()
+        .and_then(|arr| arr.first())
+        .and_then(|block| block["text"].as_str())
+        .context("Failed to extract text from Anthropic response")?;
+
+    if let Some(parent) = system_prompt_path.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create system prompt directory")?;
+    }
+
+    std::fs::write(&system_prompt_path, generated_text)
+        .with_context(|| format!("Failed to write system prompt to {:?}", system_prompt_path))?;
+
+    tracing::info!("System prompt saved to {:?}", system_prompt_path);
+
+    Ok(generated_text.to_string())
+}
+
 #[derive(Default)]
 struct ModifierState {
     ctrl: bool,
@@ -1,9 +1,28 @@
 pub mod automation;
 pub mod computer_use;
+pub mod config;
+pub mod events;
+pub mod gui;
+pub mod history;
+pub mod hotkey;
+pub mod http_api;
+pub mod input;
+pub mod ipc;
+pub mod keychain;
 pub mod learning;
+pub mod mcp;
+pub mod quick_command;
+pub mod redact;
+pub mod reload;
 pub mod screenshot;
+pub mod secrets;
+pub mod status;
+pub mod task;
+pub mod viewer;
 
-pub use automation::{Action, MacAutomation, MouseButton};
-pub use computer_use::ComputerUseAgent;
+pub use automation::{Action, AutomationBackend, MacAutomation, MouseButton, SimulatedAutomation};
+pub use computer_use::{AgentMode, ComputerUseAgent, Conversation, ConversationStore, TaskPlan};
 pub use learning::{Event, LearningCollector, LearningDatabase, LearningState};
+pub use mcp::{McpClient, McpManager, McpServerConfig};
 pub use screenshot::ScreenCapture;
+pub use task::{CancellationToken, ControlSource, TaskCancelled, TaskRunner};
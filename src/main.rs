@@ -2,23 +2,42 @@ mod automation;
 mod cli;
 mod computer_use;
 mod config;
+mod events;
 mod gui;
+mod history;
+mod history_window;
 mod hotkey;
+mod http_api;
+mod input;
 mod ipc;
+mod keychain;
 mod learning;
+mod mcp;
 mod menu_bar;
 mod notifications;
 mod preferences;
+mod quick_command;
+mod redact;
+mod reload;
 mod screenshot;
+mod secrets;
+mod status;
+mod task;
+mod viewer;
 
 use anyhow::{Context, Result};
 
 use cli::Cli;
-use config::Config;
+use config::{Config, ConfigSources, LiveSettings, PolicySettings, Settings};
 use gui::create_shared_state;
-use hotkey::EmergencyStop;
+use hotkey::{HotkeyAction, HotkeyRegistry};
 use learning::LearningCollector;
-use std::sync::{Arc, Mutex};
+use reload::{ConfigWatcher, ReloadPlan, ReloadStatus};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 fn check_macrowhisper_service() {
     use std::process::Command;
@@ -44,12 +63,600 @@ fn check_macrowhisper_service() {
     }
 }
 
+#[derive(Clone)]
+struct TaskContext {
+    state: gui::SharedGuiState,
+    api_key: String,
+    settings: LiveSettings,
+    mcp: Arc<mcp::McpManager>,
+    conversations: Arc<Mutex<computer_use::ConversationStore>>,
+    history: Option<history::SharedTaskHistory>,
+}
+
+fn spawn_agent_task(
+    ctx: &TaskContext,
+    mode: computer_use::AgentMode,
+    command: String,
+    output_schema: Option<serde_json::Value>,
+    conversation: Option<computer_use::Conversation>,
+) -> tokio::sync::oneshot::Receiver<Result<ipc::TaskOutput>> {
+    let (result_tx, result_rx) = tokio::sync::oneshot::channel();
+    let TaskContext {
+        state,
+        api_key,
+        settings,
+        mcp,
+        conversations,
+        history,
+    } = ctx.clone();
+    let settings = settings.get();
+    let mode_name = match mode {
+        computer_use::AgentMode::Act => "act",
+        computer_use::AgentMode::ReadOnly => "ask",
+        computer_use::AgentMode::DryRun => "dry_run",
+    };
+
+    {
+        let mut gui_state = state.lock().unwrap();
+        if mode == computer_use::AgentMode::Act {
+            gui_state.last_command = Some(command.clone());
+        }
+        let record_kind = match mode {
+            computer_use::AgentMode::Act => "voice_command",
+            computer_use::AgentMode::ReadOnly => "ask",
+            computer_use::AgentMode::DryRun => "dry_run",
+        };
+        gui_state.add_action(gui::ActionRecord::new(
+            record_kind.to_string(),
+            command.clone(),
+        ));
+        gui_state
+            .event_bus
+            .publish(events::DaemonEvent::TaskQueued {
+                command: command.clone(),
+                mode: mode_name.to_string(),
+            });
+    }
+
+    let recorder = history.and_then(|history| {
+        let started = history.lock().unwrap().start_task(&command, mode_name);
+        match started {
+            Ok(task_id) => Some(history::TaskRecorder::new(history, task_id)),
+            Err(e) => {
+                tracing::warn!("Failed to record task in history: {:#}", e);
+                None
+            }
+        }
+    });
+
+    let task = state.lock().unwrap().task_runner().begin();
+    let events = state.lock().unwrap().agent_events.clone();
+
+    std::thread::spawn(move || {
+        let turn = task.wait_turn();
+        state
+            .lock()
+            .unwrap()
+            .update_status(gui::AppState::Working(command.clone()));
+        events.emit(events::AgentEvent::TaskStarted {
+            command: command.clone(),
+            max_steps: computer_use::MAX_ITERATIONS,
+        });
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let result: Result<ipc::TaskOutput> = rt.block_on(async {
+            task.token().check()?;
+            let keep_conversation =
+                mode != computer_use::AgentMode::DryRun && output_schema.is_none();
+            let mut agent = computer_use::ComputerUseAgent::new(api_key, task.token())
+                .context("Failed to create agent")?
+                .with_model(settings.model.clone())
+                .with_api_url(settings.api_url.clone())
+                .with_screenshot_quality(settings.screenshot_quality)
+                .with_redaction(settings.redaction.clone())
+                .with_mcp_tools(mcp)
+                .with_mode(mode)
+                .with_events(events.clone());
+            if let Some(recorder) = &recorder {
+                agent = agent.with_recorder(recorder.clone());
+            }
+            match secrets::open_store(&settings.secrets) {
+                Ok(store) => agent = agent.with_secrets(Arc::from(store)),
+                Err(e) => tracing::warn!("Secret placeholders are unavailable: {:#}", e),
+            }
+            if let Some(schema) = output_schema {
+                agent = agent.with_output_schema(schema)?;
+            }
+            if let Some(conversation) = conversation {
+                agent = agent.with_conversation(conversation);
+            }
+
+            let text = match mode {
+                computer_use::AgentMode::DryRun => agent
+                    .plan_command(&command)
+                    .await
+                    .map(|plan| plan.to_string()),
+                _ => agent.execute_command(&command).await,
+            };
+
+            if keep_conversation {
+                conversations
+                    .lock()
+                    .unwrap()
+                    .save(agent.conversation().clone());
+            }
+
+            Ok(ipc::TaskOutput {
+                text: text?,
+                result: agent.structured_result().cloned(),
+            })
+        });
+
+        let (status, output_text, error_text) = match &result {
+            Ok(output) => (
+                history::TaskStatus::Completed,
+                Some(output.text.clone()),
+                None,
+            ),
+            Err(e) if task::is_cancelled_error(e) => {
+                (history::TaskStatus::Stopped, None, Some(e.to_string()))
+            }
+            Err(e) => (history::TaskStatus::Failed, None, Some(format!("{:#}", e))),
+        };
+        if let Some(recorder) = &recorder {
+            recorder.finish(status, output_text.as_deref(), error_text.as_deref());
+        }
+        match &result {
+            Ok(output) => {
+                tracing::info!("Command completed: {}", output.text);
+                let mut gui_state = state.lock().unwrap();
+                gui_state.update_status(gui::AppState::Idle);
+            }
+            Err(e) if task::is_cancelled_error(e) => {
+                tracing::info!("Command stopped: {}", command);
+                let mut gui_state = state.lock().unwrap();
+                gui_state.update_status(gui::AppState::Idle);
+            }
+            Err(e) => {
+                tracing::error!("Command failed: {:#}", e);
+                let mut gui_state = state.lock().unwrap();
+                gui_state.record_error(format!("Command failed: {}", e));
+            }
+        }
+
+        drop(turn);
+        drop(task);
+        events.emit(events::AgentEvent::TaskFinished {
+            status,
+            message: error_text,
+        });
+        let _ = result_tx.send(result);
+    });
+
+    result_rx
+}
+
+struct Daemon {
+    task: TaskContext,
+    learning_collector: Option<Arc<Mutex<LearningCollector>>>,
+    system_prompt_path: std::path::PathBuf,
+    event_bus: events::EventBus,
+}
+
+impl Daemon {
+    fn learning_collector(&self) -> Result<&Arc<Mutex<LearningCollector>>> {
+        self.learning_collector
+            .as_ref()
+            .context("Learning feature is disabled")
+    }
+
+    async fn run_to_completion(
+        &self,
+        mode: computer_use::AgentMode,
+        command: String,
+    ) -> Result<ipc::TaskOutput> {
+        spawn_agent_task(&self.task, mode, command, None, None)
+            .await
+            .context("Agent task ended without a result")?
+    }
+}
+
+#[async_trait::async_trait]
+impl ipc::DaemonHandler for Daemon {
+    async fn execute(
+        &self,
+        command: String,
+        output_schema: Option<serde_json::Value>,
+        wait: bool,
+    ) -> Result<Option<ipc::TaskOutput>> {
+        tracing::info!("Received execute command via IPC: {}", command);
+        let wait_for_result = wait || output_schema.is_some();
+        let result = spawn_agent_task(
+            &self.task,
+            computer_use::AgentMode::Act,
+            command,
+            output_schema,
+            None,
+        );
+        if !wait_for_result {
+            return Ok(None);
+        }
+        result
+            .await
+            .context("Agent task ended without a result")?
+            .map(Some)
+    }
+
+    async fn continue_conversation(&self, command: String) -> Result<()> {
+        tracing::info!("Received continue command via IPC: {}", command);
+        let conversation = self
+            .task
+            .conversations
+            .lock()
+            .unwrap()
+            .recent()
+            .context("No recent conversation to continue")?;
+        drop(spawn_agent_task(
+            &self.task,
+            computer_use::AgentMode::Act,
+            command,
+            None,
+            Some(conversation),
+        ));
+        Ok(())
+    }
+
+    async fn ask(&self, question: String) -> Result<String> {
+        tracing::info!("Received ask command via IPC: {}", question);
+        let output = self
+            .run_to_completion(computer_use::AgentMode::ReadOnly, question)
+            .await?;
+        Ok(output.text)
+    }
+
+    async fn plan(&self, command: String) -> Result<String> {
+        tracing::info!("Received plan command via IPC: {}", command);
+        let output = self
+            .run_to_completion(computer_use::AgentMode::DryRun, command)
+            .await?;
+        Ok(output.text)
+    }
+
+    async fn status(&self) -> Result<status::DaemonStatus> {
+        let learning = learning_status(self.learning_collector.as_ref());
+        let gui_state = self.task.state.lock().unwrap();
+        Ok(status::DaemonStatus::collect(&gui_state, learning))
+    }
+
+    fn events(&self) -> tokio::sync::broadcast::Receiver<events::TimedEvent> {
+        self.event_bus.subscribe()
+    }
+
+    async fn stop(&self) -> Result<()> {
+        tracing::info!("Received stop command via IPC");
+        let gui_state = self.task.state.lock().unwrap();
+        gui_state.trigger_stop(task::ControlSource::Ipc);
+        Ok(())
+    }
+
+    async fn pause(&self) -> Result<()> {
+        tracing::info!("Received pause command via IPC");
+        let gui_state = self.task.state.lock().unwrap();
+        if gui_state.trigger_pause(task::ControlSource::Ipc) == 0 {
+            anyhow::bail!("No task is running");
+        }
+        Ok(())
+    }
+
+    async fn resume(&self) -> Result<()> {
+        tracing::info!("Received resume command via IPC");
+        let gui_state = self.task.state.lock().unwrap();
+        if !gui_state.is_paused() {
+            anyhow::bail!("No task is paused");
+        }
+        gui_state.trigger_resume(task::ControlSource::Ipc);
+        Ok(())
+    }
+
+    async fn learn_start(&self) -> Result<()> {
+        tracing::info!("Received learn start command via IPC");
+        self.learning_collector()?.lock().unwrap().start()?;
+        self.event_bus.publish(events::DaemonEvent::LearningStarted);
+        Ok(())
+    }
+
+    async fn learn_stop(&self) -> Result<()> {
+        tracing::info!("Received learn stop command via IPC");
+        self.learning_collector()?.lock().unwrap().stop()?;
+        self.event_bus.publish(events::DaemonEvent::LearningStopped);
+        Ok(())
+    }
+
+    async fn learn_status(&self) -> Result<String> {
+        tracing::info!("Received learn status command via IPC");
+        let Some(collector) = self.learning_collector.as_ref() else {
+            return Ok("Learning feature is disabled".to_string());
+        };
+        let status_text = if collector.lock().unwrap().state().is_active() {
+            "Learning is active"
+        } else {
+            "Learning is stopped"
+        };
+        Ok(status_text.to_string())
+    }
+
+    async fn learn_finish(&self) -> Result<()> {
+        tracing::info!("Received learn finish command via IPC");
+        let database = self.learning_collector()?.lock().unwrap().database();
+        learning::generate_system_prompt(
+            database,
+            &self.task.api_key,
+            self.system_prompt_path.clone(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn learn_clear(&self) -> Result<()> {
+        tracing::info!("Received learn clear command via IPC");
+        self.learning_collector()?.lock().unwrap().clear_database()
+    }
+}
+
+fn start_http_api(rt: &tokio::runtime::Runtime, settings: &Settings, daemon: Arc<Daemon>) {
+    let token = match secrets::open_store(&settings.secrets)
+        .and_then(|store| secrets::http_token(store.as_ref(), false))
+    {
+        Ok(token) => token,
+        Err(e) => {
+            tracing::error!("HTTP API disabled: failed to load its token: {:#}", e);
+            return;
+        }
+    };
+    let listener = match http_api::bind(settings.http.port) {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!("HTTP API disabled: {:#}", e);
+            return;
+        }
+    };
+
+    let api = http_api::HttpApi::new(token, daemon)
+        .with_allowed_origins(settings.http.allowed_origins.clone());
+    rt.spawn(async move {
+        if let Err(e) = api.serve(listener).await {
+            tracing::error!("{:#}", e);
+        }
+    });
+}
+
+fn learning_status(collector: Option<&Arc<Mutex<LearningCollector>>>) -> status::LearningStatus {
+    let Some(collector) = collector else {
+        return status::LearningStatus::default();
+    };
+    let (active, database) = {
+        let collector = collector.lock().unwrap();
+        (collector.state().is_active(), collector.database())
+    };
+    let events = match database.lock().unwrap().get_session_stats() {
+        Ok(stats) => Some(stats),
+        Err(e) => {
+            tracing::warn!("Failed to read learning stats: {:#}", e);
+            None
+        }
+    };
+    status::LearningStatus {
+        enabled: true,
+        active,
+        events,
+    }
+}
+
+async fn monitor_api_reachability(
+    state: gui::SharedGuiState,
+    settings: LiveSettings,
+    api_key: String,
+) {
+    let mut interval = tokio::time::interval(status::API_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        let settings = settings.get();
+        let api_status = status::check_api(&api_key, &settings.api_url).await;
+        if api_status.health != status::ApiHealth::Ok {
+            tracing::warn!(
+                "Anthropic API check against {} failed ({:?}): {}",
+                settings.api_url,
+                api_status.health,
+                api_status.error.as_deref().unwrap_or("unknown error")
+            );
+        }
+        state.lock().unwrap().api_status = Some(api_status);
+    }
+}
+
+fn handle_hotkey_action(ctx: &TaskContext, action: HotkeyAction) {
+    match action {
+        HotkeyAction::Stop | HotkeyAction::TogglePause => {}
+        HotkeyAction::RepeatLast => {
+            let (running, last_command) = {
+                let gui_state = ctx.state.lock().unwrap();
+                (gui_state.tasks.is_running(), gui_state.last_command.clone())
+            };
+            if running {
+                tracing::info!("Ignoring repeat-last hotkey: a task is already running");
+                return;
+            }
+            let Some(command) = last_command else {
+                tracing::info!("Ignoring repeat-last hotkey: no command has run yet");
+                return;
+            };
+
+            tracing::info!("Repeating last command via hotkey: {}", command);
+            drop(spawn_agent_task(
+                ctx,
+                computer_use::AgentMode::Act,
+                command,
+                None,
+                None,
+            ));
+        }
+        HotkeyAction::QuickCommand => {
+            menu_bar::request_window(menu_bar::WindowRequest::QuickCommand);
+        }
+    }
+}
+
+struct PolicyMonitors {
+    tasks: task::TaskRunner,
+    settings: LiveSettings,
+    failsafe_started: AtomicBool,
+    auto_pause_started: AtomicBool,
+}
+
+impl PolicyMonitors {
+    fn ensure_started(&self, policy: &PolicySettings) {
+        if let Some(corner) = policy.failsafe_corner {
+            if !self.failsafe_started.swap(true, Ordering::SeqCst) {
+                let settings = self.settings.clone();
+                hotkey::CornerFailsafe::new(corner).start(self.tasks.clone(), move || {
+                    settings.get().policy.failsafe_corner
+                });
+            }
+        }
+
+        if policy.auto_pause_on_input && !self.auto_pause_started.swap(true, Ordering::SeqCst) {
+            let settings = self.settings.clone();
+            input::UserInputDetector::start(
+                self.tasks.clone(),
+                move || settings.get().policy.auto_pause_on_input,
+                || tracing::info!("⏸ Task paused because of user input"),
+            );
+        }
+    }
+}
+
+struct ConfigReloader {
+    sources: ConfigSources,
+    settings: LiveSettings,
+    state: gui::SharedGuiState,
+    conversations: Arc<Mutex<computer_use::ConversationStore>>,
+    monitors: Arc<PolicyMonitors>,
+    hotkeys: mpsc::Sender<hotkey::HotkeyConfig>,
+}
+
+impl ConfigReloader {
+    async fn run(self) {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => Some(hangup),
+            Err(e) => {
+                tracing::warn!("Failed to listen for SIGHUP: {}", e);
+                None
+            }
+        };
+        let mut watcher = ConfigWatcher::new(self.sources.path.clone());
+        let mut interval = tokio::time::interval(CONFIG_POLL_INTERVAL);
+
+        loop {
+            let reason = tokio::select! {
+                Some(()) = async {
+                    match hangup.as_mut() {
+                        Some(hangup) => hangup.recv().await,
+                        None => std::future::pending().await,
+                    }
+                } => {
+                    watcher.changed();
+                    "SIGHUP"
+                }
+                _ = interval.tick() => {
+                    if !watcher.changed() {
+                        continue;
+                    }
+                    "file changed"
+                }
+            };
+            self.reload(reason);
+        }
+    }
+
+    fn reload(&self, reason: &str) {
+        tracing::info!("Reloading config from {:?} ({})", self.sources.path, reason);
+        let bus = self.state.lock().unwrap().event_bus.clone();
+        let status = match self.apply() {
+            Ok(plan) => {
+                bus.publish(events::DaemonEvent::ConfigReloaded {
+                    changed: plan.changed.clone(),
+                    restart_required: plan.restart_required.clone(),
+                });
+                let status = ReloadStatus::Applied {
+                    at: chrono::Local::now(),
+                    plan,
+                };
+                tracing::info!("{}", status.describe());
+                status
+            }
+            Err(e) => {
+                tracing::error!("Config reload failed: {:#}", e);
+                bus.publish(events::DaemonEvent::ConfigReloadFailed {
+                    error: format!("{:#}", e),
+                });
+                ReloadStatus::Failed {
+                    at: chrono::Local::now(),
+                    error: format!("{:#}", e),
+                }
+            }
+        };
+        self.state.lock().unwrap().config_reload = Some(status);
+    }
+
+    fn apply(&self) -> Result<ReloadPlan> {
+        let settings = Settings::load_layered(&self.sources, |var| std::env::var(var).ok())?;
+        if let Err(errors) = settings.validate() {
+            anyhow::bail!("Invalid settings: {}", errors.join("; "));
+        }
+
+        let previous = self.settings.replace(settings.clone());
+        let plan = ReloadPlan::new(&previous, &settings);
+
+        notifications::configure(settings.notifications.clone());
+        self.conversations
+            .lock()
+            .unwrap()
+            .set_timeout(Duration::from_secs(
+                settings.policy.conversation_timeout_secs,
+            ));
+        self.monitors.ensure_started(&settings.policy);
+        if plan.hotkeys_changed() {
+            let _ = self.hotkeys.send(settings.hotkeys.clone());
+        }
+        if !plan.restart_required.is_empty() {
+            tracing::warn!(
+                "Restart superctrl to apply: {}",
+                plan.restart_required.join(", ")
+            );
+        }
+
+        Ok(plan)
+    }
+}
+
 fn main() -> Result<()> {
-    tracing_subscriber::fmt::init();
+    tracing_subscriber::fmt()
+        .with_writer(redact::RedactingStdout)
+        .init();
 
     let cli = Cli::parse_args();
 
-    if cli.is_status_command() || cli.is_stop_command() || cli.get_execute_command().is_some() {
+    if cli.is_status_command()
+        || cli.is_events_command()
+        || cli.is_stop_command()
+        || cli.is_pause_or_resume_command()
+        || cli.is_ask_command()
+        || cli.is_config_command()
+        || cli.is_auth_command()
+        || cli.get_execute_command().is_some()
+        || cli.get_continue_command().is_some()
+    {
         let rt = tokio::runtime::Runtime::new()?;
         return rt.block_on(cli::handle_cli_command(&cli));
     }
@@ -60,28 +667,78 @@ fn main() -> Result<()> {
 
     check_macrowhisper_service();
 
-    let config = Config::load()?;
+    let sources = cli.config_sources()?;
+    let config = Config::load(&sources)?;
+    let live_settings = LiveSettings::new(config.settings.clone());
+    notifications::configure(config.settings.notifications.clone());
 
     let state = create_shared_state();
+    state.lock().unwrap().settings_path = Some(config.settings_path.clone());
+    notifications::start(&state.lock().unwrap().event_bus);
 
     let learning_stop_flag = Arc::new(std::sync::atomic::AtomicBool::new(false));
     let learning_collector = if config.learning_enabled {
-        Some(Arc::new(Mutex::new(LearningCollector::with_path(
-            config.learning_db_path.clone(),
-            learning_stop_flag.clone(),
-        )?)))
+        Some(Arc::new(Mutex::new(
+            LearningCollector::with_path_and_clipboard_setting(
+                config.learning_db_path.clone(),
+                learning_stop_flag.clone(),
+                config.disable_clipboard_monitoring,
+            )?,
+        )))
     } else {
-        tracing::info!("Learning feature is disabled via SUPERCTRL_LEARNING_ENABLED");
+        tracing::info!(
+            "Learning feature is disabled in settings or via SUPERCTRL_LEARNING_ENABLED"
+        );
         None
     };
 
     let rt = tokio::runtime::Runtime::new()?;
     let _rt_guard = rt.enter();
 
-    let ipc_state = state.clone();
-    let api_key = config.api_key.clone();
-    let learning_collector_for_ipc = learning_collector.clone();
-    let system_prompt_path = config.system_prompt_path.clone();
+    let mcp_config = match mcp::McpConfigFile::load(&config.mcp_config_path) {
+        Ok(mcp_config) => mcp_config,
+        Err(e) => {
+            tracing::warn!("Failed to load MCP config: {:#}", e);
+            mcp::McpConfigFile::default()
+        }
+    };
+    let mcp_manager = Arc::new(rt.block_on(mcp::McpManager::start(&mcp_config)));
+
+    let task_history = match history::TaskHistory::new(config.history_db_path.clone()) {
+        Ok(task_history) => Some(Arc::new(Mutex::new(task_history))),
+        Err(e) => {
+            tracing::warn!("Task history disabled: {:#}", e);
+            None
+        }
+    };
+
+    let task_context = TaskContext {
+        state: state.clone(),
+        api_key: config.api_key.clone(),
+        settings: live_settings.clone(),
+        mcp: mcp_manager.clone(),
+        conversations: Arc::new(Mutex::new(computer_use::ConversationStore::new(
+            Duration::from_secs(config.settings.policy.conversation_timeout_secs),
+        ))),
+        history: task_history.clone(),
+    };
+
+    let hotkey_context = task_context.clone();
+    let task_context_conversations = task_context.conversations.clone();
+    let quick_command_paths = quick_command::QuickCommandPaths {
+        history: config.command_history_path.clone(),
+        routines: config.routines_path.clone(),
+    };
+
+    let daemon = Arc::new(Daemon {
+        task: task_context,
+        learning_collector: learning_collector.clone(),
+        system_prompt_path: config.system_prompt_path.clone(),
+        event_bus: state.lock().unwrap().event_bus.clone(),
+    });
+    if config.settings.http.enabled {
+        start_http_api(&rt, &config.settings, daemon.clone());
+    }
     rt.spawn(async move {
         match ipc::IpcServer::new().await {
             Ok(ipc_server) => {
@@ -89,231 +746,10 @@ fn main() -> Result<()> {
                 loop {
                     match ipc_server.accept_connection().await {
                         Ok(stream) => {
-                            let state_clone = ipc_state.clone();
-                            let api_key_clone = api_key.clone();
-                            let learning_collector_clone = learning_collector_for_ipc.clone();
-                            let system_prompt_path_clone = system_prompt_path.clone();
+                            let daemon = daemon.clone();
                             tokio::spawn(async move {
-                                let state_for_execute = state_clone.clone();
-                                let api_key_for_execute = api_key_clone.clone();
-                                let on_execute = move |command: String| {
-                                    tracing::info!("Received execute command via IPC: {}", command);
-                                    let _ = notifications::notify_command_received(&command);
-                                    let mut gui_state = state_for_execute.lock().unwrap();
-                                    gui_state
-                                        .update_status(gui::AppState::Working(command.clone()));
-                                    let action = gui::ActionRecord::new(
-                                        "voice_command".to_string(),
-                                        command.clone(),
-                                    );
-                                    gui_state.add_action(action);
-                                    drop(gui_state);
-
-                                    let state_for_task = state_for_execute.clone();
-                                    let api_key_for_task = api_key_for_execute.clone();
-                                    std::thread::spawn(move || {
-                                        let rt = tokio::runtime::Runtime::new().unwrap();
-                                        rt.block_on(async {
-                                            let stop_flag = {
-                                                let gui_state = state_for_task.lock().unwrap();
-                                                gui_state.get_stop_flag()
-                                            };
-
-                                            let mut agent = match computer_use::ComputerUseAgent::new(
-                                                api_key_for_task,
-                                                stop_flag,
-                                            ) {
-                                                Ok(agent) => agent,
-                                                Err(e) => {
-                                                    tracing::error!("Failed to create agent: {}", e);
-                                                    let mut gui_state = state_for_task.lock().unwrap();
-                                                    gui_state.update_status(gui::AppState::Error(
-                                                        format!("Failed to create agent: {}", e),
-                                                    ));
-                                                    return;
-                                                }
-                                            };
-
-                                            match agent.execute_command(&command).await {
-                                                Ok(result) => {
-                                                    tracing::info!("Command completed: {}", result);
-                                                    let _ = notifications::notify_command_completed(&command);
-                                                    let mut gui_state = state_for_task.lock().unwrap();
-                                                    gui_state.update_status(gui::AppState::Idle);
-                                                }
-                                                Err(e) => {
-                                                    tracing::error!("Command failed: {}", e);
-                                                    let _ = notifications::notify_command_failed(&command, &e.to_string());
-                                                    let mut gui_state = state_for_task.lock().unwrap();
-                                                    gui_state.update_status(gui::AppState::Error(
-                                                        format!("Command failed: {}", e),
-                                                    ));
-                                                }
-                                            }
-                                        });
-                                    });
-
-                                    Ok(())
-                                };
-
-                                let state_clone_for_stop = state_clone.clone();
-                                let on_stop = move || {
-                                    tracing::info!("Received stop command via IPC");
-                                    let gui_state = state_clone_for_stop.lock().unwrap();
-                                    gui_state.trigger_stop();
-                                    drop(gui_state);
-
-                                    let mut gui_state = state_clone_for_stop.lock().unwrap();
-                                    gui_state.update_status(gui::AppState::Idle);
-                                    Ok(())
-                                };
-
-                                let learning_collector_for_start = learning_collector_clone.clone();
-                                let on_learn_start = move || {
-                                    tracing::info!("Received learn start command via IPC");
-                                    match learning_collector_for_start.as_ref() {
-                                        Some(collector) => {
-                                            let mut c = collector.lock().unwrap();
-                                            c.start()
-                                        }
-                                        None => anyhow::bail!("Learning feature is disabled"),
-                                    }
-                                };
-
-                                let learning_collector_for_stop = learning_collector_clone.clone();
-                                let on_learn_stop = move || {
-                                    tracing::info!("Received learn stop command via IPC");
-                                    match learning_collector_for_stop.as_ref() {
-                                        Some(collector) => {
-                                            let mut c = collector.lock().unwrap();
-                                            c.stop()
-                                        }
-                                        None => anyhow::bail!("Learning feature is disabled"),
-                                    }
-                                };
-
-                                let learning_collector_for_status = learning_collector_clone.clone();
-                                let on_learn_status = move || {
-                                    tracing::info!("Received learn status command via IPC");
-                                    match learning_collector_for_status.as_ref() {
-                                        Some(collector) => {
-                                            let c = collector.lock().unwrap();
-                                            let state = c.state();
-                                            let is_active = state.is_active();
-                                            let status_text = if is_active {
-                                                "Learning is active"
-                                            } else {
-                                                "Learning is stopped"
-                                            };
-                                            Ok(status_text.to_string())
-                                        }
-                                        None => Ok("Learning feature is disabled".to_string()),
-                                    }
-                                };
-
-                                let learning_collector_for_finish = learning_collector_clone.clone();
-                                let api_key_for_finish = api_key_clone.clone();
-                                let system_prompt_path_for_finish = system_prompt_path_clone.clone();
-                                let on_learn_finish = async move {
-                                    tracing::info!("Received learn finish command via IPC");
-                                    match learning_collector_for_finish.as_ref() {
-                                        Some(collector) => {
-                                            let api_key = api_key_for_finish.clone();
-                                            let path = system_prompt_path_for_finish.clone();
-                                            let database = {
-                                                let c = collector.lock().unwrap();
-                                                c.database().clone()
-                                            };
-                                            let summary = {
-                                                let db = database.lock().unwrap();
-                                                db.aggregate_data()
-                                            }?;
-
-                                            let prompt_text = format!(
-                                                "Analyze this workflow data and create a system prompt (max 2000 words) describing this user's working style, applications, patterns, and habits. Format as a system prompt for an AI assistant.\n\n{}",
-                                                summary
-                                            );
-
-                                            let client = reqwest::Client::builder()
-                                                .timeout(std::time::Duration::from_secs(30))
-                                                .build()
-                                                .context("Failed to create HTTP client")?;
-
-                                            let request_body = serde_json::json!({
-                                                "model": "claude-sonnet-4-20250514",
-                                                "max_tokens": 4096,
-                                                "messages": [{
-                                                    "role": "user",
-                                                    "content": prompt_text
-                                                }]
-                                            });
-
-                                            let response = client
-                                                .post("https://api.anthropic.com/v1/messages")
-                                                .header("x-api-key", &api_key)
-                                                .header("anthropic-version", "2023-06-01")
-                                                .header("content-type", "application/json")
-                                                .json(&request_body)
-                                                .send()
-                                                .await
-                                                .context("Failed to call Anthropic API")?;
-
-                                            if !response.status().is_success() {
-                                                let status = response.status();
-                                                let error_text = response.text().await.unwrap_or_default();
-                                                anyhow::bail!("Anthropic API returned error: {} - {}", status, error_text);
-                                            }
-
-                                            let response_json: serde_json::Value = response
-                                                .json()
-                                                .await
-                                                .context("Failed to parse Anthropic response")?;
-
-                                            let generated_text = response_json["content"]
-                                                .as_array()
-                                                .and_then(|arr| arr.first())
-                                                .and_then(|block| block["text"].as_str())
-                                                .context("Failed to extract text from Anthropic response")?;
-
-                                            if let Some(parent) = path.parent() {
-                                                std::fs::create_dir_all(parent).context("Failed to create system prompt directory")?;
-                                            }
-
-                                            std::fs::write(&path, generated_text)
-                                                .with_context(|| format!("Failed to write system prompt to {:?}", path))?;
-
-                                            tracing::info!("System prompt saved to {:?}", path);
-
-                                            Ok(())
-                                        }
-                                        None => anyhow::bail!("Learning feature is disabled"),
-                                    }
-                                };
-
-                                let learning_collector_for_clear = learning_collector_clone.clone();
-                                let on_learn_clear = move || {
-                                    tracing::info!("Received learn clear command via IPC");
-                                    match learning_collector_for_clear.as_ref() {
-                                        Some(collector) => {
-                                            let mut c = collector.lock().unwrap();
-                                            c.clear_database()
-                                        }
-                                        None => anyhow::bail!("Learning feature is disabled"),
-                                    }
-                                };
-
                                 if let Err(e) =
-                                    ipc::IpcServer::handle_connection(
-                                        stream,
-                                        on_execute,
-                                        on_stop,
-                                        on_learn_start,
-                                        on_learn_stop,
-                                        on_learn_status,
-                                        on_learn_finish,
-                                        on_learn_clear,
-                                    )
-                                        .await
+                                    ipc::IpcServer::handle_connection(stream, daemon.as_ref()).await
                                 {
                                     tracing::error!("Error handling IPC connection: {}", e);
                                 }
@@ -331,53 +767,94 @@ fn main() -> Result<()> {
         }
     });
 
-    let emergency_stop = match EmergencyStop::new() {
-        Ok(es) => {
-            if let Err(e) = es.register_hotkey() {
-                tracing::warn!("Failed to register emergency stop hotkey: {}", e);
-                tracing::warn!(
-                    "  The app will still work, but emergency stop (⌘⇧⎋) won't be available."
-                );
-                None
-            } else {
-                Some(es)
+    let hotkey_registry = match HotkeyRegistry::new() {
+        Ok(registry) => {
+            if let Err(e) = registry.apply(&config.settings.hotkeys) {
+                tracing::warn!("Failed to apply hotkey configuration: {:#}", e);
+                tracing::warn!("  The app will still work, but global hotkeys won't be available.");
+                state.lock().unwrap().hotkey_error = Some(format!("{:#}", e));
             }
+            Some(registry)
         }
         Err(e) => {
-            tracing::warn!("Failed to initialize emergency stop: {}", e);
-            tracing::warn!(
-                "  The app will still work, but emergency stop (⌘⇧⎋) won't be available."
-            );
+            tracing::warn!("Failed to initialize global hotkeys: {}", e);
+            tracing::warn!("  The app will still work, but global hotkeys won't be available.");
+            state.lock().unwrap().hotkey_error = Some(format!("{:#}", e));
             None
         }
     };
 
-    if let Some(ref es) = emergency_stop {
-        let stop_flag = es.get_stop_flag();
-        EmergencyStop::start_listener(stop_flag.clone());
-
-        let state_for_listener = state.clone();
-        std::thread::spawn(move || loop {
-            if stop_flag.load(std::sync::atomic::Ordering::Acquire) {
-                tracing::info!("🛑 Emergency stop triggered via hotkey");
-                let _ = notifications::notify_emergency_stop();
-                let mut gui_state = state_for_listener.lock().unwrap();
-                gui_state.update_status(gui::AppState::Idle);
-                drop(gui_state);
-
-                stop_flag.store(false, std::sync::atomic::Ordering::Release);
-            }
-            std::thread::sleep(std::time::Duration::from_millis(100));
+    if let Some(ref registry) = hotkey_registry {
+        let tasks = state.lock().unwrap().task_runner();
+        state.lock().unwrap().hotkeys = registry.bindings();
+        registry.start_listener(tasks, move |action| {
+            handle_hotkey_action(&hotkey_context, action)
         });
     }
 
-    std::thread::spawn(move || {
-        rt.block_on(async {
-            loop {
-                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-            }
-        })
+    let monitors = Arc::new(PolicyMonitors {
+        tasks: state.lock().unwrap().task_runner(),
+        settings: live_settings.clone(),
+        failsafe_started: AtomicBool::new(false),
+        auto_pause_started: AtomicBool::new(false),
     });
+    monitors.ensure_started(&config.settings.policy);
+
+    rt.spawn(monitor_api_reachability(
+        state.clone(),
+        live_settings.clone(),
+        config.api_key.clone(),
+    ));
+
+    let (hotkey_tx, hotkey_rx) = mpsc::channel();
+    rt.spawn(
+        ConfigReloader {
+            sources,
+            settings: live_settings,
+            state: state.clone(),
+            conversations: task_context_conversations,
+            monitors,
+            hotkeys: hotkey_tx,
+        }
+        .run(),
+    );
+
+    std::thread::spawn(move || rt.block_on(std::future::pending::<()>()));
+
+    let reload_state = state.clone();
+    let apply_hotkey_updates = move || {
+        while let Ok(hotkeys) = hotkey_rx.try_recv() {
+            let Some(registry) = &hotkey_registry else {
+                continue;
+            };
+            match registry.apply(&hotkeys) {
+                Ok(()) => {
+                    let mut gui_state = reload_state.lock().unwrap();
+                    gui_state.hotkeys = registry.bindings();
+                    gui_state.hotkey_error = None;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to apply reloaded hotkeys: {:#}", e);
+                    let mut gui_state = reload_state.lock().unwrap();
+                    gui_state
+                        .event_bus
+                        .publish(events::DaemonEvent::ConfigReloadFailed {
+                            error: format!("{:#}", e),
+                        });
+                    gui_state.hotkey_error = Some(format!("{:#}", e));
+                    gui_state.config_reload = Some(ReloadStatus::Failed {
+                        at: chrono::Local::now(),
+                        error: format!("{:#}", e),
+                    });
+                }
+            }
+        }
+    };
 
-    menu_bar::run_menu_bar_loop(state)
+    menu_bar::run_menu_bar_loop(
+        state,
+        quick_command_paths,
+        task_history,
+        apply_hotkey_updates,
+    )
 }
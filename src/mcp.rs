@@ -0,0 +1,479 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::{mpsc, oneshot};
+
+const PROTOCOL_VERSION: &str = "2024-11-05";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+const TOOL_NAME_SEPARATOR: &str = "__";
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct McpConfigFile {
+    #[serde(rename = "mcpServers", default)]
+    pub servers: BTreeMap<String, McpServerConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpServerConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+impl McpConfigFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read MCP config at {:?}", path))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse MCP config at {:?}", path))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpTool {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(rename = "inputSchema", default = "empty_object_schema")]
+    pub input_schema: Value,
+}
+
+fn empty_object_schema() -> Value {
+    json!({ "type": "object", "properties": {} })
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct McpToolResult {
+    #[serde(default)]
+    pub content: Vec<Value>,
+    #[serde(rename = "isError", default)]
+    pub is_error: bool,
+}
+
+impl McpToolResult {
+    pub fn to_anthropic_content(&self) -> Value {
+        let blocks: Vec<Value> = self
+            .content
+            .iter()
+            .map(|item| match item["type"].as_str() {
+                Some("text") => json!({
+                    "type": "text",
+                    "text": item["text"].as_str().unwrap_or_default()
+                }),
+                Some("image") => json!({
+                    "type": "image",
+                    "source": {
+                        "type": "base64",
+                        "media_type": item["mimeType"].as_str().unwrap_or("image/png"),
+                        "data": item["data"].as_str().unwrap_or_default()
+                    }
+                }),
+                Some("resource") => {
+                    let resource = &item["resource"];
+                    let text = match resource["text"].as_str() {
+                        Some(text) => text.to_string(),
+                        None => resource.to_string(),
+                    };
+                    json!({ "type": "text", "text": text })
+                }
+                _ => json!({ "type": "text", "text": item.to_string() }),
+            })
+            .collect();
+
+        if blocks.is_empty() {
+            json!([{ "type": "text", "text": "(no content)" }])
+        } else {
+            json!(blocks)
+        }
+    }
+}
+
+type PendingRequests = Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value>>>>>;
+
+pub struct McpClient {
+    name: String,
+    tools: Vec<McpTool>,
+    outgoing: mpsc::UnboundedSender<String>,
+    pending: PendingRequests,
+    next_id: AtomicU64,
+    _child: Child,
+}
+
+impl McpClient {
+    pub async fn connect(name: &str, config: &McpServerConfig) -> Result<Self> {
+        let mut child = Command::new(&config.command)
+            .args(&config.args)
+            .envs(&config.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to launch MCP server '{}'", name))?;
+
+        let mut stdin = child.stdin.take().context("MCP server stdin unavailable")?;
+        let stdout = child
+            .stdout
+            .take()
+            .context("MCP server stdout unavailable")?;
+        let stderr = child
+            .stderr
+            .take()
+            .context("MCP server stderr unavailable")?;
+
+        let (outgoing, mut outgoing_rx) = mpsc::unbounded_channel::<String>();
+        let pending: PendingRequests = Arc::new(Mutex::new(HashMap::new()));
+
+        let server_name = name.to_string();
+        tokio::spawn(async move {
+            while let Some(message) = outgoing_rx.recv().await {
+                let line = format!("{}\n", message);
+                if let Err(e) = stdin.write_all(line.as_bytes()).await {
+                    tracing::error!("Failed to write to MCP server '{}': {}", server_name, e);
+                    break;
+                }
+                let _ = stdin.flush().await;
+            }
+        });
+
+        let server_name = name.to_string();
+        let pending_for_reader = pending.clone();
+        let outgoing_for_reader = outgoing.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            loop {
+                match lines.next_line().await {
+                    Ok(Some(line)) if line.trim().is_empty() => {}
+                    Ok(Some(line)) => {
+                        Self::handle_message(
+                            &server_name,
+                            &line,
+                            &pending_for_reader,
+                            &outgoing_for_reader,
+                        );
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        tracing::error!("Failed to read from MCP server '{}': {}", server_name, e);
+                        break;
+                    }
+                }
+            }
+
+            tracing::warn!("MCP server '{}' closed its output", server_name);
+            let mut pending = pending_for_reader.lock().unwrap();
+            for (_, sender) in pending.drain() {
+                let _ = sender.send(Err(anyhow::anyhow!("MCP server '{}' exited", server_name)));
+            }
+        });
+
+        let server_name = name.to_string();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                tracing::debug!("[mcp:{}] {}", server_name, line);
+            }
+        });
+
+        let mut client = Self {
+            name: name.to_string(),
+            tools: Vec::new(),
+            outgoing,
+            pending,
+            next_id: AtomicU64::new(1),
+            _child: child,
+        };
+
+        client
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": {
+                        "name": "superctrl",
+                        "version": env!("CARGO_PKG_VERSION")
+                    }
+                }),
+            )
+            .await
+            .with_context(|| format!("MCP server '{}' failed to initialize", name))?;
+        client.notify("notifications/initialized", json!({}))?;
+
+        client.tools = client.list_tools().await?;
+        tracing::info!(
+            "Connected to MCP server '{}' ({} tools)",
+            name,
+            client.tools.len()
+        );
+
+        Ok(client)
+    }
+
+    fn handle_message(
+        server_name: &str,
+        line: &str,
+        pending: &PendingRequests,
+        outgoing: &mpsc::UnboundedSender<String>,
+    ) {
+        let message: Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::warn!(
+                    "Ignoring malformed message from MCP server '{}': {}",
+                    server_name,
+                    e
+                );
+                return;
+            }
+        };
+
+        if let Some(method) = message["method"].as_str() {
+            if message.get("id").is_some() {
+                let response = if method == "ping" {
+                    json!({ "jsonrpc": "2.0", "id": message["id"], "result": {} })
+                } else {
+                    json!({
+                        "jsonrpc": "2.0",
+                        "id": message["id"],
+                        "error": { "code": -32601, "message": "Method not found" }
+                    })
+                };
+                let _ = outgoing.send(response.to_string());
+            }
+            return;
+        }
+
+        let Some(id) = message["id"].as_u64() else {
+            return;
+        };
+        let Some(sender) = pending.lock().unwrap().remove(&id) else {
+            return;
+        };
+
+        let result = if let Some(error) = message.get("error") {
+            Err(anyhow::anyhow!(
+                "MCP error {}: {}",
+                error["code"],
+                error["message"].as_str().unwrap_or("unknown error")
+            ))
+        } else {
+            Ok(message.get("result").cloned().unwrap_or(Value::Null))
+        };
+        let _ = sender.send(result);
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, sender);
+
+        let message = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params
+        });
+        if self.outgoing.send(message.to_string()).is_err() {
+            self.pending.lock().unwrap().remove(&id);
+            anyhow::bail!("MCP server '{}' is not running", self.name);
+        }
+
+        match tokio::time::timeout(REQUEST_TIMEOUT, receiver).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => anyhow::bail!("MCP server '{}' dropped the request", self.name),
+            Err(_) => {
+                self.pending.lock().unwrap().remove(&id);
+                anyhow::bail!(
+                    "MCP server '{}' timed out responding to {}",
+                    self.name,
+                    method
+                )
+            }
+        }
+    }
+
+    fn notify(&self, method: &str, params: Value) -> Result<()> {
+        let message = json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params
+        });
+        self.outgoing
+            .send(message.to_string())
+            .map_err(|_| anyhow::anyhow!("MCP server '{}' is not running", self.name))
+    }
+
+    async fn list_tools(&self) -> Result<Vec<McpTool>> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let params = match &cursor {
+                Some(cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+            let result = self.request("tools/list", params).await?;
+
+            let page: Vec<McpTool> = serde_json::from_value(result["tools"].clone())
+                .context("Failed to parse MCP tools/list response")?;
+            tools.extend(page);
+
+            match result["nextCursor"].as_str() {
+                Some(next) if !next.is_empty() => cursor = Some(next.to_string()),
+                _ => break,
+            }
+        }
+
+        Ok(tools)
+    }
+
+    pub async fn call_tool(&self, tool: &str, arguments: Value) -> Result<McpToolResult> {
+        let result = self
+            .request(
+                "tools/call",
+                json!({
+                    "name": tool,
+                    "arguments": arguments
+                }),
+            )
+            .await?;
+
+        serde_json::from_value(result).context("Failed to parse MCP tools/call response")
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn tools(&self) -> &[McpTool] {
+        &self.tools
+    }
+}
+
+pub fn qualified_tool_name(server: &str, tool: &str) -> String {
+    let sanitize = |s: &str| -> String {
+        s.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    };
+
+    let mut name = format!(
+        "{}{}{}",
+        sanitize(server),
+        TOOL_NAME_SEPARATOR,
+        sanitize(tool)
+    );
+    name.truncate(MAX_TOOL_NAME_LEN);
+    name
+}
+
+#[derive(Default)]
+pub struct McpManager {
+    clients: Vec<McpClient>,
+    routes: HashMap<String, (usize, String)>,
+}
+
+impl McpManager {
+    pub async fn start(config: &McpConfigFile) -> Self {
+        let mut manager = Self::default();
+
+        for (name, server) in &config.servers {
+            match McpClient::connect(name, server).await {
+                Ok(client) => manager.add_client(client),
+                Err(e) => {
+                    tracing::error!("Failed to start MCP server '{}': {:#}", name, e);
+                }
+            }
+        }
+
+        manager
+    }
+
+    pub fn add_client(&mut self, client: McpClient) {
+        let index = self.clients.len();
+        for tool in client.tools() {
+            let qualified = qualified_tool_name(client.name(), &tool.name);
+            if self.routes.contains_key(&qualified) {
+                tracing::warn!(
+                    "Skipping MCP tool '{}': name collides with another tool",
+                    qualified
+                );
+                continue;
+            }
+            self.routes.insert(qualified, (index, tool.name.clone()));
+        }
+        self.clients.push(client);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    pub fn handles(&self, tool_name: &str) -> bool {
+        self.routes.contains_key(tool_name)
+    }
+
+    pub fn tool_definitions(&self) -> Vec<Value> {
+        let mut definitions = Vec::new();
+
+        for (index, client) in self.clients.iter().enumerate() {
+            for tool in client.tools() {
+                let qualified = qualified_tool_name(client.name(), &tool.name);
+                let routed = matches!(
+                    self.routes.get(&qualified),
+                    Some((owner, name)) if *owner == index && *name == tool.name
+                );
+                if !routed {
+                    continue;
+                }
+
+                let description = match &tool.description {
+                    Some(description) => format!("[{}] {}", client.name(), description),
+                    None => format!("[{}] {}", client.name(), tool.name),
+                };
+                definitions.push(json!({
+                    "name": qualified,
+                    "description": description,
+                    "input_schema": tool.input_schema
+                }));
+            }
+        }
+
+        definitions
+    }
+
+    pub async fn call_tool(&self, tool_name: &str, input: Value) -> Result<McpToolResult> {
+        let (index, tool) = self
+            .routes
+            .get(tool_name)
+            .with_context(|| format!("Unknown MCP tool: {}", tool_name))?;
+        let client = &self.clients[*index];
+
+        tracing::info!("Calling MCP tool '{}' on server '{}'", tool, client.name());
+        client.call_tool(tool, input).await
+    }
+}
@@ -1,17 +1,34 @@
 use anyhow::Result;
+use iced::futures::stream;
+use iced::widget::horizontal_space;
+use iced::{window, Element, Subscription, Task};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
 use tray_icon::{
     menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
     TrayIcon, TrayIconBuilder,
 };
 
+use crate::events::{DaemonEvent, EventBus};
 use crate::gui::{AppState, SharedGuiState};
-use crate::preferences;
+use crate::history::SharedTaskHistory;
+use crate::history_window::{self, HistoryMessage, HistoryWindow};
+use crate::preferences::{self, PreferencesMessage, PreferencesWindow};
+use crate::quick_command::{self, QuickCommandMessage, QuickCommandPaths, QuickCommandWindow};
+use crate::task::ControlSource;
+use crate::viewer::{self, ViewerMessage, ViewerWindow};
 
 pub struct MenuBar {
     _tray_icon: TrayIcon,
     status_item: MenuItem,
     recent_actions_items: Vec<MenuItem>,
     stop_item: MenuItem,
+    pause_item: MenuItem,
+    quick_command_item: MenuItem,
+    history_item: MenuItem,
+    viewer_item: MenuItem,
     learning_toggle_item: MenuItem,
     generate_prompt_item: MenuItem,
     preferences_item: MenuItem,
@@ -44,6 +61,21 @@ impl MenuBar {
         stop_item.set_enabled(false);
         menu.append(&stop_item)?;
 
+        let pause_item = MenuItem::new("Pause Current Task", true, None);
+        pause_item.set_enabled(false);
+        menu.append(&pause_item)?;
+
+        menu.append(&PredefinedMenuItem::separator())?;
+
+        let quick_command_item = MenuItem::new("Quick Command...", true, None);
+        menu.append(&quick_command_item)?;
+
+        let history_item = MenuItem::new("Task History...", true, None);
+        menu.append(&history_item)?;
+
+        let viewer_item = MenuItem::new("Live Viewer...", true, None);
+        menu.append(&viewer_item)?;
+
         menu.append(&PredefinedMenuItem::separator())?;
 
         let learning_toggle_item = MenuItem::new("Start Learning", true, None);
@@ -73,6 +105,10 @@ impl MenuBar {
             status_item,
             recent_actions_items,
             stop_item,
+            pause_item,
+            quick_command_item,
+            history_item,
+            viewer_item,
             learning_toggle_item,
             generate_prompt_item,
             preferences_item,
@@ -124,11 +160,11 @@ impl MenuBar {
     pub fn update(&mut self) -> Result<()> {
         let state = self.state.lock().unwrap();
 
-        let status_text = format!(
-            "{} {}",
-            state.app_state.icon_symbol(),
-            state.app_state.status_text()
-        );
+        let paused = state.is_paused();
+        let status_text = match &state.app_state {
+            AppState::Working(_) if paused => "⏸ Paused".to_string(),
+            app_state => format!("{} {}", app_state.icon_symbol(), app_state.status_text()),
+        };
         self.status_item.set_text(status_text);
 
         let recent_actions = state.get_recent_actions();
@@ -143,12 +179,20 @@ impl MenuBar {
         match &state.app_state {
             AppState::Working(_) => {
                 self.stop_item.set_enabled(true);
+                self.pause_item.set_enabled(true);
             }
             _ => {
                 self.stop_item.set_enabled(false);
+                self.pause_item.set_enabled(false);
             }
         }
 
+        if paused {
+            self.pause_item.set_text("Resume Current Task");
+        } else {
+            self.pause_item.set_text("Pause Current Task");
+        }
+
         let learning_enabled = state.is_learning_enabled();
         if learning_enabled {
             self.learning_toggle_item.set_text("Stop Learning");
@@ -159,28 +203,38 @@ impl MenuBar {
         Ok(())
     }
 
-    pub fn handle_events(&self) -> Option<MenuBarEvent> {
-        if let Ok(event) = MenuEvent::receiver().try_recv() {
-            if event.id == self.stop_item.id() {
-                return Some(MenuBarEvent::StopTask);
-            } else if event.id == self.learning_toggle_item.id() {
-                let state = self.state.lock().unwrap();
-                let learning_enabled = state.is_learning_enabled();
-                drop(state);
-                if learning_enabled {
-                    return Some(MenuBarEvent::LearnStop);
-                } else {
-                    return Some(MenuBarEvent::LearnStart);
-                }
-            } else if event.id == self.generate_prompt_item.id() {
-                return Some(MenuBarEvent::LearnGenerate);
-            } else if event.id == self.preferences_item.id() {
-                return Some(MenuBarEvent::OpenPreferences);
-            } else if event.id == self.quit_item.id() {
-                return Some(MenuBarEvent::Quit);
+    pub fn handle_event(&self, event: &MenuEvent) -> Option<MenuBarEvent> {
+        if event.id == self.stop_item.id() {
+            Some(MenuBarEvent::StopTask)
+        } else if event.id == self.pause_item.id() {
+            let paused = self.state.lock().unwrap().is_paused();
+            if paused {
+                Some(MenuBarEvent::ResumeTask)
+            } else {
+                Some(MenuBarEvent::PauseTask)
             }
+        } else if event.id == self.quick_command_item.id() {
+            Some(MenuBarEvent::OpenQuickCommand)
+        } else if event.id == self.history_item.id() {
+            Some(MenuBarEvent::OpenHistory)
+        } else if event.id == self.viewer_item.id() {
+            Some(MenuBarEvent::OpenViewer)
+        } else if event.id == self.learning_toggle_item.id() {
+            let learning_enabled = self.state.lock().unwrap().is_learning_enabled();
+            if learning_enabled {
+                Some(MenuBarEvent::LearnStop)
+            } else {
+                Some(MenuBarEvent::LearnStart)
+            }
+        } else if event.id == self.generate_prompt_item.id() {
+            Some(MenuBarEvent::LearnGenerate)
+        } else if event.id == self.preferences_item.id() {
+            Some(MenuBarEvent::OpenPreferences)
+        } else if event.id == self.quit_item.id() {
+            Some(MenuBarEvent::Quit)
+        } else {
+            None
         }
-        None
     }
 
     pub fn update_icon(&mut self, state: &AppState) -> Result<()> {
@@ -193,6 +247,11 @@ impl MenuBar {
 #[derive(Debug, Clone)]
 pub enum MenuBarEvent {
     StopTask,
+    PauseTask,
+    ResumeTask,
+    OpenQuickCommand,
+    OpenHistory,
+    OpenViewer,
     LearnStart,
     LearnStop,
     LearnGenerate,
@@ -200,69 +259,402 @@ pub enum MenuBarEvent {
     Quit,
 }
 
-pub fn run_menu_bar_loop(state: SharedGuiState) -> Result<()> {
-    let mut menu_bar = MenuBar::new(state.clone())?;
-    let rt_handle = tokio::runtime::Handle::try_current().unwrap_or_else(|_| {
-        let rt = tokio::runtime::Runtime::new().unwrap();
-        rt.handle().clone()
-    });
-
-    loop {
-        if let Some(event) = menu_bar.handle_events() {
-            match event {
-                MenuBarEvent::StopTask => {
-                    tracing::info!("Stop task requested from menu bar");
-                    let gui_state = state.lock().unwrap();
-                    gui_state.trigger_stop();
-                    drop(gui_state);
-
-                    let mut gui_state = state.lock().unwrap();
-                    gui_state.update_status(AppState::Idle);
-                }
-                MenuBarEvent::LearnStart => {
-                    tracing::info!("Start learning requested from menu bar");
-                    if let Err(e) = rt_handle.block_on(crate::ipc::send_learn_start_command()) {
-                        tracing::error!("Failed to send learn start command: {}", e);
-                    } else {
-                        let mut gui_state = state.lock().unwrap();
-                        gui_state.set_learning_enabled(true);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowRequest {
+    QuickCommand,
+    History,
+    Viewer,
+    Preferences,
+}
+
+const TICK_INTERVAL: Duration = Duration::from_millis(500);
+
+static HOST_SENDER: OnceLock<broadcast::Sender<HostRequest>> = OnceLock::new();
+
+#[derive(Debug, Clone)]
+enum HostRequest {
+    Menu(MenuEvent),
+    OpenWindow(WindowRequest),
+}
+
+// iced windows must run on the main thread, which the window host owns.
+pub fn request_window(window: WindowRequest) {
+    let sent = HOST_SENDER
+        .get()
+        .is_some_and(|sender| sender.send(HostRequest::OpenWindow(window)).is_ok());
+    if !sent {
+        tracing::warn!(
+            "Cannot open the {:?} window: the menu bar is not running",
+            window
+        );
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    Tick,
+    Request(HostRequest),
+    Daemon(DaemonEvent),
+    Closed(window::Id),
+    QuickCommand(window::Id, QuickCommandMessage),
+    History(window::Id, HistoryMessage),
+    Viewer(window::Id, ViewerMessage),
+    Preferences(window::Id, PreferencesMessage),
+}
+
+enum HostedWindow {
+    QuickCommand(QuickCommandWindow),
+    History(HistoryWindow),
+    Viewer(ViewerWindow),
+    Preferences(Box<PreferencesWindow>),
+}
+
+impl HostedWindow {
+    fn request(&self) -> WindowRequest {
+        match self {
+            HostedWindow::QuickCommand(_) => WindowRequest::QuickCommand,
+            HostedWindow::History(_) => WindowRequest::History,
+            HostedWindow::Viewer(_) => WindowRequest::Viewer,
+            HostedWindow::Preferences(_) => WindowRequest::Preferences,
+        }
+    }
+}
+
+// One long-lived iced daemon owns the tray and every window, so windows can be
+// opened any number of times without blocking the menu bar.
+struct WindowHost {
+    state: SharedGuiState,
+    events: EventBus,
+    quick_command: QuickCommandPaths,
+    history: Option<SharedTaskHistory>,
+    on_tick: Box<dyn FnMut()>,
+    menu_bar: Option<MenuBar>,
+    windows: BTreeMap<window::Id, HostedWindow>,
+}
+
+impl WindowHost {
+    fn title(&self, id: window::Id) -> String {
+        match self.windows.get(&id) {
+            Some(HostedWindow::QuickCommand(_)) => quick_command::WINDOW_TITLE.to_string(),
+            Some(HostedWindow::History(_)) => history_window::WINDOW_TITLE.to_string(),
+            Some(HostedWindow::Viewer(_)) => viewer::WINDOW_TITLE.to_string(),
+            Some(HostedWindow::Preferences(_)) => preferences::WINDOW_TITLE.to_string(),
+            None => "superctrl".to_string(),
+        }
+    }
+
+    fn update(&mut self, message: Message) -> Task<Message> {
+        let task = match message {
+            Message::Tick => {
+                if self.menu_bar.is_none() {
+                    // The tray icon has to be created once the event loop is running.
+                    match MenuBar::new(self.state.clone()) {
+                        Ok(menu_bar) => self.menu_bar = Some(menu_bar),
+                        Err(e) => {
+                            tracing::error!("Failed to create the menu bar: {:#}", e);
+                            return iced::exit();
+                        }
                     }
                 }
-                MenuBarEvent::LearnStop => {
-                    tracing::info!("Stop learning requested from menu bar");
-                    if let Err(e) = rt_handle.block_on(crate::ipc::send_learn_stop_command()) {
-                        tracing::error!("Failed to send learn stop command: {}", e);
-                    } else {
-                        let mut gui_state = state.lock().unwrap();
-                        gui_state.set_learning_enabled(false);
-                    }
+                (self.on_tick)();
+                Task::none()
+            }
+            Message::Request(HostRequest::Menu(event)) => {
+                match self
+                    .menu_bar
+                    .as_ref()
+                    .and_then(|menu_bar| menu_bar.handle_event(&event))
+                {
+                    Some(event) => self.handle_menu_event(event),
+                    None => Task::none(),
                 }
-                MenuBarEvent::LearnGenerate => {
-                    tracing::info!("Generate system prompt requested from menu bar");
-                    if let Err(e) = rt_handle.block_on(crate::ipc::send_learn_finish_command()) {
-                        tracing::error!("Failed to send learn finish command: {}", e);
+            }
+            Message::Request(HostRequest::OpenWindow(window)) => self.open(window),
+            Message::Daemon(event) => {
+                match event {
+                    DaemonEvent::LearningStarted => {
+                        self.state.lock().unwrap().set_learning_enabled(true);
                     }
+                    DaemonEvent::LearningStopped => {
+                        self.state.lock().unwrap().set_learning_enabled(false);
+                    }
+                    _ => {}
                 }
-                MenuBarEvent::OpenPreferences => {
-                    tracing::info!("Open preferences requested from menu bar");
-                    preferences::open_preferences_window(state.clone());
-                }
-                MenuBarEvent::Quit => {
-                    tracing::info!("Quit requested from menu bar");
-                    std::process::exit(0);
-                }
+                Task::none()
             }
-        }
+            Message::Closed(id) => {
+                self.windows.remove(&id);
+                Task::none()
+            }
+            Message::QuickCommand(id, message) => match self.windows.get_mut(&id) {
+                Some(HostedWindow::QuickCommand(window)) => window
+                    .update(message)
+                    .map(move |message| Message::QuickCommand(id, message)),
+                _ => Task::none(),
+            },
+            Message::History(id, message) => match self.windows.get_mut(&id) {
+                Some(HostedWindow::History(window)) => window
+                    .update(message)
+                    .map(move |message| Message::History(id, message)),
+                _ => Task::none(),
+            },
+            Message::Viewer(id, message) => match self.windows.get_mut(&id) {
+                Some(HostedWindow::Viewer(window)) => window
+                    .update(message)
+                    .map(move |message| Message::Viewer(id, message)),
+                _ => Task::none(),
+            },
+            Message::Preferences(id, message) => match self.windows.get_mut(&id) {
+                Some(HostedWindow::Preferences(window)) => window
+                    .update(message)
+                    .map(move |message| Message::Preferences(id, message)),
+                _ => Task::none(),
+            },
+        };
+
+        self.refresh_menu_bar();
+        task
+    }
+
+    fn refresh_menu_bar(&mut self) {
+        let Some(menu_bar) = &mut self.menu_bar else {
+            return;
+        };
 
         if let Err(e) = menu_bar.update() {
             tracing::error!("Menu bar update error: {}", e);
         }
 
-        let current_state = state.lock().unwrap().app_state.clone();
+        let current_state = self.state.lock().unwrap().app_state.clone();
         if let Err(e) = menu_bar.update_icon(&current_state) {
             tracing::error!("Icon update error: {}", e);
         }
+    }
 
-        std::thread::sleep(std::time::Duration::from_millis(100));
+    fn handle_menu_event(&mut self, event: MenuBarEvent) -> Task<Message> {
+        match event {
+            MenuBarEvent::StopTask => {
+                tracing::info!("Stop task requested from menu bar");
+                let gui_state = self.state.lock().unwrap();
+                gui_state.trigger_stop(ControlSource::MenuBar);
+            }
+            MenuBarEvent::PauseTask => {
+                tracing::info!("Pause task requested from menu bar");
+                let gui_state = self.state.lock().unwrap();
+                gui_state.trigger_pause(ControlSource::MenuBar);
+            }
+            MenuBarEvent::ResumeTask => {
+                tracing::info!("Resume task requested from menu bar");
+                let gui_state = self.state.lock().unwrap();
+                gui_state.trigger_resume(ControlSource::MenuBar);
+            }
+            MenuBarEvent::OpenQuickCommand => return self.open(WindowRequest::QuickCommand),
+            MenuBarEvent::OpenHistory => return self.open(WindowRequest::History),
+            MenuBarEvent::OpenViewer => return self.open(WindowRequest::Viewer),
+            MenuBarEvent::LearnStart => {
+                tracing::info!("Start learning requested from menu bar");
+                return Task::future(async {
+                    if let Err(e) = crate::ipc::send_learn_start_command().await {
+                        tracing::error!("Failed to send learn start command: {}", e);
+                    }
+                })
+                .discard();
+            }
+            MenuBarEvent::LearnStop => {
+                tracing::info!("Stop learning requested from menu bar");
+                return Task::future(async {
+                    if let Err(e) = crate::ipc::send_learn_stop_command().await {
+                        tracing::error!("Failed to send learn stop command: {}", e);
+                    }
+                })
+                .discard();
+            }
+            MenuBarEvent::LearnGenerate => {
+                tracing::info!("Generate system prompt requested from menu bar");
+                return Task::future(async {
+                    if let Err(e) = crate::ipc::send_learn_finish_command().await {
+                        tracing::error!("Failed to send learn finish command: {}", e);
+                    }
+                })
+                .discard();
+            }
+            MenuBarEvent::OpenPreferences => return self.open(WindowRequest::Preferences),
+            MenuBarEvent::Quit => {
+                tracing::info!("Quit requested from menu bar");
+                std::process::exit(0);
+            }
+        }
+        Task::none()
+    }
+
+    fn open(&mut self, request: WindowRequest) -> Task<Message> {
+        if let Some((&id, _)) = self
+            .windows
+            .iter()
+            .find(|(_, window)| window.request() == request)
+        {
+            return window::gain_focus(id);
+        }
+
+        match request {
+            WindowRequest::QuickCommand => {
+                tracing::info!("Opening the quick command window");
+                let (id, opened) = window::open(quick_command::window_settings());
+                let (window, task) = QuickCommandWindow::new(id, self.quick_command.clone());
+                self.windows.insert(id, HostedWindow::QuickCommand(window));
+                Task::batch([
+                    opened.discard(),
+                    task.map(move |message| Message::QuickCommand(id, message)),
+                ])
+            }
+            WindowRequest::History => {
+                tracing::info!("Opening the task history window");
+                let Some(history) = &self.history else {
+                    tracing::warn!("Task history is not available");
+                    return Task::none();
+                };
+                let (id, opened) = window::open(history_window::window_settings());
+                let (window, task) = HistoryWindow::new(history.clone());
+                self.windows.insert(id, HostedWindow::History(window));
+                Task::batch([
+                    opened.discard(),
+                    task.map(move |message| Message::History(id, message)),
+                ])
+            }
+            WindowRequest::Viewer => {
+                tracing::info!("Opening the live viewer");
+                let (id, opened) = window::open(viewer::window_settings());
+                let (window, task) = ViewerWindow::new(self.state.clone());
+                self.windows.insert(id, HostedWindow::Viewer(window));
+                Task::batch([
+                    opened.discard(),
+                    task.map(move |message| Message::Viewer(id, message)),
+                ])
+            }
+            WindowRequest::Preferences => {
+                tracing::info!("Opening the preferences window");
+                let (id, opened) = window::open(preferences::window_settings());
+                let (window, task) = PreferencesWindow::new(self.state.clone());
+                self.windows
+                    .insert(id, HostedWindow::Preferences(Box::new(window)));
+                Task::batch([
+                    opened.discard(),
+                    task.map(move |message| Message::Preferences(id, message)),
+                ])
+            }
+        }
+    }
+
+    fn view(&self, id: window::Id) -> Element<'_, Message> {
+        match self.windows.get(&id) {
+            Some(HostedWindow::QuickCommand(window)) => window
+                .view()
+                .map(move |message| Message::QuickCommand(id, message)),
+            Some(HostedWindow::History(window)) => window
+                .view()
+                .map(move |message| Message::History(id, message)),
+            Some(HostedWindow::Viewer(window)) => window
+                .view()
+                .map(move |message| Message::Viewer(id, message)),
+            Some(HostedWindow::Preferences(window)) => window
+                .view()
+                .map(move |message| Message::Preferences(id, message)),
+            None => horizontal_space().into(),
+        }
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        let mut subscriptions = vec![
+            iced::time::every(TICK_INTERVAL).map(|_| Message::Tick),
+            window::close_events().map(Message::Closed),
+            host_requests(),
+            daemon_events(&self.events),
+        ];
+
+        for (&id, window) in &self.windows {
+            match window {
+                HostedWindow::QuickCommand(_) => subscriptions.push(
+                    QuickCommandWindow::subscription()
+                        .map(|(id, message)| Message::QuickCommand(id, message)),
+                ),
+                HostedWindow::Viewer(window) => subscriptions.push(
+                    window
+                        .subscription()
+                        .with(id)
+                        .map(|(id, message)| Message::Viewer(id, message)),
+                ),
+                HostedWindow::History(_) | HostedWindow::Preferences(_) => {}
+            }
+        }
+
+        Subscription::batch(subscriptions)
+    }
+}
+
+fn host_requests() -> Subscription<Message> {
+    let receiver = HOST_SENDER.get().map(|sender| sender.subscribe());
+    Subscription::run_with_id(
+        "host-requests",
+        stream::unfold(receiver, |receiver| async move {
+            let mut receiver = receiver?;
+            loop {
+                match receiver.recv().await {
+                    Ok(request) => return Some((Message::Request(request), Some(receiver))),
+                    Err(RecvError::Lagged(missed)) => {
+                        tracing::debug!("Menu bar missed {} request(s)", missed);
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }),
+    )
+}
+
+fn daemon_events(bus: &EventBus) -> Subscription<Message> {
+    let receiver = bus.subscribe();
+    Subscription::run_with_id(
+        "daemon-events",
+        stream::unfold(receiver, |mut receiver| async move {
+            match receiver.recv().await {
+                Ok(event) => Some((Message::Daemon(event.event), receiver)),
+                Err(RecvError::Lagged(missed)) => {
+                    tracing::debug!("Menu bar missed {} daemon event(s)", missed);
+                    Some((Message::Tick, receiver))
+                }
+                Err(RecvError::Closed) => None,
+            }
+        }),
+    )
+}
+
+pub fn run_menu_bar_loop(
+    state: SharedGuiState,
+    quick_command: QuickCommandPaths,
+    history: Option<SharedTaskHistory>,
+    on_tick: impl FnMut() + 'static,
+) -> Result<()> {
+    let (sender, _) = broadcast::channel(64);
+    if HOST_SENDER.set(sender.clone()).is_err() {
+        tracing::warn!("Menu bar loop started twice");
     }
+    MenuEvent::set_event_handler(Some(move |event| {
+        let _ = sender.send(HostRequest::Menu(event));
+    }));
+
+    let events = state.lock().unwrap().event_bus.clone();
+    let host = WindowHost {
+        state,
+        events,
+        quick_command,
+        history,
+        on_tick: Box::new(on_tick),
+        menu_bar: None,
+        windows: BTreeMap::new(),
+    };
+
+    iced::daemon(WindowHost::title, WindowHost::update, WindowHost::view)
+        .subscription(WindowHost::subscription)
+        .run_with(move || (host, Task::done(Message::Tick)))?;
+
+    Ok(())
 }
@@ -1,7 +1,67 @@
+use std::sync::{OnceLock, RwLock};
+
 use anyhow::Result;
 use notify_rust::{Notification, Timeout};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::config::NotificationSettings;
+use crate::events::{DaemonEvent, EventBus};
+use crate::task::ControlSource;
+
+static SETTINGS: OnceLock<RwLock<NotificationSettings>> = OnceLock::new();
+
+fn settings() -> &'static RwLock<NotificationSettings> {
+    SETTINGS.get_or_init(|| RwLock::new(NotificationSettings::default()))
+}
+
+pub fn configure(notifications: NotificationSettings) {
+    *settings().write().unwrap() = notifications;
+}
+
+pub fn start(bus: &EventBus) {
+    let mut receiver = bus.subscribe();
+    std::thread::spawn(move || loop {
+        match receiver.blocking_recv() {
+            Ok(event) => {
+                if let Err(e) = notify(&event.event) {
+                    tracing::debug!("Failed to show notification: {}", e);
+                }
+            }
+            Err(RecvError::Lagged(missed)) => {
+                tracing::debug!("Notifications missed {} daemon event(s)", missed);
+            }
+            Err(RecvError::Closed) => return,
+        }
+    });
+}
+
+fn notify(event: &DaemonEvent) -> Result<()> {
+    match event {
+        DaemonEvent::TaskQueued { command, mode } if mode == "act" => {
+            notify_command_received(command)
+        }
+        DaemonEvent::TaskCompleted { command } => notify_command_completed(command),
+        DaemonEvent::TaskFailed { command, error } => notify_command_failed(command, error),
+        DaemonEvent::TaskStopped { .. } => notify_emergency_stop(),
+        DaemonEvent::Paused {
+            source: ControlSource::UserInput,
+            tasks,
+        } if *tasks > 0 => notify_auto_paused(),
+        DaemonEvent::ConfigReloadFailed { error } => notify_config_reload_failed(error),
+        _ => Ok(()),
+    }
+}
+
+fn enabled(kind: impl Fn(&NotificationSettings) -> bool) -> bool {
+    let settings = settings().read().unwrap();
+    settings.enabled && kind(&settings)
+}
+
+fn notify_command_received(command: &str) -> Result<()> {
+    if !enabled(|settings| settings.command_received) {
+        return Ok(());
+    }
 
-pub fn notify_command_received(command: &str) -> Result<()> {
     Notification::new()
         .summary("superctrl")
         .body(&format!("Command received: {}", truncate(command, 60)))
@@ -11,7 +71,11 @@ pub fn notify_command_received(command: &str) -> Result<()> {
     Ok(())
 }
 
-pub fn notify_command_completed(command: &str) -> Result<()> {
+fn notify_command_completed(command: &str) -> Result<()> {
+    if !enabled(|settings| settings.command_completed) {
+        return Ok(());
+    }
+
     Notification::new()
         .summary("superctrl")
         .body(&format!("✓ Completed: {}", truncate(command, 60)))
@@ -21,7 +85,11 @@ pub fn notify_command_completed(command: &str) -> Result<()> {
     Ok(())
 }
 
-pub fn notify_command_failed(command: &str, error: &str) -> Result<()> {
+fn notify_command_failed(command: &str, error: &str) -> Result<()> {
+    if !enabled(|settings| settings.command_failed) {
+        return Ok(());
+    }
+
     Notification::new()
         .summary("superctrl")
         .body(&format!(
@@ -35,7 +103,11 @@ pub fn notify_command_failed(command: &str, error: &str) -> Result<()> {
     Ok(())
 }
 
-pub fn notify_emergency_stop() -> Result<()> {
+fn notify_emergency_stop() -> Result<()> {
+    if !enabled(|settings| settings.task_stopped) {
+        return Ok(());
+    }
+
     Notification::new()
         .summary("superctrl")
         .body("🛑 Emergency stop triggered")
@@ -45,6 +117,37 @@ pub fn notify_emergency_stop() -> Result<()> {
     Ok(())
 }
 
+fn notify_auto_paused() -> Result<()> {
+    if !enabled(|settings| settings.auto_paused) {
+        return Ok(());
+    }
+
+    Notification::new()
+        .summary("superctrl")
+        .body("⏸ Paused because you used the keyboard or mouse. Run `superctrl resume` or press the pause hotkey to continue.")
+        .icon("pause")
+        .timeout(Timeout::Milliseconds(5000))
+        .show()?;
+    Ok(())
+}
+
+fn notify_config_reload_failed(error: &str) -> Result<()> {
+    if !enabled(|_| true) {
+        return Ok(());
+    }
+
+    Notification::new()
+        .summary("superctrl")
+        .body(&format!(
+            "⚠️ Config reload failed, keeping the previous settings\n{}",
+            truncate(error, 120)
+        ))
+        .icon("error")
+        .timeout(Timeout::Milliseconds(8000))
+        .show()?;
+    Ok(())
+}
+
 fn truncate(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
         s.to_string()
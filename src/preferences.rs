@@ -1,10 +1,90 @@
-use iced::widget::{button, column, container, row, text, vertical_space};
-use iced::{Color, Element, Length, Settings, Task};
+use std::path::PathBuf;
 
+use iced::widget::{
+    button, checkbox, column, container, row, scrollable, slider, text, text_input, vertical_space,
+};
+use iced::{window, Color, Element, Length, Size, Task};
+
+use crate::config::{self, NotificationSettings, Settings};
 use crate::gui::SharedGuiState;
+use crate::hotkey::{format_hotkey, parse_hotkey, HotkeyAction, HotkeyBindings};
+use crate::secrets::{self, SecretStore, API_KEY_SECRET};
+
+pub const WINDOW_TITLE: &str = "superctrl - Settings";
+
+const LABEL_WIDTH: f32 = 200.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationOption {
+    CommandReceived,
+    CommandCompleted,
+    CommandFailed,
+    TaskStopped,
+    AutoPaused,
+}
+
+impl NotificationOption {
+    pub const ALL: [NotificationOption; 5] = [
+        NotificationOption::CommandReceived,
+        NotificationOption::CommandCompleted,
+        NotificationOption::CommandFailed,
+        NotificationOption::TaskStopped,
+        NotificationOption::AutoPaused,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NotificationOption::CommandReceived => "Command received",
+            NotificationOption::CommandCompleted => "Command completed",
+            NotificationOption::CommandFailed => "Command failed",
+            NotificationOption::TaskStopped => "Task stopped",
+            NotificationOption::AutoPaused => "Paused by user input",
+        }
+    }
+
+    fn get(self, notifications: &NotificationSettings) -> bool {
+        match self {
+            NotificationOption::CommandReceived => notifications.command_received,
+            NotificationOption::CommandCompleted => notifications.command_completed,
+            NotificationOption::CommandFailed => notifications.command_failed,
+            NotificationOption::TaskStopped => notifications.task_stopped,
+            NotificationOption::AutoPaused => notifications.auto_paused,
+        }
+    }
+
+    fn set(self, notifications: &mut NotificationSettings, enabled: bool) {
+        match self {
+            NotificationOption::CommandReceived => notifications.command_received = enabled,
+            NotificationOption::CommandCompleted => notifications.command_completed = enabled,
+            NotificationOption::CommandFailed => notifications.command_failed = enabled,
+            NotificationOption::TaskStopped => notifications.task_stopped = enabled,
+            NotificationOption::AutoPaused => notifications.auto_paused = enabled,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ApiKeySource {
+    Environment,
+    Stored,
+    Missing,
+}
 
 #[derive(Debug, Clone)]
 pub enum PreferencesMessage {
+    ApiKeyChanged(String),
+    SaveApiKey,
+    RemoveApiKey,
+    ModelChanged(String),
+    ApiUrlChanged(String),
+    ScreenshotQualityChanged(u8),
+    LearningToggled(bool),
+    ClipboardMonitoringToggled(bool),
+    HotkeyChanged(HotkeyAction, String),
+    NotificationsToggled(bool),
+    NotificationToggled(NotificationOption, bool),
+    Save,
+    Revert,
     TestConnection,
     ViewLogs,
     OpenConfig,
@@ -14,31 +94,66 @@ pub enum PreferencesMessage {
 }
 
 pub struct PreferencesWindow {
-    api_key_set: bool,
-    emergency_stop_shortcut: String,
-    connection_status: Option<String>,
+    settings_path: Option<PathBuf>,
+    saved: Settings,
+    draft: Settings,
+    active_hotkeys: HotkeyBindings,
+    validation_errors: Vec<String>,
+    save_status: Option<Result<String, String>>,
+    api_key_input: String,
+    api_key_source: ApiKeySource,
+    api_key_status: Option<Result<String, String>>,
+    connection_status: Option<Result<String, String>>,
     testing_connection: bool,
     daemon_running: Option<bool>,
     macrowhisper_configured: bool,
 }
 
 impl PreferencesWindow {
-    pub fn new(_state: SharedGuiState) -> (Self, Task<PreferencesMessage>) {
-        let api_key_set = std::env::var("ANTHROPIC_API_KEY").is_ok();
+    pub fn new(state: SharedGuiState) -> (Self, Task<PreferencesMessage>) {
         let macrowhisper_configured = std::path::Path::new("/Users")
             .join(std::env::var("USER").unwrap_or_default())
             .join(".config/macrowhisper/macrowhisper.json")
             .exists();
 
+        let mut save_status = None;
+        let daemon_settings_path = state.lock().unwrap().settings_path.clone();
+        let settings_path = match daemon_settings_path.map_or_else(Settings::default_path, Ok) {
+            Ok(path) => Some(path),
+            Err(e) => {
+                save_status = Some(Err(format!("{:#}", e)));
+                None
+            }
+        };
+        let saved = match settings_path.as_deref().map(Settings::load) {
+            Some(Ok(settings)) => settings,
+            Some(Err(e)) => {
+                save_status = Some(Err(format!("{:#}", e)));
+                Settings::default()
+            }
+            None => Settings::default(),
+        };
+
+        let mut window = Self {
+            settings_path,
+            draft: saved.clone(),
+            saved,
+            active_hotkeys: state.lock().unwrap().hotkeys.clone(),
+            validation_errors: Vec::new(),
+            save_status,
+            api_key_input: String::new(),
+            api_key_source: ApiKeySource::Missing,
+            api_key_status: None,
+            connection_status: None,
+            testing_connection: false,
+            daemon_running: None,
+            macrowhisper_configured,
+        };
+        window.refresh_api_key_source();
+        window.validate();
+
         (
-            Self {
-                api_key_set,
-                emergency_stop_shortcut: "⌘⇧⎋".to_string(),
-                connection_status: None,
-                testing_connection: false,
-                daemon_running: None,
-                macrowhisper_configured,
-            },
+            window,
             Task::perform(
                 async { crate::ipc::is_daemon_running() },
                 PreferencesMessage::DaemonStatusChecked,
@@ -46,32 +161,178 @@ impl PreferencesWindow {
         )
     }
 
+    fn refresh_api_key_source(&mut self) {
+        self.api_key_source = if config::api_key_from_env().is_some() {
+            ApiKeySource::Environment
+        } else {
+            match self.key_store().and_then(|store| store.get(API_KEY_SECRET)) {
+                Ok(Some(_)) => ApiKeySource::Stored,
+                Ok(None) => ApiKeySource::Missing,
+                Err(e) => {
+                    self.api_key_status = Some(Err(format!("{:#}", e)));
+                    ApiKeySource::Missing
+                }
+            }
+        };
+    }
+
+    fn validate(&mut self) {
+        self.validation_errors = self.draft.validate().err().unwrap_or_default();
+    }
+
+    fn edit(&mut self, change: impl FnOnce(&mut Settings)) {
+        change(&mut self.draft);
+        self.save_status = None;
+        self.validate();
+    }
+
+    fn key_store(&self) -> anyhow::Result<Box<dyn SecretStore>> {
+        secrets::open_store(&self.saved.secrets)
+    }
+
+    fn key_store_description(&self) -> String {
+        self.key_store()
+            .map(|store| store.description())
+            .unwrap_or_else(|_| "secret store".to_string())
+    }
+
+    fn connection_api_key(&self) -> Result<String, String> {
+        if !self.api_key_input.trim().is_empty() {
+            return Ok(self.api_key_input.trim().to_string());
+        }
+        if let Some(api_key) = config::api_key_from_env() {
+            return Ok(api_key);
+        }
+        self.key_store()
+            .and_then(|store| store.get(API_KEY_SECRET))
+            .map_err(|e| format!("{:#}", e))?
+            .ok_or_else(|| "No API key is set".to_string())
+    }
+
     pub fn update(&mut self, message: PreferencesMessage) -> Task<PreferencesMessage> {
         match message {
+            PreferencesMessage::ApiKeyChanged(api_key) => {
+                self.api_key_input = api_key;
+                self.api_key_status = None;
+                Task::none()
+            }
+            PreferencesMessage::SaveApiKey => {
+                let api_key = self.api_key_input.trim();
+                if api_key.is_empty() {
+                    return Task::none();
+                }
+                let saved = self
+                    .key_store()
+                    .and_then(|store| store.set(API_KEY_SECRET, api_key));
+                self.api_key_status = Some(match saved {
+                    Ok(()) => {
+                        self.api_key_input.clear();
+                        Ok(format!(
+                            "✓ API key saved to the {}",
+                            self.key_store_description()
+                        ))
+                    }
+                    Err(e) => Err(format!("{:#}", e)),
+                });
+                self.refresh_api_key_source();
+                Task::none()
+            }
+            PreferencesMessage::RemoveApiKey => {
+                self.api_key_status = Some(
+                    self.key_store()
+                        .and_then(|store| store.delete(API_KEY_SECRET))
+                        .map(|()| {
+                            format!(
+                                "✓ API key removed from the {}",
+                                self.key_store_description()
+                            )
+                        })
+                        .map_err(|e| format!("{:#}", e)),
+                );
+                self.refresh_api_key_source();
+                Task::none()
+            }
+            PreferencesMessage::ModelChanged(model) => {
+                self.edit(|settings| settings.model = model);
+                Task::none()
+            }
+            PreferencesMessage::ApiUrlChanged(api_url) => {
+                self.edit(|settings| settings.api_url = api_url);
+                Task::none()
+            }
+            PreferencesMessage::ScreenshotQualityChanged(quality) => {
+                self.edit(|settings| settings.screenshot_quality = quality);
+                Task::none()
+            }
+            PreferencesMessage::LearningToggled(enabled) => {
+                self.edit(|settings| settings.learning_enabled = enabled);
+                Task::none()
+            }
+            PreferencesMessage::ClipboardMonitoringToggled(enabled) => {
+                self.edit(|settings| settings.clipboard_monitoring = enabled);
+                Task::none()
+            }
+            PreferencesMessage::HotkeyChanged(action, spec) => {
+                self.edit(|settings| settings.hotkeys.set(action, Some(spec)));
+                Task::none()
+            }
+            PreferencesMessage::NotificationsToggled(enabled) => {
+                self.edit(|settings| settings.notifications.enabled = enabled);
+                Task::none()
+            }
+            PreferencesMessage::NotificationToggled(option, enabled) => {
+                self.edit(|settings| option.set(&mut settings.notifications, enabled));
+                Task::none()
+            }
+            PreferencesMessage::Save => {
+                let Some(path) = &self.settings_path else {
+                    return Task::none();
+                };
+                self.save_status = Some(match self.draft.save(path) {
+                    Ok(()) => {
+                        self.saved = self.draft.clone();
+                        Ok(format!(
+                            "✓ Saved to {}. The daemon applies the changes automatically; learning changes need a restart.",
+                            path.display()
+                        ))
+                    }
+                    Err(e) => Err(format!("{:#}", e)),
+                });
+                Task::none()
+            }
+            PreferencesMessage::Revert => {
+                self.draft = self.saved.clone();
+                self.save_status = None;
+                self.validate();
+                Task::none()
+            }
             PreferencesMessage::TestConnection => {
+                let api_key = match self.connection_api_key() {
+                    Ok(api_key) => api_key,
+                    Err(e) => {
+                        self.connection_status = Some(Err(e));
+                        return Task::none();
+                    }
+                };
+
                 self.testing_connection = true;
-                self.connection_status = Some("Testing connection...".to_string());
+                self.connection_status = None;
+                let api_url = self.draft.api_url.trim().to_string();
+                let model = self.draft.model.trim().to_string();
 
                 Task::perform(
-                    async {
-                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-
-                        match std::env::var("ANTHROPIC_API_KEY") {
-                            Ok(key) if !key.is_empty() => {
-                                Ok("✓ Connection successful!".to_string())
-                            }
-                            _ => Err("API key not set".to_string()),
-                        }
+                    async move {
+                        crate::computer_use::check_api_connection(&api_key, &api_url, &model)
+                            .await
+                            .map(|()| format!("✓ Connected to {} with {}", api_url, model))
+                            .map_err(|e| format!("{:#}", e))
                     },
                     PreferencesMessage::ConnectionTested,
                 )
             }
             PreferencesMessage::ConnectionTested(result) => {
                 self.testing_connection = false;
-                self.connection_status = Some(match result {
-                    Ok(msg) => msg,
-                    Err(err) => format!("✗ Error: {}", err),
-                });
+                self.connection_status = Some(result);
                 Task::none()
             }
             PreferencesMessage::ViewLogs => {
@@ -102,18 +363,25 @@ impl PreferencesWindow {
     }
 
     pub fn view(&self) -> Element<'_, PreferencesMessage> {
+        let grey = Color::from_rgb(0.5, 0.5, 0.5);
         let title = text("superctrl Settings").size(28);
 
-        let api_key_status = if self.api_key_set {
-            text("✓ Set").color(Color::from_rgb(0.0, 0.8, 0.0))
-        } else {
-            text("✗ Not set").color(Color::from_rgb(0.8, 0.0, 0.0))
+        let api_key_status = match self.api_key_source {
+            ApiKeySource::Environment => {
+                text("✓ Set by ANTHROPIC_API_KEY (overrides the stored key)")
+                    .color(Color::from_rgb(0.0, 0.8, 0.0))
+            }
+            ApiKeySource::Stored => {
+                text(format!("✓ Stored in the {}", self.key_store_description()))
+                    .color(Color::from_rgb(0.0, 0.8, 0.0))
+            }
+            ApiKeySource::Missing => text("✗ Not set").color(Color::from_rgb(0.8, 0.0, 0.0)),
         };
 
         let daemon_status = match self.daemon_running {
             Some(true) => text("✓ Running").color(Color::from_rgb(0.0, 0.8, 0.0)),
             Some(false) => text("✗ Not running").color(Color::from_rgb(0.8, 0.0, 0.0)),
-            None => text("Checking...").color(Color::from_rgb(0.5, 0.5, 0.5)),
+            None => text("Checking...").color(grey),
         };
 
         let macrowhisper_status = if self.macrowhisper_configured {
@@ -125,108 +393,251 @@ impl PreferencesWindow {
         let status_section = column![
             text("System Status").size(20),
             vertical_space().height(Length::Fixed(10.0)),
-            row![
-                text("ANTHROPIC_API_KEY:").width(Length::Fixed(200.0)),
-                api_key_status
-            ]
-            .spacing(10),
-            row![
-                text("Daemon Status:").width(Length::Fixed(200.0)),
-                daemon_status
-            ]
-            .spacing(10),
-            row![
-                text("macrowhisper:").width(Length::Fixed(200.0)),
-                macrowhisper_status
-            ]
-            .spacing(10),
-            row![
-                text("Emergency Stop:").width(Length::Fixed(200.0)),
-                text(&self.emergency_stop_shortcut)
-            ]
-            .spacing(10),
+            labeled("API Key:", api_key_status),
+            labeled("Daemon Status:", daemon_status),
+            labeled("macrowhisper:", macrowhisper_status),
         ]
         .spacing(8);
 
-        let test_button = button(if self.testing_connection {
+        let mut save_key = button("  Save Key  ").padding(8);
+        if !self.api_key_input.trim().is_empty() {
+            save_key = save_key.on_press(PreferencesMessage::SaveApiKey);
+        }
+        let mut remove_key = button("  Remove  ").padding(8);
+        if self.api_key_source == ApiKeySource::Stored {
+            remove_key = remove_key.on_press(PreferencesMessage::RemoveApiKey);
+        }
+        let mut api_section = column![
+            text("Anthropic API").size(20),
+            vertical_space().height(Length::Fixed(10.0)),
+            labeled(
+                "API Key:",
+                row![
+                    text_input("sk-ant-...", &self.api_key_input)
+                        .secure(true)
+                        .on_input(PreferencesMessage::ApiKeyChanged)
+                        .on_submit(PreferencesMessage::SaveApiKey),
+                    save_key,
+                    remove_key,
+                ]
+                .spacing(8),
+            ),
+            labeled(
+                "Model:",
+                text_input(config::DEFAULT_MODEL, &self.draft.model)
+                    .on_input(PreferencesMessage::ModelChanged),
+            ),
+            labeled(
+                "API Endpoint:",
+                text_input(config::DEFAULT_API_URL, &self.draft.api_url)
+                    .on_input(PreferencesMessage::ApiUrlChanged),
+            ),
+            labeled(
+                "Screenshot Quality:",
+                row![
+                    slider(
+                        1..=100u8,
+                        self.draft.screenshot_quality,
+                        PreferencesMessage::ScreenshotQualityChanged
+                    ),
+                    text(self.draft.screenshot_quality.to_string()).width(Length::Fixed(40.0)),
+                ]
+                .spacing(10),
+            ),
+        ]
+        .spacing(8);
+        if let Some(status) = &self.api_key_status {
+            api_section = api_section.push(status_text(status));
+        }
+
+        let learning_section = column![
+            text("Learning").size(20),
+            vertical_space().height(Length::Fixed(10.0)),
+            checkbox("Enable learning mode", self.draft.learning_enabled)
+                .on_toggle(PreferencesMessage::LearningToggled),
+            checkbox(
+                "Record clipboard changes while learning",
+                self.draft.clipboard_monitoring
+            )
+            .on_toggle(PreferencesMessage::ClipboardMonitoringToggled),
+        ]
+        .spacing(8);
+
+        let hotkeys_section = HotkeyAction::ALL.into_iter().fold(
+            column![
+                text("Hotkeys").size(20),
+                vertical_space().height(Length::Fixed(10.0)),
+            ]
+            .spacing(8),
+            |section, action| {
+                let spec = self.draft.hotkeys.get(action).unwrap_or("");
+                let preview = if spec.is_empty() {
+                    "Not set".to_string()
+                } else {
+                    parse_hotkey(spec)
+                        .map(|hotkey| format_hotkey(&hotkey))
+                        .unwrap_or_else(|_| "?".to_string())
+                };
+                let active = self
+                    .active_hotkeys
+                    .hotkey(action)
+                    .map(|hotkey| format_hotkey(&hotkey))
+                    .unwrap_or_else(|| "Not set".to_string());
+                section.push(labeled(
+                    &format!("{}:", action.label()),
+                    row![
+                        text_input("e.g. cmd+shift+esc", spec)
+                            .on_input(move |spec| PreferencesMessage::HotkeyChanged(action, spec)),
+                        text(preview).width(Length::Fixed(80.0)),
+                        text(format!("active: {}", active))
+                            .size(12)
+                            .color(grey)
+                            .width(Length::Fixed(100.0)),
+                    ]
+                    .spacing(10),
+                ))
+            },
+        );
+
+        let notifications = &self.draft.notifications;
+        let notifications_section = NotificationOption::ALL.into_iter().fold(
+            column![
+                text("Notifications").size(20),
+                vertical_space().height(Length::Fixed(10.0)),
+                checkbox("Show notifications", notifications.enabled)
+                    .on_toggle(PreferencesMessage::NotificationsToggled),
+            ]
+            .spacing(8),
+            |section, option| {
+                let mut toggle =
+                    checkbox(format!("    {}", option.label()), option.get(notifications));
+                if notifications.enabled {
+                    toggle = toggle.on_toggle(move |enabled| {
+                        PreferencesMessage::NotificationToggled(option, enabled)
+                    });
+                }
+                section.push(toggle)
+            },
+        );
+
+        let has_changes = self.draft != self.saved;
+        let mut save_button = button("  Save  ").padding(10);
+        if has_changes && self.validation_errors.is_empty() && self.settings_path.is_some() {
+            save_button = save_button.on_press(PreferencesMessage::Save);
+        }
+        let mut revert_button = button("  Revert  ").padding(10);
+        if has_changes {
+            revert_button = revert_button.on_press(PreferencesMessage::Revert);
+        }
+
+        let mut test_button = button(if self.testing_connection {
             "  Testing...  "
         } else {
             "  Test API Connection  "
         })
-        .on_press(PreferencesMessage::TestConnection)
         .padding(10);
-
-        let view_logs_button = button("  View Logs  ")
-            .on_press(PreferencesMessage::ViewLogs)
-            .padding(10);
-
-        let open_config_button = button("  Edit Config  ")
-            .on_press(PreferencesMessage::OpenConfig)
-            .padding(10);
-
-        let check_daemon_button = button("  Refresh Status  ")
-            .on_press(PreferencesMessage::CheckDaemonStatus)
-            .padding(10);
+        if !self.testing_connection {
+            test_button = test_button.on_press(PreferencesMessage::TestConnection);
+        }
 
         let buttons_row = row![
+            save_button,
+            revert_button,
             test_button,
-            view_logs_button,
-            open_config_button,
-            check_daemon_button
+            button("  View Logs  ")
+                .on_press(PreferencesMessage::ViewLogs)
+                .padding(10),
+            button("  Edit Config  ")
+                .on_press(PreferencesMessage::OpenConfig)
+                .padding(10),
+            button("  Refresh Status  ")
+                .on_press(PreferencesMessage::CheckDaemonStatus)
+                .padding(10),
         ]
         .spacing(10);
 
-        let mut content = column![
-            title,
-            vertical_space().height(Length::Fixed(20.0)),
-            status_section,
-            vertical_space().height(Length::Fixed(20.0)),
-            buttons_row,
-        ]
-        .spacing(10)
-        .padding(30);
-
-        if let Some(status) = &self.connection_status {
-            content = content.push(vertical_space().height(Length::Fixed(15.0)));
-            let status_color = if status.contains("✓") {
-                Color::from_rgb(0.0, 0.6, 0.0)
-            } else {
-                Color::from_rgb(0.8, 0.0, 0.0)
-            };
-            content = content.push(text(status).size(14).color(status_color));
+        let mut feedback = column![].spacing(6);
+        for error in &self.validation_errors {
+            feedback = feedback.push(
+                text(format!("✗ {}", error))
+                    .size(14)
+                    .color(Color::from_rgb(0.8, 0.0, 0.0)),
+            );
+        }
+        for status in [&self.save_status, &self.connection_status]
+            .into_iter()
+            .flatten()
+        {
+            feedback = feedback.push(status_text(status));
         }
 
         let help_text = column![
-            vertical_space().height(Length::Fixed(20.0)),
             text("Voice Triggers:").size(14),
             text("  \"Computer, [command]\"  |  \"Automate [command]\"")
                 .size(12)
-                .color(Color::from_rgb(0.5, 0.5, 0.5)),
+                .color(grey),
             text("  \"Control [command]\"  |  \"Do this: [command]\"")
                 .size(12)
-                .color(Color::from_rgb(0.5, 0.5, 0.5)),
+                .color(grey),
         ];
 
-        content = content.push(help_text);
+        let content = column![
+            title,
+            vertical_space().height(Length::Fixed(20.0)),
+            status_section,
+            vertical_space().height(Length::Fixed(20.0)),
+            api_section,
+            vertical_space().height(Length::Fixed(20.0)),
+            learning_section,
+            vertical_space().height(Length::Fixed(20.0)),
+            hotkeys_section,
+            vertical_space().height(Length::Fixed(20.0)),
+            notifications_section,
+            vertical_space().height(Length::Fixed(20.0)),
+            buttons_row,
+            feedback,
+            vertical_space().height(Length::Fixed(20.0)),
+            help_text,
+        ]
+        .spacing(10)
+        .padding(30);
 
-        container(content)
+        container(scrollable(content))
             .width(Length::Fill)
             .height(Length::Fill)
             .into()
     }
 }
 
-pub fn open_preferences_window(state: SharedGuiState) {
-    std::thread::spawn(move || {
-        let settings = Settings::default();
+fn labeled<'a>(
+    label: &str,
+    control: impl Into<Element<'a, PreferencesMessage>>,
+) -> Element<'a, PreferencesMessage> {
+    row![
+        text(label.to_string()).width(Length::Fixed(LABEL_WIDTH)),
+        control.into()
+    ]
+    .spacing(10)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
 
-        let _ = iced::application(
-            "superctrl - Settings",
-            PreferencesWindow::update,
-            PreferencesWindow::view,
-        )
-        .settings(settings)
-        .window_size((600.0, 450.0))
-        .run_with(|| PreferencesWindow::new(state));
-    });
+fn status_text(status: &Result<String, String>) -> Element<'_, PreferencesMessage> {
+    match status {
+        Ok(message) => text(message)
+            .size(14)
+            .color(Color::from_rgb(0.0, 0.6, 0.0))
+            .into(),
+        Err(error) => text(format!("✗ Error: {}", error))
+            .size(14)
+            .color(Color::from_rgb(0.8, 0.0, 0.0))
+            .into(),
+    }
+}
+
+pub fn window_settings() -> window::Settings {
+    window::Settings {
+        size: Size::new(760.0, 820.0),
+        ..window::Settings::default()
+    }
 }
@@ -0,0 +1,341 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use iced::keyboard::{self, key::Named, Key};
+use iced::widget::{button, column, container, text, text_input};
+use iced::{event, window, Color, Element, Event, Length, Size, Subscription, Task};
+use serde::{Deserialize, Serialize};
+
+const MAX_HISTORY: usize = 100;
+const MAX_SUGGESTIONS: usize = 5;
+const INPUT_ID: &str = "quick-command-input";
+
+pub const WINDOW_TITLE: &str = "superctrl - Quick Command";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Routine {
+    pub name: String,
+    pub command: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct RoutinesFile {
+    #[serde(default)]
+    routines: Vec<Routine>,
+}
+
+pub fn load_routines(path: &Path) -> Result<Vec<Routine>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read routines from {:?}", path))?;
+    let file: RoutinesFile = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse routines from {:?}", path))?;
+    Ok(file.routines)
+}
+
+pub fn suggestions<'a>(routines: &'a [Routine], input: &str) -> Vec<&'a Routine> {
+    let query = input.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut prefix_matches = Vec::new();
+    let mut other_matches = Vec::new();
+    for routine in routines {
+        let name = routine.name.to_lowercase();
+        let command = routine.command.to_lowercase();
+        if command == query {
+            continue;
+        }
+
+        if name.starts_with(&query) || command.starts_with(&query) {
+            prefix_matches.push(routine);
+        } else if name.contains(&query) || command.contains(&query) {
+            other_matches.push(routine);
+        }
+    }
+
+    prefix_matches
+        .into_iter()
+        .chain(other_matches)
+        .take(MAX_SUGGESTIONS)
+        .collect()
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandHistory {
+    entries: Vec<String>,
+    #[serde(skip)]
+    cursor: Option<usize>,
+    #[serde(skip)]
+    draft: String,
+}
+
+impl CommandHistory {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read command history from {:?}", path))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse command history from {:?}", path))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .context("Failed to create command history directory")?;
+        }
+
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write command history to {:?}", path))
+    }
+
+    pub fn push(&mut self, command: &str) {
+        self.cursor = None;
+        self.draft.clear();
+
+        let command = command.trim();
+        if command.is_empty() {
+            return;
+        }
+
+        self.entries.retain(|entry| entry != command);
+        self.entries.push(command.to_string());
+        if self.entries.len() > MAX_HISTORY {
+            let excess = self.entries.len() - MAX_HISTORY;
+            self.entries.drain(..excess);
+        }
+    }
+
+    pub fn older(&mut self, current: &str) -> Option<String> {
+        let index = match self.cursor {
+            None if self.entries.is_empty() => return None,
+            None => {
+                self.draft = current.to_string();
+                self.entries.len() - 1
+            }
+            Some(index) => index.saturating_sub(1),
+        };
+
+        self.cursor = Some(index);
+        Some(self.entries[index].clone())
+    }
+
+    pub fn newer(&mut self) -> Option<String> {
+        let index = self.cursor?;
+        if index + 1 < self.entries.len() {
+            self.cursor = Some(index + 1);
+            Some(self.entries[index + 1].clone())
+        } else {
+            self.cursor = None;
+            Some(std::mem::take(&mut self.draft))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QuickCommandPaths {
+    pub history: PathBuf,
+    pub routines: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub enum QuickCommandMessage {
+    InputChanged(String),
+    Submit,
+    Submitted(Result<(), String>),
+    HistoryPrevious,
+    HistoryNext,
+    AcceptSuggestion(usize),
+    Close,
+}
+
+pub struct QuickCommandWindow {
+    id: window::Id,
+    input: String,
+    history: CommandHistory,
+    routines: Vec<Routine>,
+    paths: QuickCommandPaths,
+    submitting: bool,
+    error: Option<String>,
+}
+
+impl QuickCommandWindow {
+    pub fn new(id: window::Id, paths: QuickCommandPaths) -> (Self, Task<QuickCommandMessage>) {
+        let history = CommandHistory::load(&paths.history).unwrap_or_else(|e| {
+            tracing::warn!("{:#}", e);
+            CommandHistory::default()
+        });
+        let routines = load_routines(&paths.routines).unwrap_or_else(|e| {
+            tracing::warn!("{:#}", e);
+            Vec::new()
+        });
+
+        (
+            Self {
+                id,
+                input: String::new(),
+                history,
+                routines,
+                paths,
+                submitting: false,
+                error: None,
+            },
+            text_input::focus(INPUT_ID),
+        )
+    }
+
+    pub fn update(&mut self, message: QuickCommandMessage) -> Task<QuickCommandMessage> {
+        match message {
+            QuickCommandMessage::InputChanged(input) => {
+                self.input = input;
+                self.error = None;
+                Task::none()
+            }
+            QuickCommandMessage::Submit => {
+                let command = self.input.trim().to_string();
+                if command.is_empty() || self.submitting {
+                    return Task::none();
+                }
+
+                self.submitting = true;
+                self.history.push(&command);
+                if let Err(e) = self.history.save(&self.paths.history) {
+                    tracing::warn!("{:#}", e);
+                }
+
+                tracing::info!("Quick command submitted: {}", command);
+                Task::perform(
+                    async move {
+                        crate::ipc::send_execute_command(&command, None, false)
+                            .await
+                            .map(|_| ())
+                            .map_err(|e| e.to_string())
+                    },
+                    QuickCommandMessage::Submitted,
+                )
+            }
+            QuickCommandMessage::Submitted(Ok(())) => window::close(self.id),
+            QuickCommandMessage::Submitted(Err(e)) => {
+                self.submitting = false;
+                self.error = Some(e);
+                Task::none()
+            }
+            QuickCommandMessage::HistoryPrevious => match self.history.older(&self.input) {
+                Some(entry) => {
+                    self.input = entry;
+                    text_input::move_cursor_to_end(INPUT_ID)
+                }
+                None => Task::none(),
+            },
+            QuickCommandMessage::HistoryNext => match self.history.newer() {
+                Some(entry) => {
+                    self.input = entry;
+                    text_input::move_cursor_to_end(INPUT_ID)
+                }
+                None => Task::none(),
+            },
+            QuickCommandMessage::AcceptSuggestion(index) => {
+                match suggestions(&self.routines, &self.input).get(index) {
+                    Some(routine) => {
+                        self.input = routine.command.clone();
+                        Task::batch([
+                            text_input::focus(INPUT_ID),
+                            text_input::move_cursor_to_end(INPUT_ID),
+                        ])
+                    }
+                    None => Task::none(),
+                }
+            }
+            QuickCommandMessage::Close => window::close(self.id),
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, QuickCommandMessage> {
+        let mut input = text_input("What should superctrl do?", &self.input)
+            .id(INPUT_ID)
+            .size(18)
+            .padding(12);
+        if !self.submitting {
+            input = input
+                .on_input(QuickCommandMessage::InputChanged)
+                .on_submit(QuickCommandMessage::Submit);
+        }
+
+        let mut content = column![input].spacing(8);
+
+        for (index, routine) in suggestions(&self.routines, &self.input)
+            .into_iter()
+            .enumerate()
+        {
+            let label = column![
+                text(&routine.name).size(14),
+                text(&routine.command)
+                    .size(12)
+                    .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            ];
+            content = content.push(
+                button(label)
+                    .on_press(QuickCommandMessage::AcceptSuggestion(index))
+                    .width(Length::Fill)
+                    .style(button::text),
+            );
+        }
+
+        if let Some(error) = &self.error {
+            content = content.push(
+                text(format!("✗ {}", error))
+                    .size(13)
+                    .color(Color::from_rgb(0.8, 0.0, 0.0)),
+            );
+        }
+
+        content = content.push(
+            text("↩ run  ·  ↑↓ history  ·  ⇥ complete routine  ·  ⎋ close")
+                .size(12)
+                .color(Color::from_rgb(0.5, 0.5, 0.5)),
+        );
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(16)
+            .into()
+    }
+
+    // Keys are tagged with the window they were pressed in so other windows
+    // don't drive the quick command.
+    pub fn subscription() -> Subscription<(window::Id, QuickCommandMessage)> {
+        event::listen_with(|event, _status, window| match event {
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: Key::Named(named),
+                ..
+            }) => match named {
+                Named::ArrowUp => Some(QuickCommandMessage::HistoryPrevious),
+                Named::ArrowDown => Some(QuickCommandMessage::HistoryNext),
+                Named::Tab => Some(QuickCommandMessage::AcceptSuggestion(0)),
+                Named::Escape => Some(QuickCommandMessage::Close),
+                _ => None,
+            }
+            .map(|message| (window, message)),
+            _ => None,
+        })
+    }
+}
+
+pub fn window_settings() -> window::Settings {
+    window::Settings {
+        size: Size::new(560.0, 280.0),
+        position: window::Position::Centered,
+        level: window::Level::AlwaysOnTop,
+        resizable: false,
+        ..window::Settings::default()
+    }
+}
@@ -0,0 +1,103 @@
+use std::io::Write;
+use std::sync::{OnceLock, RwLock};
+
+use tracing_subscriber::fmt::MakeWriter;
+
+const REDACTED: &str = "[REDACTED]";
+const API_KEY_PREFIX: &str = "sk-ant-";
+const MIN_SECRET_LEN: usize = 4;
+
+fn secrets() -> &'static RwLock<Vec<String>> {
+    static SECRETS: OnceLock<RwLock<Vec<String>>> = OnceLock::new();
+    SECRETS.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+pub fn register_secret(secret: &str) {
+    let secret = secret.trim();
+    if secret.len() < MIN_SECRET_LEN {
+        return;
+    }
+
+    let mut secrets = secrets().write().unwrap();
+    if !secrets.iter().any(|known| known == secret) {
+        secrets.push(secret.to_string());
+        secrets.sort_by_key(|known| std::cmp::Reverse(known.len()));
+    }
+}
+
+pub fn redact(text: &str) -> String {
+    let mut redacted = text.to_string();
+    for secret in secrets().read().unwrap().iter() {
+        if redacted.contains(secret.as_str()) {
+            redacted = redacted.replace(secret.as_str(), REDACTED);
+        }
+    }
+
+    let mut output = String::with_capacity(redacted.len());
+    let mut rest = redacted.as_str();
+    while let Some(start) = rest.find(API_KEY_PREFIX) {
+        output.push_str(&rest[..start]);
+        let token = &rest[start + API_KEY_PREFIX.len()..];
+        let end = token
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(token.len());
+        if end == 0 {
+            output.push_str(API_KEY_PREFIX);
+        } else {
+            output.push_str(REDACTED);
+        }
+        rest = &token[end..];
+    }
+    output.push_str(rest);
+    output
+}
+
+pub fn mask(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= 12 {
+        return "*".repeat(chars.len());
+    }
+    let prefix = if secret.starts_with(API_KEY_PREFIX) {
+        API_KEY_PREFIX.to_string()
+    } else {
+        chars[..3].iter().collect()
+    };
+    let suffix: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", prefix, suffix)
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RedactingStdout;
+
+impl<'a> MakeWriter<'a> for RedactingStdout {
+    type Writer = RedactedLine;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactedLine { buffer: Vec::new() }
+    }
+}
+
+pub struct RedactedLine {
+    buffer: Vec<u8>,
+}
+
+impl Write for RedactedLine {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for RedactedLine {
+    fn drop(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let line = redact(&String::from_utf8_lossy(&self.buffer));
+        let _ = std::io::stdout().lock().write_all(line.as_bytes());
+    }
+}
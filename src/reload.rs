@@ -0,0 +1,148 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local};
+
+use crate::config::Settings;
+
+const RESTART_REQUIRED: &[&str] = &[
+    "learning_enabled",
+    "clipboard_monitoring",
+    "paths.",
+    "secrets.",
+    "http.",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    std::fs::metadata(path).ok().map(|metadata| FileStamp {
+        modified: metadata.modified().ok(),
+        len: metadata.len(),
+    })
+}
+
+pub struct ConfigWatcher {
+    path: PathBuf,
+    stamp: Option<FileStamp>,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: PathBuf) -> Self {
+        let stamp = file_stamp(&path);
+        Self { path, stamp }
+    }
+
+    pub fn changed(&mut self) -> bool {
+        let stamp = file_stamp(&self.path);
+        if stamp == self.stamp {
+            return false;
+        }
+        self.stamp = stamp;
+        true
+    }
+}
+
+pub fn changed_keys(old: &Settings, new: &Settings) -> Vec<String> {
+    let mut changed = Vec::new();
+    match (toml::Value::try_from(old), toml::Value::try_from(new)) {
+        (Ok(old), Ok(new)) => diff_values("", &old, &new, &mut changed),
+        _ if old != new => changed.push("settings".to_string()),
+        _ => {}
+    }
+    changed
+}
+
+fn diff_values(prefix: &str, old: &toml::Value, new: &toml::Value, changed: &mut Vec<String>) {
+    match (old, new) {
+        (toml::Value::Table(old), toml::Value::Table(new)) => {
+            for (key, value) in new {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                match old.get(key) {
+                    Some(previous) => diff_values(&path, previous, value, changed),
+                    None => changed.push(path),
+                }
+            }
+        }
+        _ if old != new => changed.push(prefix.to_string()),
+        _ => {}
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReloadPlan {
+    pub changed: Vec<String>,
+    pub restart_required: Vec<String>,
+}
+
+impl ReloadPlan {
+    pub fn new(old: &Settings, new: &Settings) -> Self {
+        let changed = changed_keys(old, new);
+        let restart_required = changed
+            .iter()
+            .filter(|key| {
+                RESTART_REQUIRED
+                    .iter()
+                    .any(|prefix| key.as_str() == *prefix || key.starts_with(prefix))
+            })
+            .cloned()
+            .collect();
+        Self {
+            changed,
+            restart_required,
+        }
+    }
+
+    pub fn hotkeys_changed(&self) -> bool {
+        self.changed.iter().any(|key| key.starts_with("hotkeys."))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReloadStatus {
+    Applied {
+        at: DateTime<Local>,
+        plan: ReloadPlan,
+    },
+    Failed {
+        at: DateTime<Local>,
+        error: String,
+    },
+}
+
+impl ReloadStatus {
+    pub fn describe(&self) -> String {
+        match self {
+            ReloadStatus::Applied { at, plan } if plan.changed.is_empty() => {
+                format!("Config reloaded at {} (no changes)", at.format("%H:%M:%S"))
+            }
+            ReloadStatus::Applied { at, plan } => {
+                let mut description = format!(
+                    "Config reloaded at {} (changed: {})",
+                    at.format("%H:%M:%S"),
+                    plan.changed.join(", ")
+                );
+                if !plan.restart_required.is_empty() {
+                    description.push_str(&format!(
+                        "; restart superctrl to apply {}",
+                        plan.restart_required.join(", ")
+                    ));
+                }
+                description
+            }
+            ReloadStatus::Failed { at, error } => format!(
+                "Config reload failed at {}: {}. Still using the previous settings.",
+                at.format("%H:%M:%S"),
+                error
+            ),
+        }
+    }
+}
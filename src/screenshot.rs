@@ -1,12 +1,89 @@
 use anyhow::{Context, Result};
 use base64::{engine::general_purpose::STANDARD, Engine as _};
-use image::{ImageBuffer, Rgba};
+use image::{ImageBuffer, Rgba, RgbaImage};
 use std::io::Cursor;
-use xcap::Monitor;
+use xcap::{Monitor, Window};
+
+use crate::config::{RedactionSettings, ScreenRegion, DEFAULT_SCREENSHOT_QUALITY};
+
+const SYSTEM_WINDOW_OWNERS: &[&str] = &[
+    "Window Server",
+    "Dock",
+    "Control Center",
+    "SystemUIServer",
+    "Notification Center",
+    "superctrl",
+];
+const MIN_FRONTMOST_SIZE: u32 = 50;
+const TEXT_TILE: u32 = 16;
+const TEXT_EDGE_CONTRAST: i16 = 48;
+const TEXT_MIN_EDGE_DENSITY: f64 = 0.06;
+const TEXT_MIN_BACKGROUND: f64 = 0.5;
+const TEXT_BACKGROUND_TOLERANCE: i16 = 16;
+const TEXT_BLUR_SIGMA: f32 = 6.0;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowInfo {
+    pub app_name: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RedactionReport {
+    pub blanked: Option<String>,
+    pub regions: usize,
+    pub text_regions: usize,
+}
+
+impl RedactionReport {
+    pub fn is_empty(&self) -> bool {
+        self.blanked.is_none() && self.regions == 0 && self.text_regions == 0
+    }
+
+    pub fn describe(&self) -> Option<String> {
+        if let Some(reason) = &self.blanked {
+            return Some(format!(
+                "Privacy redaction: this screenshot was blanked because {}. Nothing on screen is \
+                 visible. Do not try to reveal or guess the hidden content; if the task needs \
+                 that window, tell the user it is protected.",
+                reason
+            ));
+        }
+
+        let mut parts = Vec::new();
+        if self.regions > 0 {
+            parts.push(format!(
+                "{} configured screen region(s) are blacked out",
+                self.regions
+            ));
+        }
+        if self.text_regions > 0 {
+            parts.push(format!(
+                "{} area(s) that looked like text are blurred",
+                self.text_regions
+            ));
+        }
+        if parts.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "Privacy redaction: {}. Black or blurred areas hide real content, so do not click on \
+             them or guess what they contain.",
+            parts.join(" and ")
+        ))
+    }
+}
+
+pub struct CapturedScreen {
+    pub data: String,
+    pub redaction: RedactionReport,
+}
 
 pub struct ScreenCapture {
     display_width: u32,
     display_height: u32,
+    quality: u8,
+    redaction: RedactionSettings,
 }
 
 impl ScreenCapture {
@@ -14,10 +91,22 @@ impl ScreenCapture {
         Self {
             display_width,
             display_height,
+            quality: DEFAULT_SCREENSHOT_QUALITY,
+            redaction: RedactionSettings::default(),
         }
     }
 
-    pub fn capture_screenshot(&self) -> Result<String> {
+    pub fn with_quality(mut self, quality: u8) -> Self {
+        self.quality = quality.clamp(1, 100);
+        self
+    }
+
+    pub fn with_redaction(mut self, redaction: RedactionSettings) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    pub fn capture_screenshot(&self) -> Result<CapturedScreen> {
         let monitors = Monitor::all().context("Failed to get monitors")?;
         let primary = monitors
             .into_iter()
@@ -28,10 +117,27 @@ impl ScreenCapture {
             .capture_image()
             .context("Failed to capture screen")?;
 
-        let rgba_image: ImageBuffer<Rgba<u8>, Vec<u8>> =
+        let mut rgba_image: ImageBuffer<Rgba<u8>, Vec<u8>> =
             ImageBuffer::from_raw(image.width(), image.height(), image.to_vec())
                 .context("Failed to create image buffer")?;
 
+        let redaction = match self.blank_reason() {
+            Some(reason) => {
+                blank(&mut rgba_image);
+                RedactionReport {
+                    blanked: Some(reason),
+                    ..RedactionReport::default()
+                }
+            }
+            None => {
+                let scale = rgba_image.width() as f64 / primary.width().max(1) as f64;
+                apply_redaction(&mut rgba_image, &self.redaction, scale)
+            }
+        };
+        if !redaction.is_empty() {
+            tracing::info!("Screenshot redacted: {:?}", redaction);
+        }
+
         let resized = if rgba_image.width() != self.display_width
             || rgba_image.height() != self.display_height
         {
@@ -49,17 +155,35 @@ impl ScreenCapture {
 
         let mut jpeg_bytes = Vec::new();
         let mut cursor = Cursor::new(&mut jpeg_bytes);
-        let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut cursor, 40);
+        let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut cursor, self.quality);
         rgb_image
             .write_with_encoder(encoder)
             .context("Failed to encode JPEG")?;
 
-        Ok(STANDARD.encode(&jpeg_bytes))
+        Ok(CapturedScreen {
+            data: STANDARD.encode(&jpeg_bytes),
+            redaction,
+        })
     }
 
     pub fn get_display_size(&self) -> (u32, u32) {
         (self.display_width, self.display_height)
     }
+
+    fn blank_reason(&self) -> Option<String> {
+        if self.redaction.denylist_apps.is_empty() && self.redaction.denylist_titles.is_empty() {
+            return None;
+        }
+
+        match frontmost_window() {
+            Ok(Some(window)) => denied_reason(&self.redaction, &window),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Failed to check the frontmost window: {:#}", e);
+                Some("the frontmost window could not be checked against the denylist".to_string())
+            }
+        }
+    }
 }
 
 impl Default for ScreenCapture {
@@ -67,3 +191,163 @@ impl Default for ScreenCapture {
         Self::new(800, 600)
     }
 }
+
+fn frontmost_window() -> Result<Option<WindowInfo>> {
+    let windows = Window::all().context("Failed to list windows")?;
+    Ok(windows
+        .into_iter()
+        .find(|window| {
+            !window.is_minimized()
+                && window.width() >= MIN_FRONTMOST_SIZE
+                && window.height() >= MIN_FRONTMOST_SIZE
+                && !SYSTEM_WINDOW_OWNERS.contains(&window.app_name())
+        })
+        .map(|window| WindowInfo {
+            app_name: window.app_name().to_string(),
+            title: window.title().to_string(),
+        }))
+}
+
+pub fn denied_reason(settings: &RedactionSettings, window: &WindowInfo) -> Option<String> {
+    if settings
+        .denylist_apps
+        .iter()
+        .any(|app| app.trim().eq_ignore_ascii_case(window.app_name.trim()))
+    {
+        return Some(format!("{} is frontmost", window.app_name));
+    }
+
+    let title = window.title.to_lowercase();
+    if settings
+        .denylist_titles
+        .iter()
+        .any(|pattern| title.contains(&pattern.trim().to_lowercase()))
+    {
+        return Some(format!(
+            "the frontmost {} window has a denylisted title",
+            window.app_name
+        ));
+    }
+    None
+}
+
+pub fn blank(image: &mut RgbaImage) {
+    for pixel in image.pixels_mut() {
+        *pixel = Rgba([0, 0, 0, 255]);
+    }
+}
+
+pub fn apply_redaction(
+    image: &mut RgbaImage,
+    settings: &RedactionSettings,
+    scale: f64,
+) -> RedactionReport {
+    let mut report = RedactionReport::default();
+
+    for region in &settings.regions {
+        if let Some((x, y, width, height)) = scaled_region(image, region, scale) {
+            fill_black(image, x, y, width, height);
+            report.regions += 1;
+        }
+    }
+
+    if settings.blur_text {
+        let text_regions = detect_text_regions(image);
+        for &(x, y, width, height) in &text_regions {
+            let cropped = image::imageops::crop_imm(image, x, y, width, height).to_image();
+            let blurred = image::imageops::blur(&cropped, TEXT_BLUR_SIGMA);
+            image::imageops::replace(image, &blurred, x as i64, y as i64);
+        }
+        report.text_regions = text_regions.len();
+    }
+
+    report
+}
+
+fn scaled_region(
+    image: &RgbaImage,
+    region: &ScreenRegion,
+    scale: f64,
+) -> Option<(u32, u32, u32, u32)> {
+    let x = (region.x as f64 * scale).floor() as u32;
+    let y = (region.y as f64 * scale).floor() as u32;
+    let right = region.x.saturating_add(region.width) as f64 * scale;
+    let bottom = region.y.saturating_add(region.height) as f64 * scale;
+    let right = (right.ceil() as u32).min(image.width());
+    let bottom = (bottom.ceil() as u32).min(image.height());
+    if x >= right || y >= bottom {
+        return None;
+    }
+    Some((x, y, right - x, bottom - y))
+}
+
+fn fill_black(image: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32) {
+    for py in y..y + height {
+        for px in x..x + width {
+            image.put_pixel(px, py, Rgba([0, 0, 0, 255]));
+        }
+    }
+}
+
+fn luma(pixel: &Rgba<u8>) -> i16 {
+    let [r, g, b, _] = pixel.0;
+    ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as i16
+}
+
+fn looks_like_text(image: &RgbaImage, x: u32, y: u32, width: u32, height: u32) -> bool {
+    let mut values = Vec::with_capacity((width * height) as usize);
+    let mut edges = 0usize;
+    for py in y..y + height {
+        let mut previous: Option<i16> = None;
+        for px in x..x + width {
+            let value = luma(image.get_pixel(px, py));
+            if let Some(previous) = previous {
+                if (value - previous).abs() >= TEXT_EDGE_CONTRAST {
+                    edges += 1;
+                }
+            }
+            previous = Some(value);
+            values.push(value);
+        }
+    }
+
+    let pairs = (height * width.saturating_sub(1)) as usize;
+    if pairs == 0 || (edges as f64 / pairs as f64) < TEXT_MIN_EDGE_DENSITY {
+        return false;
+    }
+
+    values.sort_unstable();
+    let background = values[values.len() / 2];
+    let flat = values
+        .iter()
+        .filter(|value| (**value - background).abs() <= TEXT_BACKGROUND_TOLERANCE)
+        .count();
+    flat as f64 / values.len() as f64 >= TEXT_MIN_BACKGROUND
+}
+
+pub fn detect_text_regions(image: &RgbaImage) -> Vec<(u32, u32, u32, u32)> {
+    let mut regions = Vec::new();
+    let mut y = 0;
+    while y < image.height() {
+        let height = TEXT_TILE.min(image.height() - y);
+        let mut run: Option<(u32, u32)> = None;
+        let mut x = 0;
+        while x < image.width() {
+            let width = TEXT_TILE.min(image.width() - x);
+            if looks_like_text(image, x, y, width, height) {
+                run = Some(match run {
+                    Some((start, _)) => (start, x + width),
+                    None => (x, x + width),
+                });
+            } else if let Some((start, end)) = run.take() {
+                regions.push((start, y, end - start, height));
+            }
+            x += width;
+        }
+        if let Some((start, end)) = run {
+            regions.push((start, y, end - start, height));
+        }
+        y += height;
+    }
+    regions
+}
@@ -0,0 +1,449 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose, Engine as _};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{expand_home, SecretsSettings};
+use crate::keychain::KeychainStore;
+use crate::redact;
+
+pub const API_KEY_SECRET: &str = "anthropic-api-key";
+pub const HTTP_TOKEN_SECRET: &str = "http-api-token";
+pub const PASSPHRASE_ENV: &str = "SUPERCTRL_SECRETS_PASSPHRASE";
+
+const PLACEHOLDER_PREFIX: &str = "{{secret:";
+const PLACEHOLDER_SUFFIX: &str = "}}";
+const FILE_VERSION: u32 = 1;
+const KEY_LEN: usize = 32;
+const TOKEN_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SecretsBackend {
+    #[default]
+    Auto,
+    Keychain,
+    File,
+}
+
+impl std::fmt::Display for SecretsBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretsBackend::Auto => write!(f, "auto"),
+            SecretsBackend::Keychain => write!(f, "keychain"),
+            SecretsBackend::File => write!(f, "file"),
+        }
+    }
+}
+
+pub trait SecretStore: Send + Sync {
+    fn description(&self) -> String;
+    fn get(&self, name: &str) -> Result<Option<String>>;
+    fn set(&self, name: &str, value: &str) -> Result<()>;
+    fn delete(&self, name: &str) -> Result<()>;
+    fn names(&self) -> Result<Vec<String>>;
+}
+
+pub fn open_store(settings: &SecretsSettings) -> Result<Box<dyn SecretStore>> {
+    match settings.backend {
+        SecretsBackend::Auto if cfg!(target_os = "macos") => Ok(Box::new(KeychainStore)),
+        SecretsBackend::Auto | SecretsBackend::File => {
+            let path = expand_home(&settings.file)?;
+            Ok(Box::new(match std::env::var(PASSPHRASE_ENV) {
+                Ok(passphrase) if !passphrase.is_empty() => {
+                    FileStore::with_passphrase(path, passphrase)
+                }
+                _ => FileStore::new(path),
+            }))
+        }
+        SecretsBackend::Keychain if cfg!(target_os = "macos") => Ok(Box::new(KeychainStore)),
+        SecretsBackend::Keychain => {
+            anyhow::bail!("The keychain secrets backend is only available on macOS")
+        }
+    }
+}
+
+pub fn placeholder(name: &str) -> String {
+    format!("{}{}{}", PLACEHOLDER_PREFIX, name, PLACEHOLDER_SUFFIX)
+}
+
+pub fn is_reserved_secret(name: &str) -> bool {
+    name == API_KEY_SECRET || name == HTTP_TOKEN_SECRET
+}
+
+pub fn http_token(store: &dyn SecretStore, rotate: bool) -> Result<String> {
+    if !rotate {
+        if let Some(token) = store.get(HTTP_TOKEN_SECRET)? {
+            redact::register_secret(&token);
+            return Ok(token);
+        }
+    }
+
+    let mut bytes = [0u8; TOKEN_LEN];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| anyhow::anyhow!("Failed to generate an HTTP API token"))?;
+    let token = general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+    store.set(HTTP_TOKEN_SECRET, &token)?;
+    redact::register_secret(&token);
+    Ok(token)
+}
+
+pub fn is_valid_secret_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn next_placeholder(text: &str) -> Option<(usize, &str, usize)> {
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find(PLACEHOLDER_PREFIX) {
+        let start = offset + start;
+        let name_start = start + PLACEHOLDER_PREFIX.len();
+        if let Some(len) = text[name_start..].find(PLACEHOLDER_SUFFIX) {
+            let name = &text[name_start..name_start + len];
+            if is_valid_secret_name(name) {
+                return Some((start, name, name_start + len + PLACEHOLDER_SUFFIX.len()));
+            }
+        }
+        offset = name_start;
+    }
+    None
+}
+
+pub fn placeholder_names(text: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = text;
+    while let Some((_, name, end)) = next_placeholder(rest) {
+        names.push(name);
+        rest = &rest[end..];
+    }
+    names
+}
+
+pub fn typeable_secret_names(store: &dyn SecretStore) -> Result<Vec<String>> {
+    Ok(store
+        .names()?
+        .into_iter()
+        .filter(|name| !is_reserved_secret(name) && is_valid_secret_name(name))
+        .collect())
+}
+
+pub fn substitute_placeholders(text: &str, store: &dyn SecretStore) -> Result<String> {
+    let mut substituted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some((start, name, end)) = next_placeholder(rest) {
+        if is_reserved_secret(name) {
+            anyhow::bail!("The secret '{}' cannot be typed", name);
+        }
+        let value = store.get(name)?.with_context(|| {
+            format!(
+                "Unknown secret '{}'. Save it with `superctrl auth set-secret {}`",
+                name, name
+            )
+        })?;
+        redact::register_secret(&value);
+        substituted.push_str(&rest[..start]);
+        substituted.push_str(&value);
+        rest = &rest[end..];
+    }
+    substituted.push_str(rest);
+    Ok(substituted)
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SecretFile {
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kdf: Option<KeyDerivation>,
+    secrets: BTreeMap<String, SealedSecret>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KeyDerivation {
+    algorithm: String,
+    salt: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+impl KeyDerivation {
+    fn generate() -> Result<Self> {
+        let mut salt = [0u8; SALT_LEN];
+        SystemRandom::new()
+            .fill(&mut salt)
+            .map_err(|_| anyhow::anyhow!("Failed to generate a salt"))?;
+        Ok(Self {
+            algorithm: "argon2id".to_string(),
+            salt: general_purpose::STANDARD.encode(salt),
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        })
+    }
+
+    fn derive(&self, passphrase: &str) -> Result<Vec<u8>> {
+        if self.algorithm != "argon2id" {
+            anyhow::bail!("Unsupported key derivation '{}'", self.algorithm);
+        }
+        let salt = general_purpose::STANDARD
+            .decode(&self.salt)
+            .context("Key derivation salt is not valid base64")?;
+        let params = Params::new(
+            self.memory_kib,
+            self.iterations,
+            self.parallelism,
+            Some(KEY_LEN),
+        )
+        .map_err(|e| anyhow::anyhow!("Invalid key derivation parameters: {}", e))?;
+
+        let mut key = vec![0u8; KEY_LEN];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| anyhow::anyhow!("Failed to derive the secrets key: {}", e))?;
+        Ok(key)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SealedSecret {
+    nonce: String,
+    ciphertext: String,
+}
+
+pub struct FileStore {
+    path: PathBuf,
+    key_path: PathBuf,
+    passphrase: Option<String>,
+}
+
+impl FileStore {
+    pub fn new(path: PathBuf) -> Self {
+        let key_path = path.with_extension("key");
+        Self {
+            path,
+            key_path,
+            passphrase: None,
+        }
+    }
+
+    pub fn with_passphrase(path: PathBuf, passphrase: String) -> Self {
+        Self {
+            passphrase: Some(passphrase),
+            ..Self::new(path)
+        }
+    }
+
+    fn uses_passphrase(&self) -> bool {
+        match self.load() {
+            Ok(file) if file.kdf.is_some() => true,
+            Ok(file) if !file.secrets.is_empty() => false,
+            _ => self.passphrase.is_some(),
+        }
+    }
+
+    fn load(&self) -> Result<SecretFile> {
+        if !self.path.exists() {
+            return Ok(SecretFile {
+                version: FILE_VERSION,
+                ..SecretFile::default()
+            });
+        }
+
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read secrets from {:?}", self.path))?;
+        let file: SecretFile = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse secrets from {:?}", self.path))?;
+        if file.version != FILE_VERSION {
+            anyhow::bail!(
+                "Unsupported secrets file version {} in {:?}",
+                file.version,
+                self.path
+            );
+        }
+        Ok(file)
+    }
+
+    fn save(&self, file: &SecretFile) -> Result<()> {
+        let content = serde_json::to_string_pretty(file)?;
+        write_private(&self.path, content.as_bytes())
+    }
+
+    fn key(&self, file: &mut SecretFile, create: bool) -> Result<Option<LessSafeKey>> {
+        if file.kdf.is_none() && file.secrets.is_empty() && create && self.passphrase.is_some() {
+            file.kdf = Some(KeyDerivation::generate()?);
+        }
+
+        let bytes = if let Some(kdf) = &file.kdf {
+            let passphrase = self.passphrase.as_deref().with_context(|| {
+                format!(
+                    "{:?} is protected by a passphrase. Set {} to unlock it",
+                    self.path, PASSPHRASE_ENV
+                )
+            })?;
+            kdf.derive(passphrase)?
+        } else if self.key_path.exists() {
+            let encoded = std::fs::read_to_string(&self.key_path)
+                .with_context(|| format!("Failed to read secrets key {:?}", self.key_path))?;
+            general_purpose::STANDARD
+                .decode(encoded.trim())
+                .with_context(|| format!("Secrets key {:?} is not valid base64", self.key_path))?
+        } else if create {
+            let mut bytes = vec![0u8; KEY_LEN];
+            SystemRandom::new()
+                .fill(&mut bytes)
+                .map_err(|_| anyhow::anyhow!("Failed to generate a secrets key"))?;
+            write_private(
+                &self.key_path,
+                general_purpose::STANDARD.encode(&bytes).as_bytes(),
+            )?;
+            bytes
+        } else {
+            return Ok(None);
+        };
+
+        let key = UnboundKey::new(&CHACHA20_POLY1305, &bytes)
+            .map_err(|_| anyhow::anyhow!("Secrets key {:?} has the wrong length", self.key_path))?;
+        Ok(Some(LessSafeKey::new(key)))
+    }
+}
+
+impl SecretStore for FileStore {
+    fn description(&self) -> String {
+        if self.uses_passphrase() {
+            format!(
+                "file {}, encrypted with a key derived from {}",
+                self.path.display(),
+                PASSPHRASE_ENV
+            )
+        } else {
+            format!(
+                "file {}, obfuscated with the key stored next to it in {} (not encrypted at rest)",
+                self.path.display(),
+                self.key_path.display()
+            )
+        }
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>> {
+        let mut file = self.load()?;
+        if !file.secrets.contains_key(name) {
+            return Ok(None);
+        }
+        let key = self.key(&mut file, false)?.with_context(|| {
+            format!(
+                "Secrets key {:?} is missing, so {:?} cannot be decrypted",
+                self.key_path, self.path
+            )
+        })?;
+
+        let sealed = &file.secrets[name];
+        let nonce: [u8; NONCE_LEN] = general_purpose::STANDARD
+            .decode(&sealed.nonce)
+            .ok()
+            .and_then(|nonce| nonce.try_into().ok())
+            .with_context(|| format!("Secret '{}' has an invalid nonce", name))?;
+        let mut in_out = general_purpose::STANDARD
+            .decode(&sealed.ciphertext)
+            .with_context(|| format!("Secret '{}' is not valid base64", name))?;
+        let plaintext = key
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(name.as_bytes()),
+                &mut in_out,
+            )
+            .map_err(|_| {
+                if file.kdf.is_some() {
+                    anyhow::anyhow!(
+                        "Failed to decrypt secret '{}' (is {} correct?)",
+                        name,
+                        PASSPHRASE_ENV
+                    )
+                } else {
+                    anyhow::anyhow!(
+                        "Failed to decrypt secret '{}' (was {:?} replaced?)",
+                        name,
+                        self.key_path
+                    )
+                }
+            })?;
+
+        String::from_utf8(plaintext.to_vec())
+            .map(Some)
+            .with_context(|| format!("Secret '{}' is not valid UTF-8", name))
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<()> {
+        let mut file = self.load()?;
+        let key = self
+            .key(&mut file, true)?
+            .context("Failed to create a secrets key")?;
+
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| anyhow::anyhow!("Failed to generate a nonce"))?;
+        let mut in_out = value.as_bytes().to_vec();
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(name.as_bytes()),
+            &mut in_out,
+        )
+        .map_err(|_| anyhow::anyhow!("Failed to encrypt secret '{}'", name))?;
+
+        file.secrets.insert(
+            name.to_string(),
+            SealedSecret {
+                nonce: general_purpose::STANDARD.encode(nonce),
+                ciphertext: general_purpose::STANDARD.encode(&in_out),
+            },
+        );
+        self.save(&file)
+    }
+
+    fn delete(&self, name: &str) -> Result<()> {
+        let mut file = self.load()?;
+        if file.secrets.remove(name).is_some() {
+            self.save(&file)?;
+        }
+        Ok(())
+    }
+
+    fn names(&self) -> Result<Vec<String>> {
+        Ok(self.load()?.secrets.into_keys().collect())
+    }
+}
+
+fn write_private(path: &Path, content: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {:?}", parent))?;
+    }
+
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut temp = options
+        .open(&temp_path)
+        .with_context(|| format!("Failed to write {:?}", temp_path))?;
+    temp.write_all(content)?;
+    temp.sync_all()?;
+    std::fs::rename(&temp_path, path).with_context(|| format!("Failed to write {:?}", path))
+}
@@ -0,0 +1,332 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::computer_use::api_error_message;
+use crate::gui::{AppState, GuiState};
+use crate::hotkey::format_hotkey;
+use crate::learning::SessionStats;
+
+pub const API_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const API_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub version: String,
+    pub pid: u32,
+    pub started_at: DateTime<Local>,
+    pub uptime_secs: u64,
+    pub state: String,
+    pub paused: bool,
+    pub current_task: Option<CurrentTask>,
+    pub active_tasks: usize,
+    pub queued_tasks: usize,
+    pub learning: LearningStatus,
+    pub hotkeys: HotkeyStatus,
+    pub last_error: Option<ErrorRecord>,
+    pub api: Option<ApiStatus>,
+    pub config_reload: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CurrentTask {
+    pub command: String,
+    pub step: usize,
+    pub max_steps: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LearningStatus {
+    pub enabled: bool,
+    pub active: bool,
+    pub events: Option<SessionStats>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HotkeyStatus {
+    pub registered: bool,
+    pub error: Option<String>,
+    pub bindings: Vec<HotkeyBinding>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HotkeyBinding {
+    pub action: String,
+    pub hotkey: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorRecord {
+    pub at: DateTime<Local>,
+    pub message: String,
+}
+
+impl ErrorRecord {
+    pub fn now(message: impl Into<String>) -> Self {
+        Self {
+            at: Local::now(),
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiHealth {
+    Ok,
+    Unreachable,
+    AuthFailed,
+    HttpError,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiStatus {
+    pub health: ApiHealth,
+    pub checked_at: DateTime<Local>,
+    pub latency_ms: Option<u64>,
+    pub http_status: Option<u16>,
+    pub error: Option<String>,
+}
+
+impl DaemonStatus {
+    pub fn collect(gui: &GuiState, learning: LearningStatus) -> Self {
+        let current_task = match &gui.app_state {
+            AppState::Working(command) => {
+                let live = gui.agent_events.current();
+                let (step, max_steps) = if live.is_running() {
+                    (live.step, live.max_steps)
+                } else {
+                    (0, 0)
+                };
+                Some(CurrentTask {
+                    command: command.clone(),
+                    step,
+                    max_steps,
+                })
+            }
+            _ => None,
+        };
+
+        let queued = gui.tasks.queued_count();
+        let running = gui.tasks.active_count().saturating_sub(queued);
+        let now = Local::now();
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            pid: std::process::id(),
+            started_at: gui.started_at,
+            uptime_secs: (now - gui.started_at).num_seconds().max(0) as u64,
+            state: gui
+                .app_state
+                .status_text()
+                .trim_end_matches("...")
+                .to_string(),
+            paused: gui.is_paused(),
+            current_task,
+            active_tasks: running,
+            queued_tasks: queued,
+            learning,
+            hotkeys: HotkeyStatus {
+                registered: gui.hotkey_error.is_none(),
+                error: gui.hotkey_error.clone(),
+                bindings: gui
+                    .hotkeys
+                    .iter()
+                    .map(|(action, hotkey)| HotkeyBinding {
+                        action: action.to_string(),
+                        hotkey: format_hotkey(&hotkey),
+                    })
+                    .collect(),
+            },
+            last_error: gui.last_error.clone(),
+            api: gui.api_status.clone(),
+            config_reload: gui.config_reload.as_ref().map(|reload| reload.describe()),
+        }
+    }
+
+    pub fn render_table(&self) -> String {
+        let mut rows: Vec<(&str, String)> = vec![
+            (
+                "Daemon",
+                format!("superctrl {} (pid {})", self.version, self.pid),
+            ),
+            (
+                "Uptime",
+                format!(
+                    "{} (since {})",
+                    format_uptime(self.uptime_secs),
+                    self.started_at.format("%Y-%m-%d %H:%M:%S")
+                ),
+            ),
+            (
+                "State",
+                if self.paused {
+                    format!("{} (paused)", self.state)
+                } else {
+                    self.state.clone()
+                },
+            ),
+        ];
+
+        if let Some(task) = &self.current_task {
+            let step = if task.max_steps > 0 {
+                format!(" (step {}/{})", task.step, task.max_steps)
+            } else {
+                String::new()
+            };
+            rows.push(("Current task", format!("{}{}", task.command, step)));
+        }
+        rows.push((
+            "Tasks",
+            format!(
+                "{} running, {} queued",
+                self.active_tasks, self.queued_tasks
+            ),
+        ));
+
+        let learning_state = if self.learning.active {
+            "active"
+        } else {
+            "stopped"
+        };
+        let learning = match &self.learning.events {
+            _ if !self.learning.enabled => "disabled".to_string(),
+            Some(events) => format!(
+                "{}, {} events ({} keypresses, {} app switches, {} clipboard changes)",
+                learning_state,
+                events.total_events,
+                events.keypress_count,
+                events.app_switch_count,
+                events.clipboard_change_count
+            ),
+            None => learning_state.to_string(),
+        };
+        rows.push(("Learning", learning));
+
+        let hotkeys = match &self.hotkeys.error {
+            Some(error) => format!("not registered: {}", error),
+            None if self.hotkeys.bindings.is_empty() => "registered (none bound)".to_string(),
+            None => format!(
+                "registered: {}",
+                self.hotkeys
+                    .bindings
+                    .iter()
+                    .map(|binding| format!("{}={}", binding.action, binding.hotkey))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        rows.push(("Hotkeys", hotkeys));
+
+        rows.push((
+            "Last error",
+            match &self.last_error {
+                Some(error) => format!("{} {}", error.at.format("%H:%M:%S"), error.message),
+                None => "none".to_string(),
+            },
+        ));
+
+        let api = match &self.api {
+            None => "not checked yet".to_string(),
+            Some(api) => {
+                let checked = api.checked_at.format("%H:%M:%S");
+                let error = api.error.as_deref().unwrap_or("unknown error");
+                let http_status = api
+                    .http_status
+                    .map(|status| status.to_string())
+                    .unwrap_or_default();
+                match (api.health, api.latency_ms) {
+                    (ApiHealth::Ok, Some(latency)) => {
+                        format!("reachable ({} ms, checked {})", latency, checked)
+                    }
+                    (ApiHealth::Ok, None) => format!("reachable (checked {})", checked),
+                    (ApiHealth::Unreachable, _) => {
+                        format!("unreachable: {} (checked {})", error, checked)
+                    }
+                    (ApiHealth::AuthFailed, _) => format!(
+                        "authentication failed (HTTP {}): {} (checked {})",
+                        http_status, error, checked
+                    ),
+                    (ApiHealth::HttpError, _) => format!(
+                        "HTTP error {}: {} (checked {})",
+                        http_status, error, checked
+                    ),
+                }
+            }
+        };
+        rows.push(("API", api));
+
+        if let Some(reload) = &self.config_reload {
+            rows.push(("Config", reload.clone()));
+        }
+
+        rows.into_iter()
+            .map(|(label, value)| format!("{:<14}{}\n", label, value))
+            .collect()
+    }
+}
+
+pub fn format_uptime(secs: u64) -> String {
+    let days = secs / 86_400;
+    let hours = secs % 86_400 / 3_600;
+    let minutes = secs % 3_600 / 60;
+    let seconds = secs % 60;
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{}s", seconds),
+        (0, 0, _) => format!("{}m {}s", minutes, seconds),
+        (0, _, _) => format!("{}h {}m", hours, minutes),
+        _ => format!("{}d {}h {}m", days, hours, minutes),
+    }
+}
+
+pub fn models_url(api_url: &str) -> String {
+    let base = api_url.trim_end_matches('/');
+    match base.strip_suffix("/messages") {
+        Some(prefix) => format!("{}/models", prefix),
+        None => format!("{}/models", base),
+    }
+}
+
+pub async fn check_api(api_key: &str, api_url: &str) -> ApiStatus {
+    let started = Instant::now();
+    let response = reqwest::Client::new()
+        .get(models_url(api_url))
+        .query(&[("limit", "1")])
+        .timeout(API_CHECK_TIMEOUT)
+        .header("x-api-key", api_key)
+        .header("anthropic-version", "2023-06-01")
+        .send()
+        .await;
+
+    match response {
+        Ok(response) => {
+            let latency_ms = Some(started.elapsed().as_millis() as u64);
+            let status = response.status();
+            let (health, error) = if status.is_success() {
+                (ApiHealth::Ok, None)
+            } else {
+                let body = response.text().await.unwrap_or_default();
+                let health = match status {
+                    reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                        ApiHealth::AuthFailed
+                    }
+                    _ => ApiHealth::HttpError,
+                };
+                (health, Some(api_error_message(&body)))
+            };
+            ApiStatus {
+                health,
+                checked_at: Local::now(),
+                latency_ms,
+                http_status: Some(status.as_u16()),
+                error,
+            }
+        }
+        Err(e) => ApiStatus {
+            health: ApiHealth::Unreachable,
+            checked_at: Local::now(),
+            latency_ms: None,
+            http_status: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    Arc, Mutex, MutexGuard, PoisonError,
+};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+use crate::events::{DaemonEvent, EventBus};
+
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<TokenInner>,
+}
+
+#[derive(Debug, Default)]
+struct TokenInner {
+    cancelled: AtomicBool,
+    paused: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::Release);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+    }
+
+    pub fn pause(&self) {
+        self.inner.paused.store(true, Ordering::Release);
+    }
+
+    pub fn resume(&self) {
+        self.inner.paused.store(false, Ordering::Release);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.inner.paused.load(Ordering::Acquire)
+    }
+
+    pub async fn wait_if_paused(&self) -> Result<bool, TaskCancelled> {
+        let mut waited = false;
+        loop {
+            let notified = self.inner.notify.notified();
+            self.check()?;
+            if !self.is_paused() {
+                return Ok(waited);
+            }
+            if !waited {
+                tracing::info!("⏸ Task paused, waiting for resume");
+                waited = true;
+            }
+            notified.await;
+        }
+    }
+
+    pub fn check(&self) -> Result<(), TaskCancelled> {
+        if self.is_cancelled() {
+            Err(TaskCancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    pub async fn run_until_cancelled<F: Future>(
+        &self,
+        future: F,
+    ) -> Result<F::Output, TaskCancelled> {
+        tokio::select! {
+            biased;
+            _ = self.cancelled() => Err(TaskCancelled),
+            output = future => Ok(output),
+        }
+    }
+
+    pub async fn sleep(&self, duration: Duration) -> Result<(), TaskCancelled> {
+        self.run_until_cancelled(tokio::time::sleep(duration)).await
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskCancelled;
+
+impl fmt::Display for TaskCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Execution stopped by user")
+    }
+}
+
+impl std::error::Error for TaskCancelled {}
+
+pub fn is_cancelled_error(error: &anyhow::Error) -> bool {
+    error.is::<TaskCancelled>()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlSource {
+    Hotkey,
+    MenuBar,
+    Ipc,
+    UserInput,
+    Failsafe,
+    Viewer,
+}
+
+impl fmt::Display for ControlSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ControlSource::Hotkey => write!(f, "hotkey"),
+            ControlSource::MenuBar => write!(f, "menu bar"),
+            ControlSource::Ipc => write!(f, "IPC"),
+            ControlSource::UserInput => write!(f, "user input"),
+            ControlSource::Failsafe => write!(f, "screen-corner failsafe"),
+            ControlSource::Viewer => write!(f, "live viewer"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TaskRunner {
+    active: Arc<Mutex<HashMap<u64, CancellationToken>>>,
+    next_id: Arc<AtomicU64>,
+    turn: Arc<Mutex<()>>,
+    queued: Arc<AtomicUsize>,
+    last_resume: Arc<Mutex<Option<Instant>>>,
+    events: EventBus,
+}
+
+impl TaskRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    pub fn begin(&self) -> TaskGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let token = CancellationToken::new();
+        self.active.lock().unwrap().insert(id, token.clone());
+
+        TaskGuard {
+            id,
+            token,
+            runner: self.clone(),
+        }
+    }
+
+    pub fn stop(&self, source: ControlSource) -> usize {
+        let active = self.active.lock().unwrap();
+        for token in active.values() {
+            token.cancel();
+        }
+
+        tracing::info!(
+            "🛑 Stop requested via {} ({} running task(s))",
+            source,
+            active.len()
+        );
+        let tasks = active.len();
+        drop(active);
+        self.events.publish(DaemonEvent::Stop { source, tasks });
+        tasks
+    }
+
+    pub fn pause(&self, source: ControlSource) -> usize {
+        let active = self.active.lock().unwrap();
+        for token in active.values() {
+            token.pause();
+        }
+
+        tracing::info!(
+            "⏸ Pause requested via {} ({} running task(s))",
+            source,
+            active.len()
+        );
+        let tasks = active.len();
+        drop(active);
+        self.events.publish(DaemonEvent::Paused { source, tasks });
+        tasks
+    }
+
+    pub fn resume(&self, source: ControlSource) -> usize {
+        let active = self.active.lock().unwrap();
+        for token in active.values() {
+            token.resume();
+        }
+        *self.last_resume.lock().unwrap() = Some(Instant::now());
+
+        tracing::info!(
+            "▶ Resume requested via {} ({} running task(s))",
+            source,
+            active.len()
+        );
+        let tasks = active.len();
+        drop(active);
+        self.events.publish(DaemonEvent::Resumed { source, tasks });
+        tasks
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.active.lock().unwrap().is_empty()
+    }
+
+    pub fn active_count(&self) -> usize {
+        self.active.lock().unwrap().len()
+    }
+
+    pub fn queued_count(&self) -> usize {
+        self.queued.load(Ordering::Acquire)
+    }
+
+    pub fn resumed_within(&self, window: Duration) -> bool {
+        self.last_resume
+            .lock()
+            .unwrap()
+            .is_some_and(|resumed_at| resumed_at.elapsed() < window)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.active
+            .lock()
+            .unwrap()
+            .values()
+            .any(CancellationToken::is_paused)
+    }
+}
+
+pub struct TaskGuard {
+    id: u64,
+    token: CancellationToken,
+    runner: TaskRunner,
+}
+
+impl TaskGuard {
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    pub fn wait_turn(&self) -> MutexGuard<'_, ()> {
+        let queued = &self.runner.queued;
+        queued.fetch_add(1, Ordering::AcqRel);
+        let turn = self
+            .runner
+            .turn
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        queued.fetch_sub(1, Ordering::AcqRel);
+        turn
+    }
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        self.runner.active.lock().unwrap().remove(&self.id);
+    }
+}
@@ -0,0 +1,234 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use iced::futures::stream;
+use iced::widget::image::Handle;
+use iced::widget::{button, column, container, progress_bar, row, scrollable, text, Image};
+use iced::{window, Color, Element, Length, Size, Subscription, Task};
+use image::{Rgba, RgbaImage};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::events::{AgentEvents, LiveView};
+use crate::gui::SharedGuiState;
+use crate::history::TaskStatus;
+use crate::task::ControlSource;
+
+const MARKER_RADIUS: f64 = 14.0;
+const MARKER_THICKNESS: f64 = 1.5;
+const MARKER_DOT_RADIUS: f64 = 3.0;
+const MARKER_COLOR: Rgba<u8> = Rgba([255, 45, 85, 255]);
+
+pub const WINDOW_TITLE: &str = "superctrl - Live Viewer";
+
+pub fn mark_click(screenshot: &[u8], point: Option<(i32, i32)>) -> Result<RgbaImage> {
+    let mut frame = image::load_from_memory(screenshot)
+        .context("Failed to decode screenshot")?
+        .to_rgba8();
+
+    let Some((cx, cy)) = point else {
+        return Ok(frame);
+    };
+    let (width, height) = (frame.width() as i32, frame.height() as i32);
+    if cx < 0 || cy < 0 || cx >= width || cy >= height {
+        return Ok(frame);
+    }
+
+    let reach = (MARKER_RADIUS + MARKER_THICKNESS).ceil() as i32;
+    for y in (cy - reach).max(0)..=(cy + reach).min(height - 1) {
+        for x in (cx - reach).max(0)..=(cx + reach).min(width - 1) {
+            let distance = (((x - cx).pow(2) + (y - cy).pow(2)) as f64).sqrt();
+            if (distance - MARKER_RADIUS).abs() <= MARKER_THICKNESS || distance <= MARKER_DOT_RADIUS
+            {
+                frame.put_pixel(x as u32, y as u32, MARKER_COLOR);
+            }
+        }
+    }
+
+    Ok(frame)
+}
+
+#[derive(Debug, Clone)]
+pub enum ViewerMessage {
+    Updated,
+    Stop,
+    TogglePause,
+}
+
+pub struct ViewerWindow {
+    state: SharedGuiState,
+    events: AgentEvents,
+    live: LiveView,
+    frame: Option<Handle>,
+}
+
+impl ViewerWindow {
+    pub fn new(state: SharedGuiState) -> (Self, Task<ViewerMessage>) {
+        let events = state.lock().unwrap().agent_events.clone();
+        let mut window = Self {
+            state,
+            events,
+            live: LiveView::default(),
+            frame: None,
+        };
+        window.refresh();
+        (window, Task::none())
+    }
+
+    fn refresh(&mut self) {
+        let live = self.events.current();
+        let screenshot_changed = match (&live.screenshot, &self.live.screenshot) {
+            (Some(new), Some(old)) => !Arc::ptr_eq(new, old),
+            (None, None) => false,
+            _ => true,
+        };
+
+        if screenshot_changed || live.last_click != self.live.last_click {
+            self.frame = live.screenshot.as_ref().and_then(|screenshot| {
+                match mark_click(screenshot, live.last_click) {
+                    Ok(frame) => Some(Handle::from_rgba(
+                        frame.width(),
+                        frame.height(),
+                        frame.into_raw(),
+                    )),
+                    Err(e) => {
+                        tracing::warn!("{:#}", e);
+                        None
+                    }
+                }
+            });
+        }
+        self.live = live;
+    }
+
+    pub fn update(&mut self, message: ViewerMessage) -> Task<ViewerMessage> {
+        match message {
+            ViewerMessage::Updated => self.refresh(),
+            ViewerMessage::Stop => {
+                self.state
+                    .lock()
+                    .unwrap()
+                    .trigger_stop(ControlSource::Viewer);
+            }
+            ViewerMessage::TogglePause => {
+                let state = self.state.lock().unwrap();
+                if state.is_paused() {
+                    state.trigger_resume(ControlSource::Viewer);
+                } else {
+                    state.trigger_pause(ControlSource::Viewer);
+                }
+            }
+        }
+        Task::none()
+    }
+
+    pub fn view(&self) -> Element<'_, ViewerMessage> {
+        let grey = Color::from_rgb(0.5, 0.5, 0.5);
+        let live = &self.live;
+        let running = live.is_running();
+        let paused = running && self.state.lock().unwrap().is_paused();
+
+        let title = live.command.as_deref().unwrap_or("No task has run yet");
+        let status = match live.status {
+            Some(TaskStatus::Running) if paused => "⏸ Paused",
+            Some(TaskStatus::Running) => "🔵 Running",
+            Some(TaskStatus::Completed) => "✓ Completed",
+            Some(TaskStatus::Failed) => "✗ Failed",
+            Some(TaskStatus::Stopped) => "🛑 Stopped",
+            None => "⚪ Idle",
+        };
+
+        let mut stop = button("  Stop  ").padding(8);
+        let mut pause = button(if paused { "  Resume  " } else { "  Pause  " }).padding(8);
+        if running {
+            stop = stop.on_press(ViewerMessage::Stop);
+            pause = pause.on_press(ViewerMessage::TogglePause);
+        }
+
+        let header = row![
+            column![text(title).size(20), text(status).size(14).color(grey)]
+                .spacing(4)
+                .width(Length::Fill),
+            pause,
+            stop,
+        ]
+        .spacing(10);
+
+        let max_steps = live.max_steps.max(1);
+        let progress = row![
+            text(format!("Step {} / {}", live.step, live.max_steps))
+                .size(14)
+                .width(Length::Fixed(110.0)),
+            progress_bar(0.0..=max_steps as f32, live.step as f32).height(Length::Fixed(8.0)),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+
+        let last_action = text(match &live.last_action {
+            Some(action) => format!("Last action: {}", action),
+            None => "Last action: none".to_string(),
+        })
+        .size(13)
+        .color(grey);
+
+        let screen: Element<'_, ViewerMessage> = match &self.frame {
+            Some(frame) => Image::new(frame.clone()).width(Length::Fill).into(),
+            None => container(text("No screenshot yet").color(grey))
+                .center_x(Length::Fill)
+                .height(Length::Fixed(200.0))
+                .into(),
+        };
+
+        let mut transcript = column![text(if live.transcript.is_empty() {
+            "The assistant has not said anything yet"
+        } else {
+            &live.transcript
+        })
+        .size(14)]
+        .spacing(8);
+        if let Some(outcome) = &live.outcome {
+            transcript = transcript.push(text(outcome).size(14).color(grey));
+        }
+
+        let content = column![
+            header,
+            progress,
+            last_action,
+            row![
+                container(screen).width(Length::FillPortion(3)),
+                scrollable(transcript)
+                    .anchor_bottom()
+                    .width(Length::FillPortion(2))
+                    .height(Length::Fill),
+            ]
+            .spacing(15),
+        ]
+        .spacing(12)
+        .padding(20);
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<ViewerMessage> {
+        let receiver = self.events.subscribe();
+        Subscription::run_with_id(
+            "agent-events",
+            stream::unfold(receiver, |mut receiver| async move {
+                match receiver.recv().await {
+                    Ok(_) | Err(RecvError::Lagged(_)) => Some((ViewerMessage::Updated, receiver)),
+                    Err(RecvError::Closed) => None,
+                }
+            }),
+        )
+    }
+}
+
+pub fn window_settings() -> window::Settings {
+    window::Settings {
+        size: Size::new(1100.0, 720.0),
+        position: window::Position::Centered,
+        ..window::Settings::default()
+    }
+}
@@ -1,13 +1,11 @@
 use anyhow::Result;
-use std::sync::{atomic::AtomicBool, Arc};
 use superctrl::computer_use::ComputerUseAgent;
+use superctrl::task::CancellationToken;
 
 #[test]
 fn test_computer_use_agent_creation() -> Result<()> {
-    let stop_flag = Arc::new(AtomicBool::new(false));
-
     let api_key = "test-key-12345".to_string();
-    let _agent = ComputerUseAgent::new(api_key, stop_flag)?;
+    let _agent = ComputerUseAgent::new(api_key, CancellationToken::new())?;
 
     Ok(())
 }
@@ -65,3 +63,63 @@ fn test_tool_result_format() {
     assert_eq!(tool_result[0]["source"]["media_type"], "image/jpeg");
     assert_eq!(tool_result[0]["source"]["data"], screenshot_data);
 }
+
+#[test]
+fn test_structured_result_validation() {
+    use serde_json::json;
+    use superctrl::computer_use::validate_structured_result;
+
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "build_number": { "type": "integer" },
+            "branch": { "type": "string" }
+        },
+        "required": ["build_number"]
+    });
+
+    assert!(validate_structured_result(&schema, &json!({ "build_number": 4521 })).is_ok());
+
+    let errors = validate_structured_result(&schema, &json!({ "build_number": "4521" }))
+        .expect_err("string build number should be rejected");
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with("/build_number"));
+
+    assert!(validate_structured_result(&schema, &json!({ "branch": "main" })).is_err());
+}
+
+#[test]
+fn test_conversation_follow_up_prunes_images() {
+    use serde_json::json;
+    use std::time::Duration;
+    use superctrl::computer_use::{Conversation, ConversationStore};
+
+    let mut conversation = Conversation::new();
+    conversation.push_user_text("open Safari");
+    conversation.push("assistant", json!([{ "type": "text", "text": "Done" }]));
+    conversation.push(
+        "user",
+        json!([{
+            "type": "tool_result",
+            "tool_use_id": "toolu_1",
+            "content": [{ "type": "image", "source": { "type": "base64", "data": "AAAA" } }]
+        }]),
+    );
+    conversation.push_user_text("now open a new tab");
+
+    let messages = conversation.messages();
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[2].content.as_array().unwrap().len(), 2);
+
+    let mut store = ConversationStore::new(Duration::from_secs(60));
+    store.save(conversation);
+    let restored = store.recent().expect("conversation should still be fresh");
+    assert_eq!(
+        restored.messages()[2].content[0]["content"][0],
+        json!({ "type": "text", "text": "[screenshot omitted]" })
+    );
+
+    let mut expired = ConversationStore::new(Duration::ZERO);
+    expired.save(restored);
+    assert!(expired.recent().is_none());
+}
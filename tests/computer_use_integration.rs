@@ -8,7 +8,7 @@ use superctrl::{Action, MacAutomation, MouseButton, ScreenCapture};
 #[test]
 fn test_screenshot_capture() -> Result<()> {
     let capture = ScreenCapture::default();
-    let screenshot = capture.capture_screenshot()?;
+    let screenshot = capture.capture_screenshot()?.data;
 
     assert!(!screenshot.is_empty(), "Screenshot should not be empty");
     assert!(
@@ -87,3 +87,39 @@ fn test_mouse_button_types() {
         };
     }
 }
+
+#[test]
+fn test_simulated_automation_records_plan() -> Result<()> {
+    use superctrl::{AutomationBackend, SimulatedAutomation};
+
+    let mut simulated = SimulatedAutomation::new();
+
+    simulated.execute_action(Action::Keypress {
+        keys: vec!["meta".to_string(), "space".to_string()],
+    })?;
+    simulated.execute_action(Action::Type {
+        text: "Safari".to_string(),
+    })?;
+    simulated.execute_action(Action::Keypress {
+        keys: vec!["return".to_string()],
+    })?;
+    simulated.execute_action(Action::MouseMove { x: 400, y: 80 })?;
+    simulated.execute_action(Action::Click {
+        x: 412,
+        y: 88,
+        button: MouseButton::Left,
+    })?;
+
+    assert_eq!(
+        simulated.steps(),
+        &[
+            "press cmd+space",
+            "type 'Safari'",
+            "press return",
+            "move mouse to (400, 80)",
+            "click (412, 88)"
+        ]
+    );
+
+    Ok(())
+}
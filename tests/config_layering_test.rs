@@ -0,0 +1,182 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use superctrl::config::{expand_home, ConfigSources, Settings, DEFAULT_MODEL};
+use superctrl::hotkey::{HotkeyAction, ScreenCorner};
+
+fn temp_config(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("superctrl-config-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join("config.toml")
+}
+
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    move |name| vars.get(name).cloned()
+}
+
+fn sources(path: &Path, overrides: &[&str]) -> ConfigSources {
+    ConfigSources {
+        path: path.to_path_buf(),
+        overrides: overrides.iter().map(|o| o.to_string()).collect(),
+    }
+}
+
+#[test]
+fn test_precedence_defaults_file_env_cli() -> Result<()> {
+    let path = temp_config("precedence");
+    std::fs::write(
+        &path,
+        "model = \"from-file\"\nscreenshot_quality = 60\n\n[policy]\nconversation_timeout_secs = 120\n",
+    )?;
+
+    let file_only = Settings::load_layered(&sources(&path, &[]), env(&[]))?;
+    assert_eq!(file_only.model, "from-file");
+    assert_eq!(file_only.screenshot_quality, 60);
+    assert_eq!(file_only.policy.conversation_timeout_secs, 120);
+    assert!(file_only.learning_enabled);
+
+    let with_env = Settings::load_layered(
+        &sources(&path, &[]),
+        env(&[
+            ("SUPERCTRL_MODEL", "from-env"),
+            ("SUPERCTRL_LEARNING_ENABLED", "0"),
+        ]),
+    )?;
+    assert_eq!(with_env.model, "from-env");
+    assert_eq!(with_env.screenshot_quality, 60);
+    assert!(!with_env.learning_enabled);
+
+    let with_cli = Settings::load_layered(
+        &sources(
+            &path,
+            &["model=from-cli", "policy.conversation_timeout_secs=30"],
+        ),
+        env(&[("SUPERCTRL_MODEL", "from-env")]),
+    )?;
+    assert_eq!(with_cli.model, "from-cli");
+    assert_eq!(with_cli.policy.conversation_timeout_secs, 30);
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_existing_env_vars_still_apply() -> Result<()> {
+    let path = temp_config("legacy-env");
+    let settings = Settings::load_layered(
+        &sources(&path, &[]),
+        env(&[
+            ("SUPERCTRL_DISABLE_CLIPBOARD_MONITORING", "true"),
+            ("SUPERCTRL_PAUSE_HOTKEY", ""),
+            ("SUPERCTRL_FAILSAFE_CORNER", "bottom-left"),
+            ("SUPERCTRL_AUTO_PAUSE_ON_INPUT", "true"),
+            ("SUPERCTRL_ROUTINES_PATH", "/tmp/routines.json"),
+        ]),
+    )?;
+
+    assert!(!settings.clipboard_monitoring);
+    assert_eq!(settings.hotkeys.get(HotkeyAction::TogglePause), None);
+    assert_eq!(
+        settings.policy.failsafe_corner,
+        Some(ScreenCorner::BottomLeft)
+    );
+    assert!(settings.policy.auto_pause_on_input);
+    assert_eq!(settings.paths.routines, PathBuf::from("/tmp/routines.json"));
+    assert_eq!(settings.model, DEFAULT_MODEL);
+    Ok(())
+}
+
+#[test]
+fn test_invalid_env_value_names_the_variable() {
+    let path = temp_config("invalid-env");
+    let error = Settings::load_layered(
+        &sources(&path, &[]),
+        env(&[("SUPERCTRL_FAILSAFE_CORNER", "middle")]),
+    )
+    .unwrap_err();
+    assert!(format!("{:#}", error).contains("SUPERCTRL_FAILSAFE_CORNER"));
+}
+
+#[test]
+fn test_get_and_set_by_dotted_key() -> Result<()> {
+    let mut settings = Settings::default();
+    assert_eq!(
+        settings.get("policy.failsafe_corner")?.as_str(),
+        Some("off")
+    );
+    assert!(settings.get("hotkeys")?.is_table());
+    assert!(settings.get("policy.nonexistent").is_err());
+
+    settings.set("policy.failsafe_corner", "bottom-right")?;
+    settings.set("notifications.enabled", "no")?;
+    settings.set("screenshot_quality", "75")?;
+    assert_eq!(
+        settings.policy.failsafe_corner,
+        Some(ScreenCorner::BottomRight)
+    );
+    assert!(!settings.notifications.enabled);
+    assert_eq!(settings.screenshot_quality, 75);
+
+    assert!(settings.set("screenshot_quality", "high").is_err());
+    assert!(settings.set("screenshot_quality", "300").is_err());
+    assert!(settings.set("learning_enabled", "maybe").is_err());
+    assert!(settings.set("policy", "x").is_err());
+    assert!(settings.apply_overrides(&["model".to_string()]).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_set_in_file_writes_only_the_key() -> Result<()> {
+    let path = temp_config("set-in-file");
+    std::fs::write(&path, "model = \"claude-opus-4-1\"\n")?;
+
+    let settings = Settings::set_in_file(&path, "paths.routines", "~/dotfiles/routines.json")?;
+    assert_eq!(settings.model, "claude-opus-4-1");
+    assert_eq!(
+        settings.paths.routines,
+        PathBuf::from("~/dotfiles/routines.json")
+    );
+
+    let content = std::fs::read_to_string(&path)?;
+    assert!(content.contains("[paths]"));
+    assert!(!content.contains("api_url"));
+    assert_eq!(Settings::load(&path)?, settings);
+
+    let error = Settings::set_in_file(&path, "screenshot_quality", "0").unwrap_err();
+    assert!(format!("{:#}", error).contains("between 1 and 100"));
+    assert_eq!(Settings::load(&path)?, settings);
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_validation_rejects_empty_paths() {
+    let mut settings = Settings::default();
+    settings.policy.conversation_timeout_secs = 0;
+    settings.paths.history_db = PathBuf::new();
+
+    let errors = settings.validate().unwrap_err();
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(errors[0].contains("paths.history_db"));
+}
+
+#[test]
+fn test_expand_home() -> Result<()> {
+    let home = dirs::home_dir().unwrap();
+    assert_eq!(
+        expand_home(&PathBuf::from("~/.superctrl/history.db"))?,
+        home.join(".superctrl/history.db")
+    );
+    assert_eq!(
+        expand_home(&PathBuf::from("/var/superctrl.db"))?,
+        PathBuf::from("/var/superctrl.db")
+    );
+    Ok(())
+}
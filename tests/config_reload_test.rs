@@ -0,0 +1,126 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::{Local, TimeZone};
+use superctrl::config::{LiveSettings, Settings};
+use superctrl::hotkey::{HotkeyAction, ScreenCorner};
+use superctrl::reload::{changed_keys, ConfigWatcher, ReloadPlan, ReloadStatus};
+
+fn temp_config(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("superctrl-reload-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join("config.toml")
+}
+
+#[test]
+fn test_watcher_reports_writes_and_removal() -> Result<()> {
+    let path = temp_config("watcher");
+    let mut watcher = ConfigWatcher::new(path.clone());
+    assert!(!watcher.changed(), "missing file is the starting point");
+
+    std::fs::write(&path, "model = \"a\"\n")?;
+    assert!(watcher.changed());
+    assert!(!watcher.changed(), "each change is reported once");
+
+    std::fs::write(&path, "model = \"claude-opus-4-1\"\n")?;
+    assert!(watcher.changed());
+
+    std::fs::remove_file(&path)?;
+    assert!(watcher.changed());
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_changed_keys_lists_dotted_leaves() {
+    let old = Settings::default();
+    let mut new = old.clone();
+    new.model = "claude-opus-4-1".to_string();
+    new.hotkeys
+        .set(HotkeyAction::Stop, Some("ctrl+alt+s".to_string()));
+    new.policy.failsafe_corner = Some(ScreenCorner::TopRight);
+
+    assert_eq!(
+        changed_keys(&old, &new),
+        vec!["hotkeys.stop", "model", "policy.failsafe_corner"]
+    );
+    assert!(changed_keys(&old, &old).is_empty());
+}
+
+#[test]
+fn test_reload_plan_flags_settings_that_need_a_restart() {
+    let old = Settings::default();
+    let mut new = old.clone();
+    new.screenshot_quality = 70;
+    new.learning_enabled = false;
+    new.paths.routines = PathBuf::from("~/dotfiles/routines.json");
+
+    let plan = ReloadPlan::new(&old, &new);
+    assert_eq!(
+        plan.restart_required,
+        vec!["learning_enabled", "paths.routines"]
+    );
+    assert!(plan.changed.contains(&"screenshot_quality".to_string()));
+    assert!(!plan.hotkeys_changed());
+
+    new.hotkeys.set(HotkeyAction::QuickCommand, None);
+    assert!(ReloadPlan::new(&old, &new).hotkeys_changed());
+}
+
+#[test]
+fn test_reload_status_descriptions() {
+    let at = Local.with_ymd_and_hms(2026, 3, 1, 14, 5, 9).unwrap();
+    let mut new = Settings::default();
+    new.policy.failsafe_corner = Some(ScreenCorner::BottomRight);
+    new.clipboard_monitoring = false;
+
+    let applied = ReloadStatus::Applied {
+        at,
+        plan: ReloadPlan::new(&Settings::default(), &new),
+    };
+    assert_eq!(
+        applied.describe(),
+        "Config reloaded at 14:05:09 (changed: clipboard_monitoring, policy.failsafe_corner); \
+         restart superctrl to apply clipboard_monitoring"
+    );
+
+    let unchanged = ReloadStatus::Applied {
+        at,
+        plan: ReloadPlan::new(&new, &new),
+    };
+    assert_eq!(
+        unchanged.describe(),
+        "Config reloaded at 14:05:09 (no changes)"
+    );
+
+    let failed = ReloadStatus::Failed {
+        at,
+        error: "Invalid settings: Model must not be empty".to_string(),
+    };
+    assert!(failed
+        .describe()
+        .contains("Still using the previous settings"));
+}
+
+#[test]
+fn test_live_settings_swap_is_visible_to_every_clone() {
+    let live = LiveSettings::new(Settings::default());
+    let reader = live.clone();
+    let before = reader.get();
+
+    let previous = live.replace(Settings {
+        model: "claude-opus-4-1".to_string(),
+        ..Settings::default()
+    });
+
+    assert_eq!(previous.model, before.model);
+    assert_eq!(reader.get().model, "claude-opus-4-1");
+    assert_eq!(
+        before.model,
+        Settings::default().model,
+        "snapshots stay put"
+    );
+}
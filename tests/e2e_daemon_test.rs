@@ -5,6 +5,7 @@ use std::sync::{
 };
 use std::time::Duration;
 use superctrl::computer_use::ComputerUseAgent;
+use superctrl::task::CancellationToken;
 use tokio::time::timeout;
 
 #[tokio::test]
@@ -46,8 +47,7 @@ async fn test_real_api_call() -> Result<()> {
         }
     };
 
-    let stop_flag = Arc::new(AtomicBool::new(false));
-    let mut agent = ComputerUseAgent::new(api_key, stop_flag)?;
+    let mut agent = ComputerUseAgent::new(api_key, CancellationToken::new())?;
 
     let result = timeout(
         Duration::from_secs(30),
@@ -95,7 +95,7 @@ async fn test_screenshot_and_scale() -> Result<()> {
     use superctrl::{computer_use::calculate_scale_factor, ScreenCapture};
 
     let capture = ScreenCapture::default();
-    let screenshot = capture.capture_screenshot()?;
+    let screenshot = capture.capture_screenshot()?.data;
 
     assert!(!screenshot.is_empty());
     assert!(screenshot.starts_with("iVBOR") || screenshot.starts_with("/9j/"));
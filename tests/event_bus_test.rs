@@ -0,0 +1,172 @@
+use anyhow::Result;
+use superctrl::events::{AgentEvent, AgentEvents, DaemonEvent, EventBus, TimedEvent};
+use superctrl::gui::GuiState;
+use superctrl::history::TaskStatus;
+use superctrl::ipc::{DaemonHandler, IpcCommand, IpcServer};
+use superctrl::task::{ControlSource, TaskRunner};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+fn drain(receiver: &mut tokio::sync::broadcast::Receiver<TimedEvent>) -> Vec<DaemonEvent> {
+    let mut events = Vec::new();
+    while let Ok(event) = receiver.try_recv() {
+        events.push(event.event);
+    }
+    events
+}
+
+#[test]
+fn test_daemon_events_serialize_with_type_tag() {
+    let event = TimedEvent {
+        at: chrono::Local::now(),
+        event: DaemonEvent::Stop {
+            source: ControlSource::UserInput,
+            tasks: 1,
+        },
+    };
+
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["type"], "stop");
+    assert_eq!(json["source"], "user_input");
+    assert_eq!(json["tasks"], 1);
+    assert!(json["at"].is_string());
+
+    let parsed: TimedEvent = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed, event);
+
+    let learning = serde_json::to_value(DaemonEvent::LearningStarted).unwrap();
+    assert_eq!(learning, serde_json::json!({ "type": "learning_started" }));
+}
+
+#[test]
+fn test_agent_events_publish_task_lifecycle_to_bus() {
+    let bus = EventBus::new();
+    let mut receiver = bus.subscribe();
+    let events = AgentEvents::new().with_bus(bus);
+
+    events.emit(AgentEvent::TaskStarted {
+        command: "open Mail".to_string(),
+        max_steps: 50,
+    });
+    events.emit(AgentEvent::Step {
+        step: 1,
+        max_steps: 50,
+    });
+    events.emit(AgentEvent::AssistantText("Opening Mail".to_string()));
+    events.emit(AgentEvent::TaskFinished {
+        status: TaskStatus::Failed,
+        message: Some("Mail is not installed".to_string()),
+    });
+
+    assert_eq!(
+        drain(&mut receiver),
+        vec![
+            DaemonEvent::TaskStarted {
+                command: "open Mail".to_string(),
+                max_steps: 50,
+            },
+            DaemonEvent::TaskStep {
+                command: "open Mail".to_string(),
+                step: 1,
+                max_steps: 50,
+            },
+            DaemonEvent::TaskFailed {
+                command: "open Mail".to_string(),
+                error: "Mail is not installed".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_task_runner_publishes_control_events() {
+    let bus = EventBus::new();
+    let mut receiver = bus.subscribe();
+    let tasks = TaskRunner::new().with_events(bus);
+    let _task = tasks.begin();
+
+    tasks.pause(ControlSource::Hotkey);
+    tasks.resume(ControlSource::MenuBar);
+    tasks.stop(ControlSource::Ipc);
+
+    assert_eq!(
+        drain(&mut receiver),
+        vec![
+            DaemonEvent::Paused {
+                source: ControlSource::Hotkey,
+                tasks: 1,
+            },
+            DaemonEvent::Resumed {
+                source: ControlSource::MenuBar,
+                tasks: 1,
+            },
+            DaemonEvent::Stop {
+                source: ControlSource::Ipc,
+                tasks: 1,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_gui_state_shares_one_bus() {
+    let state = GuiState::default();
+    let mut receiver = state.event_bus.subscribe();
+
+    state.trigger_stop(ControlSource::MenuBar);
+    state.agent_events.emit(AgentEvent::TaskStarted {
+        command: "open Notes".to_string(),
+        max_steps: 10,
+    });
+
+    let events = drain(&mut receiver);
+    assert_eq!(events.len(), 2);
+    assert!(matches!(events[0], DaemonEvent::Stop { tasks: 0, .. }));
+    assert!(matches!(events[1], DaemonEvent::TaskStarted { .. }));
+}
+
+struct EventsHandler(EventBus);
+
+#[async_trait::async_trait]
+impl DaemonHandler for EventsHandler {
+    fn events(&self) -> tokio::sync::broadcast::Receiver<TimedEvent> {
+        self.0.subscribe()
+    }
+}
+
+#[tokio::test]
+async fn test_ipc_streams_events_as_json_lines() -> Result<()> {
+    let bus = EventBus::new();
+    let (mut client, server) = UnixStream::pair()?;
+    let handler = EventsHandler(bus.clone());
+    let handler = tokio::spawn(async move { IpcServer::handle_connection(server, &handler).await });
+
+    let request = serde_json::to_string(&IpcCommand::Events)? + "\n";
+    client.write_all(request.as_bytes()).await?;
+
+    let (reader, writer) = client.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let subscribed: serde_json::Value = serde_json::from_str(&lines.next_line().await?.unwrap())?;
+    assert_eq!(subscribed["success"], true);
+
+    bus.publish(DaemonEvent::LearningStarted);
+    bus.publish(DaemonEvent::ConfigReloadFailed {
+        error: "invalid TOML".to_string(),
+    });
+
+    let first: TimedEvent = serde_json::from_str(&lines.next_line().await?.unwrap())?;
+    let second: TimedEvent = serde_json::from_str(&lines.next_line().await?.unwrap())?;
+    assert_eq!(first.event, DaemonEvent::LearningStarted);
+    assert_eq!(
+        second.event,
+        DaemonEvent::ConfigReloadFailed {
+            error: "invalid TOML".to_string(),
+        }
+    );
+
+    drop(writer);
+    drop(lines);
+    handler.await??;
+
+    Ok(())
+}
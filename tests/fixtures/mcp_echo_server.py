@@ -0,0 +1,84 @@
+#!/usr/bin/env python3
+"""Minimal stdio MCP server used by the integration tests."""
+
+import json
+import os
+import sys
+
+TOOLS = [
+    {
+        "name": "echo",
+        "description": "Echo the given text back",
+        "inputSchema": {
+            "type": "object",
+            "properties": {"text": {"type": "string"}},
+            "required": ["text"],
+        },
+    },
+    {
+        "name": "fail",
+        "description": "Always returns a tool error",
+        "inputSchema": {"type": "object", "properties": {}},
+    },
+]
+
+if os.environ.get("MCP_FIXTURE_COLLIDING_TOOLS"):
+    TOOLS += [
+        {"name": name, "description": "Return the tool name", "inputSchema": {"type": "object"}}
+        for name in ("a.b", "a_b")
+    ]
+
+
+def respond(message_id, result=None, error=None):
+    message = {"jsonrpc": "2.0", "id": message_id}
+    if error is not None:
+        message["error"] = error
+    else:
+        message["result"] = result
+    sys.stdout.write(json.dumps(message) + "\n")
+    sys.stdout.flush()
+
+
+for line in sys.stdin:
+    line = line.strip()
+    if not line:
+        continue
+
+    request = json.loads(line)
+    method = request.get("method")
+    message_id = request.get("id")
+
+    if message_id is None:
+        continue
+
+    if method == "initialize":
+        respond(
+            message_id,
+            {
+                "protocolVersion": "2024-11-05",
+                "capabilities": {"tools": {}},
+                "serverInfo": {"name": "echo-fixture", "version": "0.1.0"},
+            },
+        )
+    elif method == "tools/list":
+        respond(message_id, {"tools": TOOLS})
+    elif method == "tools/call":
+        params = request.get("params", {})
+        name = params.get("name")
+        arguments = params.get("arguments", {})
+        if name == "echo":
+            respond(
+                message_id,
+                {"content": [{"type": "text", "text": arguments.get("text", "")}]},
+            )
+        elif name == "fail":
+            respond(
+                message_id,
+                {"content": [{"type": "text", "text": "fixture failure"}], "isError": True},
+            )
+        elif any(tool["name"] == name for tool in TOOLS):
+            respond(message_id, {"content": [{"type": "text", "text": name}]})
+        else:
+            respond(message_id, error={"code": -32602, "message": "Unknown tool"})
+    else:
+        respond(message_id, error={"code": -32601, "message": "Method not found"})
@@ -0,0 +1,179 @@
+use anyhow::Result;
+use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+use global_hotkey::{GlobalHotKeyEvent, HotKeyState};
+use superctrl::hotkey::{
+    format_hotkey, parse_hotkey, swap_bindings, HotkeyAction, HotkeyBindings, HotkeyConfig,
+    HotkeyRegistry,
+};
+use superctrl::task::TaskRunner;
+
+#[test]
+fn test_parse_hotkey_specs() -> Result<()> {
+    assert_eq!(
+        parse_hotkey("cmd+shift+esc")?,
+        HotKey::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::Escape)
+    );
+    assert_eq!(
+        parse_hotkey("Ctrl + Opt + Return")?,
+        HotKey::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::Enter)
+    );
+    assert_eq!(
+        parse_hotkey("command+shift+f9")?,
+        HotKey::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::F9)
+    );
+
+    assert!(parse_hotkey("cmd+shift+nope").is_err());
+    assert!(parse_hotkey("cmd++p").is_err());
+    assert!(
+        parse_hotkey("p").is_err(),
+        "a hotkey without modifiers would swallow normal typing"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_format_hotkey_uses_mac_symbols() -> Result<()> {
+    assert_eq!(format_hotkey(&parse_hotkey("cmd+shift+esc")?), "⇧⌘⎋");
+    assert_eq!(format_hotkey(&parse_hotkey("ctrl+cmd+p")?), "⌃⌘P");
+    assert_eq!(format_hotkey(&parse_hotkey("alt+cmd+1")?), "⌥⌘1");
+    Ok(())
+}
+
+#[test]
+fn test_default_bindings() -> Result<()> {
+    let bindings = HotkeyBindings::from_config(&HotkeyConfig::default())?;
+
+    assert_eq!(
+        bindings.hotkey(HotkeyAction::Stop),
+        Some(parse_hotkey("cmd+shift+esc")?)
+    );
+    assert!(bindings.hotkey(HotkeyAction::TogglePause).is_some());
+    assert!(bindings.hotkey(HotkeyAction::RepeatLast).is_some());
+    assert!(bindings.hotkey(HotkeyAction::QuickCommand).is_some());
+    assert_eq!(bindings.iter().count(), HotkeyAction::ALL.len());
+
+    Ok(())
+}
+
+#[test]
+fn test_conflicting_bindings_are_rejected() {
+    let config = HotkeyConfig {
+        repeat_last: Some("Shift+Command+Escape".to_string()),
+        ..HotkeyConfig::default()
+    };
+
+    let error = HotkeyBindings::from_config(&config).unwrap_err();
+    let message = format!("{:#}", error);
+    assert!(message.contains("stop"), "{}", message);
+    assert!(message.contains("repeat-last"), "{}", message);
+}
+
+#[test]
+fn test_invalid_binding_names_the_action() {
+    let config = HotkeyConfig {
+        quick_command: Some("cmd+banana".to_string()),
+        ..HotkeyConfig::default()
+    };
+
+    let error = HotkeyBindings::from_config(&config).unwrap_err();
+    assert!(format!("{:#}", error).contains("quick-command"));
+}
+
+#[test]
+fn test_unbound_actions_are_skipped() -> Result<()> {
+    let config = HotkeyConfig {
+        stop: Some("cmd+shift+esc".to_string()),
+        pause: None,
+        repeat_last: None,
+        quick_command: Some("alt+cmd+space".to_string()),
+    };
+
+    let bindings = HotkeyBindings::from_config(&config)?;
+    assert_eq!(bindings.hotkey(HotkeyAction::TogglePause), None);
+    assert_eq!(
+        bindings.hotkey(HotkeyAction::QuickCommand),
+        Some(parse_hotkey("alt+cmd+space")?)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_app_actions_do_not_touch_running_task() -> Result<()> {
+    let bindings = HotkeyBindings::from_config(&HotkeyConfig::default())?;
+    let tasks = TaskRunner::new();
+    let task = tasks.begin();
+
+    for action in [HotkeyAction::RepeatLast, HotkeyAction::QuickCommand] {
+        let event = GlobalHotKeyEvent {
+            id: bindings.hotkey(action).unwrap().id(),
+            state: HotKeyState::Pressed,
+        };
+        assert_eq!(
+            HotkeyRegistry::handle_event(&bindings, &event, &tasks),
+            Some(action)
+        );
+    }
+
+    assert!(!task.token().is_cancelled());
+    assert!(!task.token().is_paused());
+
+    Ok(())
+}
+
+#[test]
+fn test_failed_reload_keeps_previous_bindings() -> Result<()> {
+    let current = HotkeyBindings::from_config(&HotkeyConfig::default())?;
+    let taken = parse_hotkey("alt+cmd+space")?;
+    let config = HotkeyConfig {
+        quick_command: Some("alt+cmd+space".to_string()),
+        pause: Some("ctrl+cmd+j".to_string()),
+        ..HotkeyConfig::default()
+    };
+    let requested = HotkeyBindings::from_config(&config)?;
+
+    let mut registered = Vec::new();
+    let mut unregistered = Vec::new();
+    let error = swap_bindings(
+        &current,
+        requested.clone(),
+        |hotkey| {
+            if hotkey == taken {
+                anyhow::bail!("already registered");
+            }
+            registered.push(hotkey);
+            Ok(())
+        },
+        |hotkey| unregistered.push(hotkey),
+    )
+    .unwrap_err();
+
+    let message = format!("{:#}", error);
+    assert!(message.contains("quick-command"), "{}", message);
+    assert!(!message.contains("pause"), "{}", message);
+    assert_eq!(registered, vec![parse_hotkey("ctrl+cmd+j")?]);
+    assert_eq!(
+        unregistered, registered,
+        "only the newly registered hotkeys are rolled back"
+    );
+
+    let mut unregistered = Vec::new();
+    let applied = swap_bindings(
+        &current,
+        requested.clone(),
+        |_| Ok(()),
+        |hotkey| unregistered.push(hotkey),
+    )?;
+    assert_eq!(applied, requested);
+    assert_eq!(
+        unregistered,
+        vec![
+            current.hotkey(HotkeyAction::TogglePause).unwrap(),
+            current.hotkey(HotkeyAction::QuickCommand).unwrap(),
+        ],
+        "replaced bindings are released after the new ones are registered"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,334 @@
+use anyhow::Result;
+use futures_util::{SinkExt, StreamExt};
+use hyper::header::{self, HeaderMap, HeaderValue};
+use hyper::{Body, Method, Request, StatusCode};
+use std::sync::Arc;
+use superctrl::events::{DaemonEvent, EventBus, TimedEvent};
+use superctrl::gui::GuiState;
+use superctrl::http_api::{is_authorized, route, HttpApi};
+use superctrl::ipc::{DaemonHandler, IpcCommand};
+use superctrl::status::{DaemonStatus, LearningStatus};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+
+const TOKEN: &str = "test-token-0123456789";
+
+struct TestDaemon(EventBus);
+
+#[async_trait::async_trait]
+impl DaemonHandler for TestDaemon {
+    async fn status(&self) -> Result<DaemonStatus> {
+        Ok(DaemonStatus::collect(
+            &GuiState::default(),
+            LearningStatus::default(),
+        ))
+    }
+
+    fn events(&self) -> tokio::sync::broadcast::Receiver<TimedEvent> {
+        self.0.subscribe()
+    }
+
+    async fn pause(&self) -> Result<()> {
+        anyhow::bail!("No task is running")
+    }
+}
+
+fn test_api() -> HttpApi {
+    HttpApi::new(TOKEN.to_string(), Arc::new(TestDaemon(EventBus::new())))
+}
+
+fn request(method: Method, path: &str, token: Option<&str>, body: &str) -> Request<Body> {
+    let mut builder = Request::builder().method(method).uri(path);
+    if let Some(token) = token {
+        builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    builder.body(Body::from(body.to_string())).unwrap()
+}
+
+async fn json_body(response: hyper::Response<Body>) -> Result<serde_json::Value> {
+    let bytes = hyper::body::to_bytes(response.into_body()).await?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+#[test]
+fn test_routes_map_to_ipc_commands() {
+    assert!(matches!(
+        route(&Method::GET, "/v1/status", b""),
+        Ok(IpcCommand::Status)
+    ));
+    assert!(matches!(
+        route(&Method::POST, "/v1/learn/start/", b""),
+        Ok(IpcCommand::LearnStart)
+    ));
+    match route(
+        &Method::POST,
+        "/v1/execute",
+        br#"{"command": "open Safari", "output_schema": {"type": "object"}}"#,
+    ) {
+        Ok(IpcCommand::Execute {
+            command,
+            output_schema,
+            wait: false,
+        }) => {
+            assert_eq!(command, "open Safari");
+            assert_eq!(output_schema, Some(serde_json::json!({"type": "object"})));
+        }
+        other => panic!("unexpected route: {:?}", other),
+    }
+    assert!(matches!(
+        route(&Method::POST, "/v1/ask", br#"{"question": "what is open?"}"#),
+        Ok(IpcCommand::Ask { question }) if question == "what is open?"
+    ));
+
+    let errors = [
+        (
+            Method::GET,
+            "/v1/execute",
+            "",
+            StatusCode::METHOD_NOT_ALLOWED,
+        ),
+        (
+            Method::POST,
+            "/v1/status",
+            "",
+            StatusCode::METHOD_NOT_ALLOWED,
+        ),
+        (Method::GET, "/v1/unknown", "", StatusCode::NOT_FOUND),
+        (Method::POST, "/v1/plan", "{}", StatusCode::BAD_REQUEST),
+        (
+            Method::POST,
+            "/v1/continue",
+            "not json",
+            StatusCode::BAD_REQUEST,
+        ),
+    ];
+    for (method, path, body, status) in errors {
+        let error = route(&method, path, body.as_bytes()).unwrap_err();
+        assert_eq!(error.0, status, "{} {}", method, path);
+    }
+}
+
+#[test]
+fn test_token_from_header_or_query() {
+    let mut headers = HeaderMap::new();
+    assert!(!is_authorized(&headers, None, TOKEN));
+    assert!(is_authorized(
+        &headers,
+        Some(&format!("a=1&token={}", TOKEN)),
+        TOKEN
+    ));
+    assert!(!is_authorized(&headers, Some("token=wrong"), TOKEN));
+
+    headers.insert(
+        header::AUTHORIZATION,
+        HeaderValue::from_str(&format!("Bearer {}", TOKEN)).unwrap(),
+    );
+    assert!(is_authorized(&headers, None, TOKEN));
+
+    headers.insert(
+        header::AUTHORIZATION,
+        HeaderValue::from_static("Bearer test-token-0123456780"),
+    );
+    assert!(!is_authorized(&headers, None, TOKEN));
+}
+
+#[tokio::test]
+async fn test_requests_require_the_token_and_share_the_dispatcher() -> Result<()> {
+    let api = test_api();
+
+    let response = api
+        .handle(request(Method::GET, "/v1/status", None, ""))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = api
+        .handle(request(
+            Method::GET,
+            "/v1/status?token=nope",
+            Some("nope"),
+            "",
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = api
+        .handle(request(Method::GET, "/v1/status", Some(TOKEN), ""))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!response
+        .headers()
+        .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    let body = json_body(response).await?;
+    assert_eq!(body["success"], true);
+    assert_eq!(body["message"], "Daemon is running (Idle)");
+
+    let response = api
+        .handle(request(Method::POST, "/v1/pause", Some(TOKEN), ""))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(json_body(response).await?["success"], false);
+
+    let response = api
+        .handle(request(Method::POST, "/v1/ask", Some(TOKEN), "{}"))
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = api
+        .handle(request(Method::OPTIONS, "/v1/execute", None, ""))
+        .await;
+    assert_eq!(
+        response.status(),
+        StatusCode::UNAUTHORIZED,
+        "preflights from unlisted origins are not answered"
+    );
+
+    Ok(())
+}
+
+fn from_origin(method: Method, origin: &str, token: Option<&str>) -> Request<Body> {
+    let mut request = request(method, "/v1/status", token, "");
+    request
+        .headers_mut()
+        .insert(header::ORIGIN, HeaderValue::from_str(origin).unwrap());
+    request
+}
+
+#[tokio::test]
+async fn test_cors_is_limited_to_allowed_origins() -> Result<()> {
+    let api = test_api().with_allowed_origins(vec!["http://localhost:3000/".to_string()]);
+
+    let response = api
+        .handle(from_origin(Method::OPTIONS, "http://localhost:3000", None))
+        .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let headers = response.headers();
+    assert_eq!(
+        headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        "http://localhost:3000"
+    );
+    assert_eq!(
+        headers[header::ACCESS_CONTROL_ALLOW_HEADERS],
+        "authorization, content-type"
+    );
+
+    let response = api
+        .handle(from_origin(Method::GET, "http://localhost:3000", None))
+        .await;
+    assert_eq!(
+        response.status(),
+        StatusCode::UNAUTHORIZED,
+        "an allowed origin still needs the token"
+    );
+
+    let response = api
+        .handle(from_origin(
+            Method::GET,
+            "http://localhost:3000",
+            Some(TOKEN),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        "http://localhost:3000"
+    );
+
+    let response = api
+        .handle(from_origin(Method::OPTIONS, "https://evil.example", None))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = api
+        .handle(from_origin(
+            Method::GET,
+            "https://evil.example",
+            Some(TOKEN),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!response
+        .headers()
+        .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_websocket_streams_daemon_events() -> Result<()> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let address = listener.local_addr()?;
+    let bus = EventBus::new();
+    let api = HttpApi::new(TOKEN.to_string(), Arc::new(TestDaemon(bus.clone())));
+    let server = tokio::spawn(api.serve(listener));
+
+    let url = format!("ws://{}/v1/events?token={}", address, TOKEN);
+    let (mut socket, response) =
+        tokio_tungstenite::client_async(url, TcpStream::connect(address).await?).await?;
+    assert_eq!(response.status(), 101);
+
+    socket.send(Message::Ping(b"ping".to_vec())).await?;
+    assert_eq!(
+        socket.next().await.unwrap()?,
+        Message::Pong(b"ping".to_vec())
+    );
+
+    bus.publish(DaemonEvent::TaskCompleted {
+        command: "open Safari".to_string(),
+    });
+    let Message::Text(text) = socket.next().await.unwrap()? else {
+        panic!("expected a text message");
+    };
+    let event: TimedEvent = serde_json::from_str(&text)?;
+    assert_eq!(
+        event.event,
+        DaemonEvent::TaskCompleted {
+            command: "open Safari".to_string(),
+        }
+    );
+
+    socket.close(None).await?;
+    server.abort();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_websocket_rejects_unmasked_client_frames() -> Result<()> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let address = listener.local_addr()?;
+    let bus = EventBus::new();
+    let api = HttpApi::new(TOKEN.to_string(), Arc::new(TestDaemon(bus.clone())));
+    let server = tokio::spawn(api.serve(listener));
+
+    let mut stream = TcpStream::connect(address).await?;
+    let handshake = format!(
+        "GET /v1/events?token={} HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\n\
+         Connection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+         Sec-WebSocket-Version: 13\r\n\r\n",
+        TOKEN, address
+    );
+    stream.write_all(handshake.as_bytes()).await?;
+
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        head.push(stream.read_u8().await?);
+    }
+    let head = String::from_utf8(head)?;
+    assert!(head.starts_with("HTTP/1.1 101"), "{}", head);
+    assert!(head.contains("s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+
+    stream.write_all(&[0x89, 0x04]).await?;
+    stream.write_all(b"ping").await?;
+    let mut rest = Vec::new();
+    let _ = tokio::time::timeout(
+        std::time::Duration::from_secs(2),
+        stream.read_to_end(&mut rest),
+    )
+    .await?;
+    assert!(
+        !rest.starts_with(&[0x8A]),
+        "an unmasked ping must not be answered"
+    );
+
+    server.abort();
+    Ok(())
+}
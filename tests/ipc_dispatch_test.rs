@@ -0,0 +1,220 @@
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use superctrl::ipc::{DaemonHandler, IpcCommand, IpcServer, TaskOutput};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+
+#[derive(Default)]
+struct RecordingHandler {
+    calls: Mutex<Vec<String>>,
+}
+
+impl RecordingHandler {
+    fn record(&self, call: impl Into<String>) {
+        self.calls.lock().unwrap().push(call.into());
+    }
+
+    fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl DaemonHandler for RecordingHandler {
+    async fn execute(
+        &self,
+        command: String,
+        output_schema: Option<serde_json::Value>,
+        wait: bool,
+    ) -> Result<Option<TaskOutput>> {
+        self.record(format!("execute {}", command));
+        if !wait && output_schema.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(TaskOutput {
+            text: "Read the title".to_string(),
+            result: output_schema.map(|_| serde_json::json!({ "done": true })),
+        }))
+    }
+
+    async fn ask(&self, question: String) -> Result<String> {
+        self.record(format!("ask {}", question));
+        Ok("Safari is open".to_string())
+    }
+
+    async fn pause(&self) -> Result<()> {
+        self.record("pause");
+        anyhow::bail!("No task is running")
+    }
+
+    async fn learn_status(&self) -> Result<String> {
+        self.record("learn_status");
+        Ok("Learning is stopped".to_string())
+    }
+}
+
+async fn send_raw(handler: Arc<RecordingHandler>, request: &[u8]) -> Result<serde_json::Value> {
+    let (mut client, server) = UnixStream::pair()?;
+    let connection =
+        tokio::spawn(async move { IpcServer::handle_connection(server, handler.as_ref()).await });
+
+    for chunk in request.chunks(1000) {
+        client.write_all(chunk).await?;
+        client.flush().await?;
+        tokio::task::yield_now().await;
+    }
+
+    let mut response = String::new();
+    client.read_to_string(&mut response).await?;
+    connection.await??;
+    Ok(serde_json::from_str(&response)?)
+}
+
+#[tokio::test]
+async fn test_dispatch_routes_each_command_to_its_handler_method() {
+    let handler = RecordingHandler::default();
+
+    let response = IpcServer::dispatch(
+        &handler,
+        IpcCommand::Execute {
+            command: "open Safari".to_string(),
+            output_schema: None,
+            wait: false,
+        },
+    )
+    .await;
+    assert!(response.success);
+    assert_eq!(response.message, "Command execution started");
+    assert_eq!(response.data, None);
+
+    let response = IpcServer::dispatch(
+        &handler,
+        IpcCommand::Execute {
+            command: "read the title".to_string(),
+            output_schema: None,
+            wait: true,
+        },
+    )
+    .await;
+    assert_eq!(response.message, "Read the title");
+    assert_eq!(response.data, None);
+
+    let response = IpcServer::dispatch(
+        &handler,
+        IpcCommand::Execute {
+            command: "read the title".to_string(),
+            output_schema: Some(serde_json::json!({ "type": "object" })),
+            wait: false,
+        },
+    )
+    .await;
+    assert_eq!(response.message, "Read the title");
+    assert_eq!(response.data, Some(serde_json::json!({ "done": true })));
+
+    let response = IpcServer::dispatch(
+        &handler,
+        IpcCommand::Ask {
+            question: "what is open?".to_string(),
+        },
+    )
+    .await;
+    assert_eq!(response.message, "Safari is open");
+
+    let response = IpcServer::dispatch(&handler, IpcCommand::Pause).await;
+    assert!(!response.success);
+    assert_eq!(response.message, "Failed to pause: No task is running");
+
+    let response = IpcServer::dispatch(&handler, IpcCommand::LearnStatus).await;
+    assert_eq!(response.message, "Learning is stopped");
+
+    assert_eq!(
+        handler.calls(),
+        vec![
+            "execute open Safari",
+            "execute read the title",
+            "execute read the title",
+            "ask what is open?",
+            "pause",
+            "learn_status",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_unimplemented_commands_report_unsupported() {
+    let handler = RecordingHandler::default();
+
+    let response = IpcServer::dispatch(&handler, IpcCommand::LearnClear).await;
+    assert!(!response.success);
+    assert_eq!(
+        response.message,
+        "Failed to clear learning: LearnClear is not supported by this daemon"
+    );
+
+    let response = IpcServer::dispatch(&handler, IpcCommand::Status).await;
+    assert!(!response.success);
+    assert!(response.message.starts_with("Failed to get status"));
+
+    let response = IpcServer::dispatch(&handler, IpcCommand::Events).await;
+    assert_eq!(response.message, "Events are only available as a stream");
+
+    assert!(handler.calls().is_empty());
+}
+
+#[tokio::test]
+async fn test_connection_rejects_invalid_commands() -> Result<()> {
+    let handler = RecordingHandler::default();
+    let (mut client, server) = UnixStream::pair()?;
+    let connection =
+        tokio::spawn(async move { IpcServer::handle_connection(server, &handler).await });
+
+    client.write_all(b"{\"Reboot\":null}").await?;
+    client.shutdown().await?;
+
+    let mut response = String::new();
+    client.read_to_string(&mut response).await?;
+    connection.await??;
+
+    let response: serde_json::Value = serde_json::from_str(&response)?;
+    assert_eq!(response["success"], false);
+    assert!(response["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Invalid command"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_large_requests_are_read_up_to_the_newline() -> Result<()> {
+    let handler = Arc::new(RecordingHandler::default());
+    let properties: serde_json::Map<String, serde_json::Value> = (0..200)
+        .map(|i| {
+            (
+                format!("field_{}", i),
+                serde_json::json!({ "type": "string", "description": "x".repeat(20) }),
+            )
+        })
+        .collect();
+    let command = IpcCommand::Execute {
+        command: "fill in the form ".repeat(50),
+        output_schema: Some(serde_json::json!({ "type": "object", "properties": properties })),
+        wait: false,
+    };
+    let request = serde_json::to_string(&command)? + "\n";
+    assert!(request.len() > 4096);
+
+    let response = send_raw(handler.clone(), request.as_bytes()).await?;
+    assert_eq!(response["success"], true, "{}", response);
+    assert_eq!(response["data"], serde_json::json!({ "done": true }));
+    assert_eq!(handler.calls().len(), 1);
+
+    let mut request = b"{\"Ask\":{\"question\":\"".to_vec();
+    request.resize(1024 * 1024 + 1, b'x');
+    let response = send_raw(handler.clone(), &request).await?;
+    assert_eq!(response["success"], false);
+    assert_eq!(response["message"], "Request is larger than 1 MiB");
+    assert_eq!(handler.calls().len(), 1);
+
+    Ok(())
+}
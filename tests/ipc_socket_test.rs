@@ -0,0 +1,100 @@
+use anyhow::Result;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use superctrl::ipc::{self, IpcServer};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+fn temp_socket_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("superctrl-ipc-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn mode(path: &std::path::Path) -> u32 {
+    std::fs::metadata(path).unwrap().permissions().mode() & 0o777
+}
+
+#[test]
+fn test_socket_path_is_per_user() {
+    let path = ipc::socket_path();
+    assert_eq!(path.file_name().unwrap(), "superctrl.sock");
+    assert_ne!(path, PathBuf::from("/tmp/superctrl.sock"));
+}
+
+#[tokio::test]
+async fn test_bind_creates_private_directory_and_socket() -> Result<()> {
+    let dir = temp_socket_dir("private");
+    let socket_path = dir.join("superctrl.sock");
+
+    let server = IpcServer::bind(&socket_path).await?;
+    assert_eq!(mode(&dir), 0o700);
+    assert_eq!(mode(&socket_path), 0o600);
+
+    drop(server);
+    assert!(!socket_path.exists());
+
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755))?;
+    let error = IpcServer::bind(&socket_path).await.err().unwrap();
+    assert!(format!("{:#}", error).contains("accessible by other users"));
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bind_replaces_stale_socket_but_not_live_one() -> Result<()> {
+    let dir = temp_socket_dir("stale");
+    std::fs::create_dir_all(&dir)?;
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700))?;
+    let socket_path = dir.join("superctrl.sock");
+
+    let live = std::os::unix::net::UnixListener::bind(&socket_path)?;
+    let error = IpcServer::bind(&socket_path).await.err().unwrap();
+    assert!(format!("{:#}", error).contains("already listening"));
+    assert!(socket_path.exists());
+
+    drop(live);
+    assert!(socket_path.exists());
+    let server = IpcServer::bind(&socket_path).await?;
+    drop(server);
+
+    std::fs::write(&socket_path, "not a socket")?;
+    let error = IpcServer::bind(&socket_path).await.err().unwrap();
+    assert!(format!("{:#}", error).contains("is not a socket"));
+    assert_eq!(std::fs::read_to_string(&socket_path)?, "not a socket");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_same_user_peers_are_accepted() -> Result<()> {
+    let dir = temp_socket_dir("peer");
+    let socket_path = dir.join("superctrl.sock");
+    let server = IpcServer::bind(&socket_path).await?;
+
+    let client = tokio::spawn({
+        let socket_path = socket_path.clone();
+        async move {
+            let mut stream = ipc::connect(&socket_path).await?;
+            stream.write_all(b"ping").await?;
+            let mut reply = String::new();
+            stream.read_to_string(&mut reply).await?;
+            anyhow::Ok(reply)
+        }
+    });
+
+    let mut stream = server.accept_connection().await?;
+    ipc::verify_peer(&stream)?;
+    let mut request = [0u8; 4];
+    stream.read_exact(&mut request).await?;
+    assert_eq!(&request, b"ping");
+    stream.write_all(b"pong").await?;
+    drop(stream);
+
+    assert_eq!(client.await??, "pong");
+
+    drop(server);
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
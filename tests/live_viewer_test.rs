@@ -0,0 +1,166 @@
+use std::io::Cursor;
+use std::sync::Arc;
+
+use image::{ImageFormat, Rgba, RgbaImage};
+use superctrl::events::{AgentEvent, AgentEvents, LiveView};
+use superctrl::history::TaskStatus;
+use superctrl::viewer::mark_click;
+
+fn started(command: &str) -> AgentEvent {
+    AgentEvent::TaskStarted {
+        command: command.to_string(),
+        max_steps: 50,
+    }
+}
+
+fn click(x: i32, y: i32) -> AgentEvent {
+    AgentEvent::ToolUse {
+        tool: "computer".to_string(),
+        action: Some("left_click".to_string()),
+        point: Some((x, y)),
+    }
+}
+
+fn blank_png(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    RgbaImage::from_pixel(width, height, Rgba([255, 255, 255, 255]))
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .unwrap();
+    bytes
+}
+
+#[test]
+fn test_live_view_follows_a_task() {
+    let mut view = LiveView::default();
+    assert!(!view.is_running());
+
+    view.apply(&started("open Safari"));
+    view.apply(&AgentEvent::Step {
+        step: 1,
+        max_steps: 50,
+    });
+    view.apply(&AgentEvent::AssistantText(
+        "Taking a screenshot.".to_string(),
+    ));
+    view.apply(&click(120, 80));
+    view.apply(&AgentEvent::Screenshot(Arc::new(vec![1, 2, 3])));
+    view.apply(&AgentEvent::Step {
+        step: 2,
+        max_steps: 50,
+    });
+    view.apply(&AgentEvent::AssistantText("Safari is open.".to_string()));
+
+    assert!(view.is_running());
+    assert_eq!(view.command.as_deref(), Some("open Safari"));
+    assert_eq!((view.step, view.max_steps), (2, 50));
+    assert_eq!(view.transcript, "Taking a screenshot.\n\nSafari is open.");
+    assert_eq!(view.last_action.as_deref(), Some("computer: left_click"));
+    assert_eq!(view.last_click, Some((120, 80)));
+    assert_eq!(view.screenshot.as_deref(), Some(&vec![1, 2, 3]));
+
+    view.apply(&AgentEvent::TaskFinished {
+        status: TaskStatus::Stopped,
+        message: Some("Execution stopped by user".to_string()),
+    });
+    assert!(!view.is_running());
+    assert_eq!(view.status, Some(TaskStatus::Stopped));
+    assert_eq!(view.outcome.as_deref(), Some("Execution stopped by user"));
+}
+
+#[test]
+fn test_live_view_keeps_last_click_across_other_actions() {
+    let mut view = LiveView::default();
+    view.apply(&started("fill in the form"));
+    view.apply(&click(10, 20));
+    view.apply(&AgentEvent::ToolUse {
+        tool: "computer".to_string(),
+        action: Some("type".to_string()),
+        point: None,
+    });
+
+    assert_eq!(view.last_action.as_deref(), Some("computer: type"));
+    assert_eq!(view.last_click, Some((10, 20)));
+}
+
+#[test]
+fn test_new_task_resets_the_view() {
+    let mut view = LiveView::default();
+    view.apply(&started("first"));
+    view.apply(&click(10, 20));
+    view.apply(&AgentEvent::AssistantText("Done.".to_string()));
+    view.apply(&AgentEvent::TaskFinished {
+        status: TaskStatus::Completed,
+        message: None,
+    });
+
+    view.apply(&started("second"));
+    assert_eq!(view.command.as_deref(), Some("second"));
+    assert_eq!(view.status, Some(TaskStatus::Running));
+    assert_eq!(view.step, 0);
+    assert!(view.transcript.is_empty());
+    assert_eq!(view.last_click, None);
+    assert_eq!(view.screenshot, None);
+}
+
+#[test]
+fn test_agent_events_broadcast_and_track_current_view() {
+    let events = AgentEvents::new();
+    let mut first = events.subscribe();
+    let mut second = events.clone().subscribe();
+
+    events.emit(started("open Mail"));
+    events.emit(AgentEvent::Step {
+        step: 1,
+        max_steps: 50,
+    });
+
+    for receiver in [&mut first, &mut second] {
+        assert_eq!(receiver.try_recv().unwrap(), started("open Mail"));
+        assert!(matches!(
+            receiver.try_recv().unwrap(),
+            AgentEvent::Step { step: 1, .. }
+        ));
+    }
+
+    let current = events.current();
+    assert_eq!(current.command.as_deref(), Some("open Mail"));
+    assert_eq!(current.step, 1);
+}
+
+#[test]
+fn test_emit_without_subscribers_still_updates_current_view() {
+    let events = AgentEvents::new();
+    events.emit(started("open Notes"));
+    assert!(events.current().is_running());
+}
+
+#[test]
+fn test_mark_click_draws_marker_at_point() {
+    let frame = mark_click(&blank_png(100, 80), Some((50, 40))).unwrap();
+    let white = Rgba([255, 255, 255, 255]);
+
+    assert_eq!((frame.width(), frame.height()), (100, 80));
+    assert_ne!(*frame.get_pixel(50, 40), white, "centre dot");
+    assert_ne!(*frame.get_pixel(64, 40), white, "ring");
+    assert_eq!(*frame.get_pixel(57, 40), white, "inside the ring");
+    assert_eq!(*frame.get_pixel(5, 5), white, "far from the click");
+}
+
+#[test]
+fn test_mark_click_handles_missing_and_offscreen_points() {
+    let white = Rgba([255, 255, 255, 255]);
+
+    let unmarked = mark_click(&blank_png(20, 20), None).unwrap();
+    assert!(unmarked.pixels().all(|pixel| *pixel == white));
+
+    let offscreen = mark_click(&blank_png(20, 20), Some((500, 500))).unwrap();
+    assert!(offscreen.pixels().all(|pixel| *pixel == white));
+
+    let edge = mark_click(&blank_png(20, 20), Some((0, 0))).unwrap();
+    assert_ne!(*edge.get_pixel(0, 0), white);
+}
+
+#[test]
+fn test_mark_click_rejects_invalid_screenshot() {
+    assert!(mark_click(b"not an image", Some((1, 1))).is_err());
+}
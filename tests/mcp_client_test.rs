@@ -0,0 +1,100 @@
+use anyhow::Result;
+use serde_json::{json, Value};
+use superctrl::mcp::{qualified_tool_name, McpClient, McpManager, McpServerConfig};
+
+fn fixture_config() -> McpServerConfig {
+    McpServerConfig {
+        command: "python3".to_string(),
+        args: vec![format!(
+            "{}/tests/fixtures/mcp_echo_server.py",
+            env!("CARGO_MANIFEST_DIR")
+        )],
+        env: Default::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_mcp_client_lists_tools() -> Result<()> {
+    let client = McpClient::connect("fixture", &fixture_config()).await?;
+
+    let names: Vec<&str> = client.tools().iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, vec!["echo", "fail"]);
+    assert_eq!(client.tools()[0].input_schema["required"][0], "text");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mcp_client_calls_tool() -> Result<()> {
+    let client = McpClient::connect("fixture", &fixture_config()).await?;
+
+    let result = client.call_tool("echo", json!({ "text": "hello" })).await?;
+    assert!(!result.is_error);
+
+    let content = result.to_anthropic_content();
+    assert_eq!(content[0]["type"], "text");
+    assert_eq!(content[0]["text"], "hello");
+
+    let failed = client.call_tool("fail", json!({})).await?;
+    assert!(failed.is_error);
+
+    assert!(client.call_tool("missing", json!({})).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mcp_manager_routes_qualified_names() -> Result<()> {
+    let mut manager = McpManager::default();
+    manager.add_client(McpClient::connect("docs server", &fixture_config()).await?);
+
+    let definitions = manager.tool_definitions();
+    assert_eq!(definitions.len(), 2);
+    assert_eq!(definitions[0]["name"], "docs_server__echo");
+    assert!(definitions[0]["input_schema"].is_object());
+
+    assert!(manager.handles("docs_server__echo"));
+    assert!(!manager.handles("echo"));
+
+    let result = manager
+        .call_tool("docs_server__echo", json!({ "text": "routed" }))
+        .await?;
+    assert_eq!(result.to_anthropic_content()[0]["text"], "routed");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mcp_manager_lists_one_definition_per_colliding_name() -> Result<()> {
+    let mut config = fixture_config();
+    config
+        .env
+        .insert("MCP_FIXTURE_COLLIDING_TOOLS".to_string(), "1".to_string());
+    let mut manager = McpManager::default();
+    manager.add_client(McpClient::connect("fixture", &config).await?);
+
+    let names: Vec<Value> = manager
+        .tool_definitions()
+        .into_iter()
+        .map(|definition| definition["name"].clone())
+        .collect();
+    assert_eq!(
+        names,
+        vec!["fixture__echo", "fixture__fail", "fixture__a_b"],
+        "a.b and a_b share a qualified name, so only the routed one is listed"
+    );
+
+    let result = manager.call_tool("fixture__a_b", json!({})).await?;
+    assert_eq!(result.to_anthropic_content()[0]["text"], "a.b");
+
+    Ok(())
+}
+
+#[test]
+fn test_qualified_tool_name_is_api_safe() {
+    assert_eq!(
+        qualified_tool_name("jira", "create.issue"),
+        "jira__create_issue"
+    );
+    assert!(qualified_tool_name(&"x".repeat(80), "tool").len() <= 64);
+}
@@ -0,0 +1,131 @@
+use anyhow::Result;
+use std::path::PathBuf;
+use superctrl::quick_command::{load_routines, suggestions, CommandHistory, Routine};
+
+fn routine(name: &str, command: &str) -> Routine {
+    Routine {
+        name: name.to_string(),
+        command: command.to_string(),
+    }
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "superctrl-quick-command-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_history_recall_walks_back_and_restores_draft() {
+    let mut history = CommandHistory::default();
+    history.push("open Safari");
+    history.push("check my email");
+
+    assert_eq!(
+        history.older("half typed").as_deref(),
+        Some("check my email")
+    );
+    assert_eq!(
+        history.older("check my email").as_deref(),
+        Some("open Safari")
+    );
+    assert_eq!(
+        history.older("open Safari").as_deref(),
+        Some("open Safari"),
+        "recall stops at the oldest entry"
+    );
+
+    assert_eq!(history.newer().as_deref(), Some("check my email"));
+    assert_eq!(history.newer().as_deref(), Some("half typed"));
+    assert_eq!(history.newer(), None);
+}
+
+#[test]
+fn test_history_moves_repeated_commands_to_the_end() {
+    let mut history = CommandHistory::default();
+    assert_eq!(history.older(""), None);
+
+    history.push("open Safari");
+    history.push("check my email");
+    history.push("  open Safari ");
+    history.push("   ");
+
+    assert_eq!(history.older("").as_deref(), Some("open Safari"));
+    assert_eq!(history.older("").as_deref(), Some("check my email"));
+    assert_eq!(history.older("").as_deref(), Some("check my email"));
+}
+
+#[test]
+fn test_history_persists_without_cursor() -> Result<()> {
+    let dir = temp_dir("history");
+    let path = dir.join("nested/command_history.json");
+
+    assert_eq!(CommandHistory::load(&path)?.older(""), None);
+
+    let mut history = CommandHistory::default();
+    history.push("open Safari");
+    history.older("");
+    history.save(&path)?;
+
+    let mut loaded = CommandHistory::load(&path)?;
+    assert_eq!(loaded.newer(), None, "the recall position is not saved");
+    assert_eq!(loaded.older("").as_deref(), Some("open Safari"));
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_routine_suggestions() {
+    let routines = vec![
+        routine("Inbox zero", "Archive every read email in Mail's inbox"),
+        routine("Standup notes", "Open Notes and create a standup note"),
+        routine("Morning", "Open Mail, Calendar and Slack"),
+    ];
+
+    let names = |input: &str| {
+        suggestions(&routines, input)
+            .into_iter()
+            .map(|routine| routine.name.as_str())
+            .collect::<Vec<_>>()
+    };
+
+    assert!(names("").is_empty());
+    assert_eq!(names("stand"), vec!["Standup notes"]);
+    assert_eq!(
+        names("open"),
+        vec!["Standup notes", "Morning"],
+        "matches on the command text too"
+    );
+    assert_eq!(
+        names("MAIL"),
+        vec!["Inbox zero", "Morning"],
+        "matching is case-insensitive"
+    );
+    assert_eq!(names("Open Mail, Calendar and Slack"), Vec::<&str>::new());
+}
+
+#[test]
+fn test_load_routines() -> Result<()> {
+    let dir = temp_dir("routines");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("routines.json");
+
+    assert!(load_routines(&path)?.is_empty());
+
+    std::fs::write(
+        &path,
+        r#"{"routines": [{"name": "Morning", "command": "Open Mail"}]}"#,
+    )?;
+    assert_eq!(load_routines(&path)?, vec![routine("Morning", "Open Mail")]);
+
+    std::fs::write(&path, "not json")?;
+    assert!(load_routines(&path).is_err());
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,170 @@
+use anyhow::Result;
+use image::{Rgba, RgbaImage};
+use superctrl::config::{RedactionSettings, ScreenRegion, Settings};
+use superctrl::screenshot::{
+    apply_redaction, denied_reason, detect_text_regions, RedactionReport, WindowInfo,
+};
+
+const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+fn window(app_name: &str, title: &str) -> WindowInfo {
+    WindowInfo {
+        app_name: app_name.to_string(),
+        title: title.to_string(),
+    }
+}
+
+fn text_like(image: &mut RgbaImage, x: u32, y: u32, width: u32) {
+    for py in y + 4..y + 12 {
+        for px in (x..x + width).step_by(4) {
+            image.put_pixel(px, py, BLACK);
+        }
+    }
+}
+
+#[test]
+fn test_denylisted_app_or_title_blanks_the_frame() {
+    let settings = RedactionSettings {
+        denylist_apps: vec!["1Password 7".to_string(), "Slack".to_string()],
+        denylist_titles: vec!["Direct Message".to_string()],
+        ..RedactionSettings::default()
+    };
+
+    assert_eq!(
+        denied_reason(&settings, &window("slack", "general")).as_deref(),
+        Some("slack is frontmost")
+    );
+    let reason = denied_reason(&settings, &window("Discord", "direct message - alex")).unwrap();
+    assert!(reason.contains("denylisted title"));
+    assert!(!reason.contains("alex"), "the title itself is not repeated");
+    assert_eq!(denied_reason(&settings, &window("Safari", "Docs")), None);
+    assert_eq!(
+        denied_reason(&RedactionSettings::default(), &window("Slack", "")),
+        None
+    );
+}
+
+#[test]
+fn test_regions_are_scaled_and_clipped() {
+    let mut image = RgbaImage::from_pixel(200, 100, WHITE);
+    let settings = RedactionSettings {
+        regions: vec![
+            ScreenRegion {
+                x: 10,
+                y: 10,
+                width: 20,
+                height: 5,
+            },
+            ScreenRegion {
+                x: 90,
+                y: 40,
+                width: 50,
+                height: 50,
+            },
+            ScreenRegion {
+                x: 500,
+                y: 0,
+                width: 10,
+                height: 10,
+            },
+            ScreenRegion {
+                x: u32::MAX - 5,
+                y: u32::MAX - 5,
+                width: 10,
+                height: 10,
+            },
+        ],
+        ..RedactionSettings::default()
+    };
+
+    let report = apply_redaction(&mut image, &settings, 2.0);
+    assert_eq!(report.regions, 2, "off-screen regions are skipped");
+    assert_eq!(*image.get_pixel(20, 20), BLACK);
+    assert_eq!(*image.get_pixel(59, 29), BLACK);
+    assert_eq!(*image.get_pixel(60, 30), WHITE);
+    assert_eq!(*image.get_pixel(199, 99), BLACK);
+    assert_eq!(*image.get_pixel(179, 79), WHITE);
+}
+
+#[test]
+fn test_blur_text_only_touches_text_like_areas() {
+    let mut image = RgbaImage::from_pixel(128, 64, WHITE);
+    text_like(&mut image, 16, 16, 64);
+    let before = image.clone();
+
+    assert!(detect_text_regions(&RgbaImage::from_pixel(64, 64, WHITE)).is_empty());
+    let settings = RedactionSettings {
+        blur_text: true,
+        ..RedactionSettings::default()
+    };
+    let report = apply_redaction(&mut image, &settings, 1.0);
+
+    assert_eq!(report.text_regions, 1);
+    assert_eq!(detect_text_regions(&before), vec![(16, 16, 64, 16)]);
+    assert_ne!(*image.get_pixel(16, 20), BLACK, "text is blurred");
+    assert_eq!(*image.get_pixel(100, 50), WHITE, "background is untouched");
+}
+
+#[test]
+fn test_redaction_report_tells_the_model() {
+    assert_eq!(RedactionReport::default().describe(), None);
+
+    let partial = RedactionReport {
+        regions: 2,
+        text_regions: 3,
+        ..RedactionReport::default()
+    };
+    let note = partial.describe().unwrap();
+    assert!(note.contains("2 configured screen region(s) are blacked out"));
+    assert!(note.contains("3 area(s) that looked like text are blurred"));
+
+    let blanked = RedactionReport {
+        blanked: Some("Slack is frontmost".to_string()),
+        regions: 1,
+        ..RedactionReport::default()
+    };
+    let note = blanked.describe().unwrap();
+    assert!(note.contains("blanked because Slack is frontmost"));
+    assert!(!note.contains("region"));
+}
+
+#[test]
+fn test_redaction_settings_from_config() -> Result<()> {
+    let mut settings: Settings = toml::from_str(
+        "[redaction]\ndenylist_apps = [\"1Password 7\"]\nregions = [{ x = 0, y = 0, width = 300, height = 40 }]\n",
+    )?;
+    assert_eq!(settings.redaction.denylist_apps, vec!["1Password 7"]);
+    assert_eq!(settings.redaction.regions[0].width, 300);
+    assert!(!settings.redaction.blur_text);
+
+    settings.set(
+        "redaction.denylist_titles",
+        "[\"Direct Message\", \"Bank\"]",
+    )?;
+    settings.set("redaction.blur_text", "on")?;
+    assert_eq!(
+        settings.redaction.denylist_titles,
+        vec!["Direct Message", "Bank"]
+    );
+    assert!(settings.redaction.blur_text);
+    assert!(settings.set("redaction.denylist_apps", "Slack").is_err());
+
+    settings.set("redaction.denylist_apps", "[\" \"]")?;
+    settings.set(
+        "redaction.regions",
+        "[{ x = 0, y = 0, width = 0, height = 10 }]",
+    )?;
+    let errors = settings.validate().unwrap_err();
+    assert_eq!(errors.len(), 2, "{:?}", errors);
+
+    settings.set("redaction.denylist_apps", "[]")?;
+    settings.set(
+        "redaction.regions",
+        "[{ x = 4294967290, y = 0, width = 10, height = 10 }, { x = 0, y = 20000, width = 10, height = 10 }]",
+    )?;
+    let errors = settings.validate().unwrap_err();
+    assert_eq!(errors.len(), 2, "{:?}", errors);
+    assert!(errors[0].contains("redaction.regions[0] must lie within"));
+    Ok(())
+}
@@ -0,0 +1,282 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use superctrl::config::{SecretsSettings, Settings};
+use superctrl::redact::{mask, redact, register_secret};
+use superctrl::secrets::{
+    http_token, open_store, placeholder, placeholder_names, substitute_placeholders,
+    typeable_secret_names, FileStore, SecretStore, SecretsBackend, API_KEY_SECRET,
+    HTTP_TOKEN_SECRET,
+};
+
+fn temp_store(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("superctrl-secrets-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir.join("secrets.json")
+}
+
+#[test]
+fn test_file_store_round_trip_without_plaintext_on_disk() -> Result<()> {
+    let path = temp_store("round-trip");
+    let store = FileStore::new(path.clone());
+    assert_eq!(store.get(API_KEY_SECRET)?, None);
+    assert!(store.names()?.is_empty());
+
+    store.set(API_KEY_SECRET, "sk-ant-REDACTED")?;
+    store.set("staging-password", "hunter2")?;
+
+    assert_eq!(
+        store.get(API_KEY_SECRET)?.as_deref(),
+        Some("sk-ant-REDACTED")
+    );
+    assert_eq!(
+        FileStore::new(path.clone())
+            .get("staging-password")?
+            .as_deref(),
+        Some("hunter2")
+    );
+    assert_eq!(store.names()?, vec![API_KEY_SECRET, "staging-password"]);
+
+    let on_disk = std::fs::read_to_string(&path)?;
+    assert!(!on_disk.contains("plaintext-check"));
+    assert!(!on_disk.contains("hunter2"));
+
+    store.delete("staging-password")?;
+    store.delete("never-stored")?;
+    assert_eq!(store.get("staging-password")?, None);
+    assert_eq!(store.names()?, vec![API_KEY_SECRET]);
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_file_store_files_are_private() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = temp_store("permissions");
+    FileStore::new(path.clone()).set(API_KEY_SECRET, "sk-ant-private")?;
+
+    for file in [path.clone(), path.with_extension("key")] {
+        let mode = std::fs::metadata(&file)?.permissions().mode();
+        assert_eq!(mode & 0o777, 0o600, "{:?}", file);
+    }
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_file_store_rejects_a_different_key() -> Result<()> {
+    let path = temp_store("wrong-key");
+    let store = FileStore::new(path.clone());
+    store.set(API_KEY_SECRET, "sk-ant-original")?;
+
+    let key_path = path.with_extension("key");
+    std::fs::remove_file(&key_path)?;
+    let error = store.get(API_KEY_SECRET).unwrap_err();
+    assert!(format!("{:#}", error).contains("is missing"));
+
+    let other = temp_store("wrong-key-other");
+    FileStore::new(other.clone()).set("unused", "value")?;
+    std::fs::copy(other.with_extension("key"), &key_path)?;
+    let error = store.get(API_KEY_SECRET).unwrap_err();
+    assert!(format!("{:#}", error).contains("Failed to decrypt"));
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    std::fs::remove_dir_all(other.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_passphrase_store_keeps_no_key_on_disk() -> Result<()> {
+    let path = temp_store("passphrase");
+    let store = FileStore::with_passphrase(path.clone(), "correct horse".to_string());
+    store.set(API_KEY_SECRET, "sk-ant-derived")?;
+
+    assert!(!path.with_extension("key").exists());
+    assert!(store.description().contains("derived from"));
+    assert_eq!(
+        FileStore::with_passphrase(path.clone(), "correct horse".to_string())
+            .get(API_KEY_SECRET)?
+            .as_deref(),
+        Some("sk-ant-derived")
+    );
+
+    let error = FileStore::with_passphrase(path.clone(), "wrong".to_string())
+        .get(API_KEY_SECRET)
+        .unwrap_err();
+    assert!(format!("{:#}", error).contains("Failed to decrypt"));
+    let error = FileStore::new(path.clone())
+        .get(API_KEY_SECRET)
+        .unwrap_err();
+    assert!(format!("{:#}", error).contains("SUPERCTRL_SECRETS_PASSPHRASE"));
+
+    let keyless = temp_store("keyless");
+    let store = FileStore::new(keyless.clone());
+    store.set(API_KEY_SECRET, "sk-ant-keyless")?;
+    assert!(store.description().contains("not encrypted at rest"));
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    std::fs::remove_dir_all(keyless.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_ciphertext_is_bound_to_the_secret_name() -> Result<()> {
+    let path = temp_store("swap");
+    let store = FileStore::new(path.clone());
+    store.set("a", "first")?;
+    store.set("b", "second")?;
+
+    let mut file: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+    let secrets = file["secrets"].as_object_mut().unwrap();
+    let a = secrets["a"].clone();
+    secrets.insert("b".to_string(), a);
+    std::fs::write(&path, serde_json::to_string(&file)?)?;
+
+    assert_eq!(store.get("a")?.as_deref(), Some("first"));
+    assert!(store.get("b").is_err());
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_open_store_uses_configured_file() -> Result<()> {
+    let path = temp_store("configured");
+    let settings = SecretsSettings {
+        backend: SecretsBackend::File,
+        file: path.clone(),
+    };
+
+    let store = open_store(&settings)?;
+    store.set(API_KEY_SECRET, "sk-ant-configured")?;
+    assert!(store.description().contains("secrets.json"));
+    assert_eq!(
+        FileStore::new(path.clone()).get(API_KEY_SECRET)?.as_deref(),
+        Some("sk-ant-configured")
+    );
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_secrets_backend_setting() -> Result<()> {
+    let mut settings = Settings::default();
+    assert_eq!(settings.secrets.backend, SecretsBackend::Auto);
+
+    settings.set("secrets.backend", "file")?;
+    assert_eq!(settings.secrets.backend, SecretsBackend::File);
+    assert!(settings.set("secrets.backend", "vault").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_placeholder_names() {
+    assert_eq!(
+        placeholder("staging-password"),
+        "{{secret:staging-password}}"
+    );
+    assert_eq!(
+        placeholder_names("{{secret:user}}\t{{secret:staging.pass_1}}"),
+        vec!["user", "staging.pass_1"]
+    );
+    assert_eq!(
+        placeholder_names("{{secret:}} {{secret:has space}} {{secret:a}"),
+        Vec::<&str>::new()
+    );
+    assert_eq!(
+        placeholder_names("{{secret:{{secret:ok}}"),
+        vec!["ok"],
+        "a broken token does not hide the next one"
+    );
+}
+
+#[test]
+fn test_substitute_placeholders_from_store() -> Result<()> {
+    let path = temp_store("substitute");
+    let store = FileStore::new(path.clone());
+    store.set(API_KEY_SECRET, "sk-ant-never-typed")?;
+    store.set("staging-user", "admin@example.com")?;
+    store.set("staging-password", "p@ss {{secret:staging-user}}")?;
+
+    assert_eq!(
+        substitute_placeholders(
+            "{{secret:staging-user}}\t{{secret:staging-password}}",
+            &store
+        )?,
+        "admin@example.com\tp@ss {{secret:staging-user}}"
+    );
+    assert_eq!(substitute_placeholders("plain text", &store)?, "plain text");
+    assert_eq!(
+        redact("typed admin@example.com"),
+        "typed [REDACTED]",
+        "substituted values are redacted from logs"
+    );
+
+    let error = substitute_placeholders("{{secret:missing}}", &store).unwrap_err();
+    assert!(error.to_string().contains("Unknown secret 'missing'"));
+    let error = substitute_placeholders("{{secret:anthropic-api-key}}", &store).unwrap_err();
+    assert!(error.to_string().contains("cannot be typed"));
+
+    assert_eq!(
+        typeable_secret_names(&store)?,
+        vec!["staging-password", "staging-user"]
+    );
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_http_token_is_generated_once_and_never_typed() -> Result<()> {
+    let path = temp_store("http-token");
+    let store = FileStore::new(path.clone());
+
+    let token = http_token(&store, false)?;
+    assert!(token.len() >= 40);
+    assert_eq!(
+        store.get(HTTP_TOKEN_SECRET)?.as_deref(),
+        Some(token.as_str())
+    );
+    assert_eq!(http_token(&store, false)?, token, "reused until rotated");
+
+    let rotated = http_token(&store, true)?;
+    assert_ne!(rotated, token);
+    assert_eq!(http_token(&store, false)?, rotated);
+
+    assert!(typeable_secret_names(&store)?.is_empty());
+    let error = substitute_placeholders("{{secret:http-api-token}}", &store).unwrap_err();
+    assert!(error.to_string().contains("cannot be typed"));
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_redact_registered_secrets_and_api_keys() {
+    register_secret("correct-horse-battery");
+    register_secret("ab");
+
+    assert_eq!(
+        redact("password=correct-horse-battery ok"),
+        "password=[REDACTED] ok"
+    );
+    assert_eq!(
+        redact("x-api-key: sk-ant-api03-AbC_12-xyz, next"),
+        "x-api-key: [REDACTED], next"
+    );
+    assert_eq!(redact("prefix sk-ant- alone"), "prefix sk-ant- alone");
+    assert_eq!(redact("ab stays"), "ab stays");
+}
+
+#[test]
+fn test_mask_shows_only_the_ends() {
+    assert_eq!(mask("sk-ant-REDACTED"), "sk-ant-…mnop");
+    assert_eq!(mask("ghp_1234567890abcdef"), "ghp…cdef");
+    assert_eq!(mask("short"), "*****");
+}
@@ -0,0 +1,161 @@
+use anyhow::Result;
+use std::path::PathBuf;
+use superctrl::config::{Settings, DEFAULT_API_URL, DEFAULT_MODEL, DEFAULT_SCREENSHOT_QUALITY};
+use superctrl::hotkey::{HotkeyAction, HotkeyConfig};
+
+fn temp_settings(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "superctrl-settings-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir.join("config.toml")
+}
+
+#[test]
+fn test_missing_file_loads_defaults() -> Result<()> {
+    let settings = Settings::load(&temp_settings("missing"))?;
+
+    assert_eq!(settings, Settings::default());
+    assert_eq!(settings.model, DEFAULT_MODEL);
+    assert_eq!(settings.api_url, DEFAULT_API_URL);
+    assert_eq!(settings.screenshot_quality, DEFAULT_SCREENSHOT_QUALITY);
+    assert!(settings.learning_enabled);
+    assert!(settings.clipboard_monitoring);
+    assert_eq!(settings.hotkeys, HotkeyConfig::default());
+    assert!(settings.notifications.enabled);
+    assert!(settings.validate().is_ok());
+    Ok(())
+}
+
+#[test]
+fn test_save_and_load_round_trip() -> Result<()> {
+    let path = temp_settings("round-trip");
+
+    let mut settings = Settings {
+        model: "claude-opus-4-1".to_string(),
+        api_url: "https://llm-proxy.internal.example/v1/messages".to_string(),
+        screenshot_quality: 75,
+        clipboard_monitoring: false,
+        ..Settings::default()
+    };
+    settings.hotkeys.set(HotkeyAction::RepeatLast, None);
+    settings
+        .hotkeys
+        .set(HotkeyAction::Stop, Some("ctrl+alt+s".to_string()));
+    settings.notifications.command_received = false;
+    settings.save(&path)?;
+
+    let loaded = Settings::load(&path)?;
+    assert_eq!(loaded, settings);
+    assert_eq!(loaded.hotkeys.get(HotkeyAction::RepeatLast), None);
+    assert_eq!(loaded.hotkeys.get(HotkeyAction::Stop), Some("ctrl+alt+s"));
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_partial_file_keeps_defaults_for_missing_keys() -> Result<()> {
+    let path = temp_settings("partial");
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    std::fs::write(
+        &path,
+        "screenshot_quality = 60\n\n[hotkeys]\npause = \"\"\n\n[notifications]\nenabled = false\n",
+    )?;
+
+    let settings = Settings::load(&path)?;
+    assert_eq!(settings.screenshot_quality, 60);
+    assert_eq!(settings.model, DEFAULT_MODEL);
+    assert_eq!(settings.hotkeys.get(HotkeyAction::TogglePause), None);
+    assert_eq!(
+        settings.hotkeys.get(HotkeyAction::Stop),
+        HotkeyConfig::default().get(HotkeyAction::Stop)
+    );
+    assert!(!settings.notifications.enabled);
+    assert!(settings.notifications.command_completed);
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_invalid_toml_is_reported() -> Result<()> {
+    let path = temp_settings("invalid-toml");
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    std::fs::write(&path, "screenshot_quality = \"high\"\n")?;
+
+    let error = Settings::load(&path).unwrap_err();
+    assert!(format!("{:#}", error).contains("Failed to parse settings"));
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_validation_reports_every_problem() {
+    let mut settings = Settings {
+        model: "  ".to_string(),
+        api_url: "ftp://example.com".to_string(),
+        screenshot_quality: 0,
+        ..Settings::default()
+    };
+    settings.hotkeys.set(
+        HotkeyAction::QuickCommand,
+        Some("cmd+shift+esc".to_string()),
+    );
+
+    let errors = settings.validate().unwrap_err();
+    assert_eq!(errors.len(), 4, "{:?}", errors);
+    assert!(errors[0].contains("Model"));
+    assert!(errors[1].contains("http or https"));
+    assert!(errors[2].contains("between 1 and 100"));
+    assert!(errors[3].contains("bound to both"));
+
+    settings.api_url = "not a url".to_string();
+    let errors = settings.validate().unwrap_err();
+    assert!(errors.iter().any(|error| error.contains("not a valid URL")));
+}
+
+#[test]
+fn test_allowed_origins_must_be_origins() {
+    let mut settings = Settings::default();
+    settings.http.allowed_origins = vec![
+        "http://localhost:3000".to_string(),
+        "https://app.example.com/".to_string(),
+    ];
+    assert!(settings.validate().is_ok());
+
+    settings.http.allowed_origins = vec![
+        "*".to_string(),
+        "https://app.example.com/path".to_string(),
+        "file:///tmp".to_string(),
+    ];
+    let errors = settings.validate().unwrap_err();
+    assert_eq!(errors.len(), 3, "{:?}", errors);
+    assert!(errors[0].contains("http.allowed_origins entry '*'"));
+}
+
+#[test]
+fn test_invalid_settings_are_not_saved() {
+    let path = temp_settings("invalid-save");
+    let mut settings = Settings::default();
+    settings
+        .hotkeys
+        .set(HotkeyAction::Stop, Some("esc".to_string()));
+
+    let error = settings.save(&path).unwrap_err();
+    assert!(format!("{:#}", error).contains("Invalid stop hotkey"));
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_blank_hotkey_means_unbound() {
+    let mut hotkeys = HotkeyConfig::default();
+    hotkeys.set(HotkeyAction::TogglePause, Some("   ".to_string()));
+    assert_eq!(hotkeys.pause, None);
+
+    hotkeys.pause = Some(String::new());
+    assert_eq!(hotkeys.get(HotkeyAction::TogglePause), None);
+}
@@ -0,0 +1,144 @@
+use anyhow::Result;
+use chrono::Local;
+use superctrl::events::AgentEvent;
+use superctrl::gui::{AppState, GuiState};
+use superctrl::hotkey::{HotkeyBindings, HotkeyConfig};
+use superctrl::learning::SessionStats;
+use superctrl::status::{
+    format_uptime, models_url, ApiHealth, ApiStatus, CurrentTask, DaemonStatus, LearningStatus,
+};
+
+fn learning() -> LearningStatus {
+    LearningStatus {
+        enabled: true,
+        active: true,
+        events: Some(SessionStats {
+            total_events: 10,
+            keypress_count: 7,
+            app_switch_count: 2,
+            clipboard_change_count: 1,
+            active_session_count: 1,
+        }),
+    }
+}
+
+#[test]
+fn test_status_reports_the_running_task() -> Result<()> {
+    let mut gui = GuiState {
+        hotkeys: HotkeyBindings::from_config(&HotkeyConfig::default())?,
+        ..GuiState::default()
+    };
+    gui.record_error("Command failed: boom".to_string());
+    gui.update_status(AppState::Working("open safari".to_string()));
+    let _task = gui.tasks.begin();
+    gui.agent_events.emit(AgentEvent::TaskStarted {
+        command: "open safari".to_string(),
+        max_steps: 50,
+    });
+    gui.agent_events.emit(AgentEvent::Step {
+        step: 3,
+        max_steps: 50,
+    });
+
+    let status = DaemonStatus::collect(&gui, learning());
+    assert_eq!(status.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(status.pid, std::process::id());
+    assert_eq!(status.state, "Working");
+    assert_eq!(
+        status.current_task,
+        Some(CurrentTask {
+            command: "open safari".to_string(),
+            step: 3,
+            max_steps: 50,
+        })
+    );
+    assert_eq!(status.active_tasks, 1);
+    assert_eq!(status.queued_tasks, 0);
+    assert!(status.hotkeys.registered);
+    assert_eq!(status.hotkeys.bindings[0].action, "stop");
+    assert_eq!(
+        status
+            .last_error
+            .as_ref()
+            .map(|error| error.message.as_str()),
+        Some("Command failed: boom")
+    );
+    assert_eq!(status.api, None);
+
+    let json = serde_json::to_value(&status)?;
+    assert_eq!(json["current_task"]["step"], 3);
+    assert_eq!(serde_json::from_value::<DaemonStatus>(json)?, status);
+    Ok(())
+}
+
+#[test]
+fn test_status_table() {
+    let gui = GuiState {
+        app_state: AppState::Working("open safari".to_string()),
+        hotkey_error: Some("Accessibility permission missing".to_string()),
+        api_status: Some(ApiStatus {
+            health: ApiHealth::Unreachable,
+            checked_at: Local::now(),
+            latency_ms: None,
+            http_status: None,
+            error: Some("operation timed out".to_string()),
+        }),
+        ..GuiState::default()
+    };
+
+    let table = DaemonStatus::collect(&gui, learning()).render_table();
+    assert!(table.starts_with("Daemon        superctrl "));
+    assert!(table.contains("\nState         Working\n"));
+    assert!(table.contains("\nCurrent task  open safari\n"));
+    assert!(table.contains("\nTasks         0 running, 0 queued\n"));
+    assert!(table.contains(
+        "\nLearning      active, 10 events (7 keypresses, 2 app switches, 1 clipboard changes)\n"
+    ));
+    assert!(table.contains("\nHotkeys       not registered: Accessibility permission missing\n"));
+    assert!(table.contains("\nLast error    none\n"));
+    assert!(table.contains("\nAPI           unreachable: operation timed out (checked "));
+
+    let gui = GuiState {
+        api_status: Some(ApiStatus {
+            health: ApiHealth::AuthFailed,
+            checked_at: Local::now(),
+            latency_ms: Some(120),
+            http_status: Some(401),
+            error: Some("invalid x-api-key".to_string()),
+        }),
+        ..GuiState::default()
+    };
+    let table = DaemonStatus::collect(&gui, learning()).render_table();
+    assert!(table
+        .contains("\nAPI           authentication failed (HTTP 401): invalid x-api-key (checked "));
+
+    let idle = DaemonStatus::collect(&GuiState::default(), LearningStatus::default());
+    let table = idle.render_table();
+    assert!(!table.contains("Current task"));
+    assert!(table.contains("\nLearning      disabled\n"));
+    assert!(table.contains("\nAPI           not checked yet\n"));
+}
+
+#[test]
+fn test_format_uptime() {
+    assert_eq!(format_uptime(5), "5s");
+    assert_eq!(format_uptime(125), "2m 5s");
+    assert_eq!(format_uptime(3 * 3_600 + 120), "3h 2m");
+    assert_eq!(format_uptime(90_000), "1d 1h 0m");
+}
+
+#[test]
+fn test_api_check_lists_models_next_to_the_messages_endpoint() {
+    assert_eq!(
+        models_url("https://api.anthropic.com/v1/messages"),
+        "https://api.anthropic.com/v1/models"
+    );
+    assert_eq!(
+        models_url("http://localhost:8080/v1/messages/"),
+        "http://localhost:8080/v1/models"
+    );
+    assert_eq!(
+        models_url("https://proxy.example.com/anthropic"),
+        "https://proxy.example.com/anthropic/models"
+    );
+}
@@ -0,0 +1,360 @@
+use anyhow::Result;
+use global_hotkey::{GlobalHotKeyEvent, HotKeyState};
+use std::time::{Duration, Instant};
+use superctrl::computer_use::ComputerUseAgent;
+use superctrl::gui::GuiState;
+use superctrl::hotkey::{
+    CornerFailsafe, HotkeyAction, HotkeyBindings, HotkeyConfig, HotkeyRegistry, ScreenCorner,
+};
+use superctrl::input::SyntheticInput;
+use superctrl::ipc::{DaemonHandler, IpcCommand, IpcServer};
+use superctrl::task::{is_cancelled_error, ControlSource, TaskCancelled, TaskRunner};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+
+#[test]
+fn test_stop_cancels_running_task_only() {
+    let tasks = TaskRunner::new();
+    let first = tasks.begin();
+
+    assert_eq!(tasks.stop(ControlSource::Ipc), 1);
+    assert!(first.token().is_cancelled());
+    drop(first);
+
+    let second = tasks.begin();
+    assert!(
+        !second.token().is_cancelled(),
+        "a stop must not leak into the next task"
+    );
+}
+
+#[test]
+fn test_tasks_queue_behind_the_running_task() {
+    let tasks = TaskRunner::new();
+    let first = tasks.begin();
+    let turn = first.wait_turn();
+
+    let waiting = tasks.clone();
+    let second = std::thread::spawn(move || {
+        let task = waiting.begin();
+        let _turn = task.wait_turn();
+        task.token().is_cancelled()
+    });
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while tasks.queued_count() == 0 {
+        assert!(Instant::now() < deadline, "the second task never queued");
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(tasks.active_count(), 2);
+
+    assert_eq!(tasks.stop(ControlSource::Ipc), 2);
+    drop(turn);
+    drop(first);
+    assert!(
+        second.join().unwrap(),
+        "a queued task sees the stop once it gets its turn"
+    );
+    assert_eq!(tasks.queued_count(), 0);
+    assert_eq!(tasks.active_count(), 0);
+}
+
+#[test]
+fn test_stop_without_running_task_is_noop() {
+    let tasks = TaskRunner::new();
+    assert_eq!(tasks.stop(ControlSource::Hotkey), 0);
+
+    let task = tasks.begin();
+    assert!(!task.token().is_cancelled());
+}
+
+#[test]
+fn test_hotkey_stops_running_task() -> Result<()> {
+    let tasks = TaskRunner::new();
+    let task = tasks.begin();
+    let bindings = HotkeyBindings::from_config(&HotkeyConfig::default())?;
+    let stop_id = bindings.hotkey(HotkeyAction::Stop).unwrap().id();
+
+    let released = GlobalHotKeyEvent {
+        id: stop_id,
+        state: HotKeyState::Released,
+    };
+    assert_eq!(
+        HotkeyRegistry::handle_event(&bindings, &released, &tasks),
+        None
+    );
+
+    let other = GlobalHotKeyEvent {
+        id: stop_id + 1,
+        state: HotKeyState::Pressed,
+    };
+    assert_eq!(
+        HotkeyRegistry::handle_event(&bindings, &other, &tasks),
+        None
+    );
+    assert!(!task.token().is_cancelled());
+
+    let pressed = GlobalHotKeyEvent {
+        id: stop_id,
+        state: HotKeyState::Pressed,
+    };
+    assert_eq!(
+        HotkeyRegistry::handle_event(&bindings, &pressed, &tasks),
+        Some(HotkeyAction::Stop)
+    );
+    assert!(task.token().is_cancelled());
+
+    Ok(())
+}
+
+#[test]
+fn test_pause_hotkey_toggles_pause() -> Result<()> {
+    let tasks = TaskRunner::new();
+    let task = tasks.begin();
+    let bindings = HotkeyBindings::from_config(&HotkeyConfig::default())?;
+    let pressed = GlobalHotKeyEvent {
+        id: bindings.hotkey(HotkeyAction::TogglePause).unwrap().id(),
+        state: HotKeyState::Pressed,
+    };
+
+    assert_eq!(
+        HotkeyRegistry::handle_event(&bindings, &pressed, &tasks),
+        Some(HotkeyAction::TogglePause)
+    );
+    assert!(task.token().is_paused());
+    assert!(!task.token().is_cancelled());
+
+    HotkeyRegistry::handle_event(&bindings, &pressed, &tasks);
+    assert!(!task.token().is_paused());
+
+    Ok(())
+}
+
+#[test]
+fn test_screen_corner_parsing_and_bounds() -> Result<()> {
+    let display = (1920, 1080);
+
+    assert_eq!("top-left".parse::<ScreenCorner>()?, ScreenCorner::TopLeft);
+    assert_eq!(
+        "Bottom_Right".parse::<ScreenCorner>()?,
+        ScreenCorner::BottomRight
+    );
+    assert!("middle".parse::<ScreenCorner>().is_err());
+
+    assert!(ScreenCorner::TopLeft.contains((0, 0), display));
+    assert!(!ScreenCorner::TopLeft.contains((40, 0), display));
+    assert!(ScreenCorner::BottomRight.contains((1919, 1079), display));
+    assert!(!ScreenCorner::TopRight.contains((1919, 1079), display));
+
+    Ok(())
+}
+
+#[test]
+fn test_failsafe_corner_stops_running_task() {
+    let tasks = TaskRunner::new();
+    let synthetic = SyntheticInput::new();
+    let mut failsafe = CornerFailsafe::new(ScreenCorner::TopLeft);
+    let display = (1920, 1080);
+    let now = Instant::now();
+
+    assert!(
+        !failsafe.check((0, 0), display, &tasks, &synthetic, now),
+        "nothing to abort without a running task"
+    );
+
+    let task = tasks.begin();
+    assert!(!failsafe.check((800, 600), display, &tasks, &synthetic, now));
+    assert!(!task.token().is_cancelled());
+
+    assert!(failsafe.check((0, 0), display, &tasks, &synthetic, now));
+    assert!(task.token().is_cancelled());
+    assert!(
+        !failsafe.check((1, 1), display, &tasks, &synthetic, now),
+        "staying in the corner must not stop again"
+    );
+}
+
+#[test]
+fn test_failsafe_ignores_agent_moving_into_corner() {
+    let tasks = TaskRunner::new();
+    let task = tasks.begin();
+    let synthetic = SyntheticInput::new();
+    let mut failsafe = CornerFailsafe::new(ScreenCorner::TopLeft);
+    let now = Instant::now();
+
+    let display = (1920, 1080);
+    let mut poll = |cursor, after_ms| {
+        failsafe.check(
+            cursor,
+            display,
+            &tasks,
+            &synthetic,
+            now + Duration::from_millis(after_ms),
+        )
+    };
+
+    synthetic.record_at(Some((0, 0)), now);
+    assert!(!poll((800, 600), 5), "the injected move has not landed yet");
+    assert!(!poll((0, 0), 10));
+    assert!(
+        !poll((0, 0), 5_000),
+        "an idle pointer the agent left in the corner is not a user move"
+    );
+    assert!(!task.token().is_cancelled());
+
+    assert!(!poll((800, 600), 6_000));
+    assert!(
+        poll((0, 0), 7_000),
+        "the user moving back into the corner stops the task"
+    );
+    assert!(task.token().is_cancelled());
+}
+
+#[test]
+fn test_menu_bar_stops_running_task() {
+    let state = GuiState::new();
+    let task = state.task_runner().begin();
+
+    assert_eq!(state.trigger_stop(ControlSource::MenuBar), 1);
+    assert!(task.token().is_cancelled());
+    drop(task);
+
+    let next = state.task_runner().begin();
+    assert!(!next.token().is_cancelled());
+}
+
+struct StopHandler(TaskRunner);
+
+#[async_trait::async_trait]
+impl DaemonHandler for StopHandler {
+    async fn stop(&self) -> Result<()> {
+        self.0.stop(ControlSource::Ipc);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_ipc_and_cli_stop_running_task() -> Result<()> {
+    let tasks = TaskRunner::new();
+    let task = tasks.begin();
+
+    let (mut client, server) = UnixStream::pair()?;
+    let handler = StopHandler(tasks.clone());
+    let handler = tokio::spawn(async move { IpcServer::handle_connection(server, &handler).await });
+
+    let request = serde_json::to_string(&IpcCommand::Stop)?;
+    client.write_all(request.as_bytes()).await?;
+    client.shutdown().await?;
+
+    let mut response = String::new();
+    client.read_to_string(&mut response).await?;
+    handler.await??;
+
+    let response: serde_json::Value = serde_json::from_str(&response)?;
+    assert_eq!(response["success"], true);
+    assert!(task.token().is_cancelled());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cancelled_agent_reports_task_cancelled() -> Result<()> {
+    let tasks = TaskRunner::new();
+    let task = tasks.begin();
+    tasks.stop(ControlSource::Hotkey);
+
+    let mut agent = ComputerUseAgent::new("test-key".to_string(), task.token())?;
+    let error = agent
+        .execute_command("open Safari")
+        .await
+        .expect_err("a cancelled task must not run");
+    assert!(is_cancelled_error(&error));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stop_interrupts_in_flight_wait() {
+    let tasks = TaskRunner::new();
+    let task = tasks.begin();
+    let token = task.token();
+
+    let stopper = tasks.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        stopper.stop(ControlSource::Hotkey);
+    });
+
+    let started = Instant::now();
+    let result = token.sleep(Duration::from_secs(10)).await;
+    assert_eq!(result, Err(TaskCancelled));
+    assert!(
+        started.elapsed() < Duration::from_millis(150),
+        "stop took {:?}",
+        started.elapsed()
+    );
+}
+
+#[tokio::test]
+async fn test_stop_interrupts_pending_request() {
+    let tasks = TaskRunner::new();
+    let task = tasks.begin();
+    let token = task.token();
+
+    let stopper = tasks.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        stopper.stop(ControlSource::Ipc);
+    });
+
+    let started = Instant::now();
+    let result = token
+        .run_until_cancelled(std::future::pending::<()>())
+        .await;
+    assert_eq!(result, Err(TaskCancelled));
+    assert!(started.elapsed() < Duration::from_millis(150));
+
+    assert_eq!(
+        token.run_until_cancelled(async { 1 }).await,
+        Err(TaskCancelled),
+        "a cancelled token must not start new work"
+    );
+}
+
+#[tokio::test]
+async fn test_paused_task_holds_until_resumed() {
+    let tasks = TaskRunner::new();
+    let task = tasks.begin();
+    let token = task.token();
+
+    assert_eq!(token.wait_if_paused().await, Ok(false));
+
+    assert_eq!(tasks.pause(ControlSource::Ipc), 1);
+    assert!(tasks.is_paused());
+
+    let resumer = tasks.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        resumer.resume(ControlSource::Ipc);
+    });
+
+    let started = Instant::now();
+    assert_eq!(token.wait_if_paused().await, Ok(true));
+    assert!(started.elapsed() >= Duration::from_millis(40));
+    assert!(!tasks.is_paused());
+}
+
+#[tokio::test]
+async fn test_stop_releases_paused_task() {
+    let tasks = TaskRunner::new();
+    let task = tasks.begin();
+    let token = task.token();
+    tasks.pause(ControlSource::MenuBar);
+
+    let stopper = tasks.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        stopper.stop(ControlSource::Hotkey);
+    });
+
+    assert_eq!(token.wait_if_paused().await, Err(TaskCancelled));
+}
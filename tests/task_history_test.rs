@@ -0,0 +1,154 @@
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use superctrl::history::{screenshot_from_result, TaskHistory, TaskRecorder, TaskStatus};
+
+fn temp_db(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("superctrl-history-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir.join("history.db")
+}
+
+#[test]
+fn test_task_lifecycle_is_persisted() -> Result<()> {
+    let path = temp_db("lifecycle");
+
+    let task_id = {
+        let mut history = TaskHistory::new(path.clone())?;
+        let task_id = history.start_task("open Safari", "act")?;
+        history.add_usage(task_id, 1200, 80)?;
+        history.add_usage(task_id, 1500, 40)?;
+        history.finish_task(task_id, TaskStatus::Completed, Some("Safari is open"), None)?;
+        task_id
+    };
+
+    let history = TaskHistory::new(path.clone())?;
+    let task = history.task(task_id)?.expect("task should be stored");
+    assert_eq!(task.command, "open Safari");
+    assert_eq!(task.mode, "act");
+    assert_eq!(task.status, TaskStatus::Completed);
+    assert_eq!(task.input_tokens, 2700);
+    assert_eq!(task.output_tokens, 120);
+    assert_eq!(task.total_tokens(), 2820);
+    assert_eq!(task.result.as_deref(), Some("Safari is open"));
+    assert!(task.finished_at.is_some());
+    assert!(task.duration() >= chrono::Duration::zero());
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_recorder_stores_steps_with_screenshots_and_errors() -> Result<()> {
+    let path = temp_db("steps");
+    let history = Arc::new(Mutex::new(TaskHistory::new(path.clone())?));
+    let task_id = history
+        .lock()
+        .unwrap()
+        .start_task("click the button", "act")?;
+    let recorder = TaskRecorder::new(history.clone(), task_id);
+
+    let jpeg = vec![0xFF, 0xD8, 0xFF, 0xE0, 1, 2, 3];
+    let click = json!({ "action": "left_click", "coordinate": [10, 20] });
+    recorder.record_step(
+        "computer",
+        &click,
+        &json!([{
+            "type": "image",
+            "source": { "type": "base64", "media_type": "image/jpeg", "data": STANDARD.encode(&jpeg) }
+        }]),
+        None,
+    );
+    recorder.record_step(
+        "linear_create_issue",
+        &json!({ "title": "Bug" }),
+        &json!([{ "type": "text", "text": "Error calling tool: timeout" }]),
+        Some("Error calling tool: timeout"),
+    );
+    recorder.record_usage(10, 5);
+    recorder.finish(TaskStatus::Failed, None, Some("Maximum iterations reached"));
+
+    let history = history.lock().unwrap();
+    let steps = history.steps(task_id)?;
+    assert_eq!(steps.len(), 2);
+
+    assert_eq!(steps[0].index, 1);
+    assert_eq!(steps[0].tool, "computer");
+    assert_eq!(steps[0].input, click);
+    assert_eq!(steps[0].screenshot.as_deref(), Some(jpeg.as_slice()));
+    assert_eq!(steps[0].error, None);
+
+    assert_eq!(steps[1].index, 2);
+    assert_eq!(steps[1].screenshot, None);
+    assert_eq!(
+        steps[1].error.as_deref(),
+        Some("Error calling tool: timeout")
+    );
+
+    let task = history.task(task_id)?.unwrap();
+    assert_eq!(task.status, TaskStatus::Failed);
+    assert_eq!(task.total_tokens(), 15);
+    assert_eq!(task.error.as_deref(), Some("Maximum iterations reached"));
+
+    drop(history);
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_running_tasks_are_closed_on_restart() -> Result<()> {
+    let path = temp_db("restart");
+
+    let task_id = TaskHistory::new(path.clone())?.start_task("open Mail", "act")?;
+
+    let history = TaskHistory::new(path.clone())?;
+    let task = history.task(task_id)?.unwrap();
+    assert_eq!(task.status, TaskStatus::Failed);
+    assert!(task.error.is_some());
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_recent_tasks_are_newest_first_and_pruned() -> Result<()> {
+    let path = temp_db("recent");
+    let mut history = TaskHistory::new(path.clone())?;
+
+    let first = history.start_task("task 0", "ask")?;
+    for i in 1..205 {
+        history.start_task(&format!("task {}", i), "ask")?;
+    }
+
+    let recent = history.recent_tasks(3)?;
+    let commands: Vec<_> = recent.iter().map(|task| task.command.as_str()).collect();
+    assert_eq!(commands, vec!["task 204", "task 203", "task 202"]);
+
+    assert_eq!(history.recent_tasks(1000)?.len(), 200);
+    assert!(
+        history.task(first)?.is_none(),
+        "the oldest tasks are pruned"
+    );
+
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_screenshot_from_result() {
+    assert_eq!(
+        screenshot_from_result(&json!([
+            { "type": "text", "text": "Resumed" },
+            { "type": "image", "source": { "type": "base64", "data": STANDARD.encode([1, 2, 3]) } }
+        ])),
+        Some(vec![1, 2, 3])
+    );
+    assert_eq!(
+        screenshot_from_result(&json!([{ "type": "text", "text": "ok" }])),
+        None
+    );
+    assert_eq!(screenshot_from_result(&json!("not an array")), None);
+}
@@ -0,0 +1,228 @@
+use rdev::{Button, EventType, Key};
+use std::time::{Duration, Instant};
+use superctrl::automation::{injected_events, Action, MouseButton};
+use superctrl::input::{InjectedEvent, SyntheticInput, UserInputDetector, SYNTHETIC_WINDOW};
+use superctrl::task::{ControlSource, TaskRunner};
+
+#[test]
+fn test_injected_events_are_synthetic() {
+    let synthetic = SyntheticInput::new();
+    let injected_at = Instant::now();
+    synthetic.record_at(Some((500, 300)), injected_at);
+    synthetic.expect_at(
+        [InjectedEvent::Button(Button::Left), InjectedEvent::Key],
+        injected_at,
+    );
+
+    let shortly_after = injected_at + Duration::from_millis(20);
+    assert!(synthetic.is_synthetic(&EventType::MouseMove { x: 500.0, y: 300.0 }, shortly_after));
+    assert!(synthetic.is_synthetic(&EventType::ButtonPress(Button::Left), shortly_after));
+    assert!(synthetic.is_synthetic(&EventType::KeyPress(Key::KeyA), shortly_after));
+}
+
+#[test]
+fn test_real_events_are_not_synthetic() {
+    let synthetic = SyntheticInput::new();
+    let now = Instant::now();
+    assert!(!synthetic.is_synthetic(&EventType::KeyPress(Key::KeyA), now));
+
+    synthetic.record_at(Some((500, 300)), now);
+    synthetic.expect_at([InjectedEvent::Button(Button::Left)], now);
+
+    let moved_elsewhere = EventType::MouseMove { x: 820.0, y: 40.0 };
+    assert!(!synthetic.is_synthetic(&moved_elsewhere, now + Duration::from_millis(20)));
+    assert!(
+        !synthetic.is_synthetic(&EventType::KeyPress(Key::KeyA), now),
+        "a key press was not injected"
+    );
+    assert!(
+        !synthetic.is_synthetic(&EventType::ButtonPress(Button::Right), now),
+        "a right click was not injected"
+    );
+
+    let much_later = now + Duration::from_secs(2);
+    assert!(
+        !synthetic.is_synthetic(&EventType::ButtonPress(Button::Left), much_later),
+        "expected events expire"
+    );
+}
+
+#[test]
+fn test_each_injected_event_is_matched_once() {
+    let synthetic = SyntheticInput::new();
+    let now = Instant::now();
+    synthetic.expect_at(
+        injected_events(&Action::Click {
+            x: 10,
+            y: 10,
+            button: MouseButton::Left,
+        }),
+        now,
+    );
+
+    let click = EventType::ButtonPress(Button::Left);
+    assert!(synthetic.is_synthetic(&click, now));
+    assert!(
+        !synthetic.is_synthetic(&click, now),
+        "a second click is the user's"
+    );
+}
+
+#[test]
+fn test_typing_keeps_last_pointer_position() {
+    let synthetic = SyntheticInput::new();
+    let now = Instant::now();
+    synthetic.record_at(Some((10, 10)), now);
+    synthetic.record_at(None, now + Duration::from_millis(10));
+
+    let at = now + Duration::from_millis(30);
+    assert!(synthetic.is_synthetic(&EventType::MouseMove { x: 10.0, y: 10.0 }, at));
+    assert!(!synthetic.is_synthetic(&EventType::MouseMove { x: 300.0, y: 10.0 }, at));
+}
+
+#[test]
+fn test_injected_events_bound_what_each_action_posts() {
+    let typed = |text: &str| {
+        injected_events(&Action::Type {
+            text: text.to_string(),
+        })
+        .len()
+    };
+    assert_eq!(typed("hello world"), 11);
+    assert_eq!(typed("\nnext line"), 10);
+    assert_eq!(typed("héllo"), 5);
+
+    let keys = Action::Keypress {
+        keys: vec!["cmd".to_string(), "shift".to_string(), "t".to_string()],
+    };
+    assert_eq!(injected_events(&keys), vec![InjectedEvent::Key; 3]);
+
+    let scroll = Action::Scroll {
+        x: 0,
+        y: 0,
+        scroll_x: 0,
+        scroll_y: -3,
+    };
+    assert_eq!(injected_events(&scroll), vec![InjectedEvent::Wheel]);
+    assert!(
+        injected_events(&Action::MouseMove { x: 5, y: 5 }).is_empty(),
+        "a move injects no button press"
+    );
+}
+
+#[test]
+fn test_user_key_during_agent_typing_pauses() {
+    let synthetic = SyntheticInput::new();
+    let tasks = TaskRunner::new();
+    let _task = tasks.begin();
+    let typing = Action::Type {
+        text: "sixteen chars!!!".to_string(),
+    };
+    let start = Instant::now();
+
+    for step in 0..3u64 {
+        let at = start + Duration::from_millis(step * 600);
+        synthetic.record_at(None, at);
+        synthetic.expect_at(injected_events(&typing), at);
+
+        let injected = EventType::KeyPress(Key::KeyA);
+        for offset in [2, 9] {
+            let at = at + Duration::from_millis(offset);
+            assert!(!UserInputDetector::should_pause(
+                &injected,
+                synthetic.is_synthetic(&injected, at),
+                &tasks
+            ));
+        }
+
+        let user_click = EventType::ButtonPress(Button::Left);
+        let during = at + Duration::from_millis(5);
+        assert!(
+            UserInputDetector::should_pause(
+                &user_click,
+                synthetic.is_synthetic(&user_click, during),
+                &tasks
+            ),
+            "the user's click while the agent types must pause it"
+        );
+
+        let sent = at + Duration::from_millis(20);
+        synthetic.record_at(None, sent);
+
+        let user_key = EventType::KeyPress(Key::KeyJ);
+        let between = sent + SYNTHETIC_WINDOW + Duration::from_millis(50);
+        assert!(
+            UserInputDetector::should_pause(
+                &user_key,
+                synthetic.is_synthetic(&user_key, between),
+                &tasks
+            ),
+            "the user's own key press between typing steps must pause the agent"
+        );
+    }
+}
+
+#[test]
+fn test_typing_never_absorbs_more_keys_than_it_sent() {
+    let synthetic = SyntheticInput::new();
+    let now = Instant::now();
+    synthetic.expect_at(
+        injected_events(&Action::Type {
+            text: "ok".to_string(),
+        }),
+        now,
+    );
+
+    let key = EventType::KeyPress(Key::KeyA);
+    assert!(synthetic.is_synthetic(&key, now));
+    assert!(synthetic.is_synthetic(&key, now));
+    assert!(
+        !synthetic.is_synthetic(&key, now),
+        "a third key press during two characters of typing is the user's"
+    );
+}
+
+#[test]
+fn test_real_input_pauses_running_task() {
+    let tasks = TaskRunner::new();
+    let real_move = EventType::MouseMove { x: 100.0, y: 100.0 };
+
+    assert!(
+        !UserInputDetector::should_pause(&real_move, false, &tasks),
+        "nothing to pause without a running task"
+    );
+
+    let task = tasks.begin();
+    assert!(UserInputDetector::should_pause(&real_move, false, &tasks));
+    assert!(!UserInputDetector::should_pause(
+        &EventType::KeyRelease(Key::KeyA),
+        false,
+        &tasks
+    ));
+
+    tasks.pause(ControlSource::UserInput);
+    assert!(!UserInputDetector::should_pause(&real_move, false, &tasks));
+
+    tasks.resume(ControlSource::Ipc);
+    assert!(
+        !UserInputDetector::should_pause(&real_move, false, &tasks),
+        "input right after resuming must not pause again"
+    );
+    assert!(!task.token().is_paused());
+}
+
+#[test]
+fn test_synthetic_input_does_not_pause() {
+    let synthetic = SyntheticInput::new();
+    let tasks = TaskRunner::new();
+    let _task = tasks.begin();
+
+    let now = Instant::now();
+    synthetic.record_at(Some((200, 200)), now);
+    let moved = EventType::MouseMove { x: 200.0, y: 200.0 };
+    assert!(!UserInputDetector::should_pause(
+        &moved,
+        synthetic.is_synthetic(&moved, now + Duration::from_millis(5)),
+        &tasks
+    ));
+}